@@ -0,0 +1,247 @@
+//! Generates the typed accessors of `src/lib.rs` from a Broadband Forum data
+//! model document, `dm/tr-181-excerpt-full.xml` unless `CWMP_DATAMODEL_XML`
+//! names another one, e.g. a local copy of `tr-181-2-15-0-cwmp-full.xml` or
+//! `tr-098-1-8-0-full.xml`.
+//!
+//! Every object becomes a struct named after its path without instance
+//! placeholders (`Device_WiFi_SSID` for `Device.WiFi.SSID.{i}.`), with a
+//! method per child object and parameter. Methods are named after the
+//! object or parameter, with a trailing `_` when that is a Rust keyword or
+//! clashes with `path`, `PARAMETERS` or `OBJECT`.
+#[allow(dead_code)]
+#[path = "src/model.rs"]
+mod model;
+
+use model::{Access, Object, Parameter, ValueType};
+use std::fmt::Write;
+
+const DEFAULT_XML: &str = "dm/tr-181-excerpt-full.xml";
+
+fn main() {
+    println!("cargo::rerun-if-changed=build.rs");
+    println!("cargo::rerun-if-changed=src/model.rs");
+    println!("cargo::rerun-if-env-changed=CWMP_DATAMODEL_XML");
+
+    let xml = std::env::var("CWMP_DATAMODEL_XML").unwrap_or_else(|_| DEFAULT_XML.to_string());
+    println!("cargo::rerun-if-changed={xml}");
+
+    let document = std::fs::read_to_string(&xml)
+        .unwrap_or_else(|e| panic!("failed to read the data model {xml}: {e}"));
+    let objects = model::parse(&document).unwrap_or_else(|e| panic!("{xml}: {e}"));
+    let code = generate(&xml, &objects);
+
+    let out_dir = std::env::var("OUT_DIR").expect("set by cargo");
+    std::fs::write(format!("{out_dir}/datamodel.rs"), code)
+        .expect("failed to write the data model");
+}
+
+// `Device.WiFi.SSID.{i}.` -> ["Device", "WiFi", "SSID", "{i}"]
+fn segments(name: &str) -> Vec<&str> {
+    name.trim_end_matches('.').split('.').collect()
+}
+
+fn ident(name: &str) -> String {
+    segments(name)
+        .into_iter()
+        .filter(|segment| *segment != "{i}")
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+// the enclosing object and the name of `name` in it
+fn split_parent(name: &str) -> Option<(String, &str)> {
+    let mut segments = segments(name);
+    if segments.last() == Some(&"{i}") {
+        segments.pop();
+    }
+    let last = segments.pop()?;
+    if segments.is_empty() {
+        return None;
+    }
+    Some((format!("{}.", segments.join(".")), last))
+}
+
+// strict and reserved keywords, `self` and friends can't be raw identifiers
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+// the items every generated struct has besides its accessors
+const RESERVED: &[&str] = &["path", "PARAMETERS", "OBJECT"];
+
+fn method(name: &str) -> String {
+    if KEYWORDS.contains(&name) || RESERVED.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
+
+fn rust_type(parameter: &Parameter) -> &'static str {
+    if parameter.syntax.list {
+        return "String";
+    }
+    match parameter.syntax.type_ {
+        ValueType::String | ValueType::Base64 | ValueType::HexBinary => "String",
+        ValueType::Boolean => "bool",
+        ValueType::Int => "i32",
+        ValueType::UnsignedInt => "u32",
+        ValueType::Long => "i64",
+        ValueType::UnsignedLong => "u64",
+        ValueType::DateTime => "::cwmp_typed::cwmp::datetime::CwmpDateTime",
+    }
+}
+
+fn access(access: Access) -> &'static str {
+    match access {
+        Access::ReadOnly => "crate::model::Access::ReadOnly",
+        Access::ReadWrite => "crate::model::Access::ReadWrite",
+    }
+}
+
+fn option<T: std::fmt::Display>(value: Option<T>) -> String {
+    match value {
+        Some(value) => format!("Some({value})"),
+        None => "None".to_string(),
+    }
+}
+
+fn parameter_info(code: &mut String, parameter: &Parameter) {
+    let syntax = &parameter.syntax;
+    let ranges: Vec<String> = syntax
+        .ranges
+        .iter()
+        .map(|range| {
+            format!(
+                "crate::model::Range {{ min: {}, max: {} }}",
+                option(range.min),
+                option(range.max)
+            )
+        })
+        .collect();
+    let enumeration: Vec<String> = syntax
+        .enumeration
+        .iter()
+        .map(|value| format!("{value:?}"))
+        .collect();
+    writeln!(
+        code,
+        "        crate::ParameterInfo {{ name: {:?}, access: {}, type_: crate::model::ValueType::{:?}, list: {}, ranges: &[{}], enumeration: &[{}], max_length: {}, data_type: {} }},",
+        parameter.name,
+        access(parameter.access),
+        syntax.type_,
+        syntax.list,
+        ranges.join(", "),
+        enumeration.join(", "),
+        option(syntax.max_length),
+        option(syntax.data_type.as_ref().map(|name| format!("{name:?}"))),
+    )
+    .unwrap();
+}
+
+fn object(code: &mut String, object: &Object, children: &[&Object]) {
+    let name = ident(&object.name);
+    writeln!(code, "/// `{}`", object.name).unwrap();
+    writeln!(code, "#[derive(Debug, Clone, PartialEq, Eq)]").unwrap();
+    writeln!(code, "pub struct {name} {{").unwrap();
+    writeln!(code, "    path: ::cwmp_typed::cwmp::path::ParameterPath,").unwrap();
+    writeln!(code, "}}\n").unwrap();
+
+    writeln!(code, "impl {name} {{").unwrap();
+    writeln!(
+        code,
+        "    pub const PARAMETERS: &'static [crate::ParameterInfo] = &["
+    )
+    .unwrap();
+    for parameter in &object.parameters {
+        parameter_info(code, parameter);
+    }
+    writeln!(code, "    ];").unwrap();
+    writeln!(
+        code,
+        "    pub const OBJECT: crate::ObjectInfo = crate::ObjectInfo {{ path: {:?}, access: {}, min_entries: {}, max_entries: {}, parameters: Self::PARAMETERS }};\n",
+        object.name,
+        access(object.access),
+        object.min_entries,
+        option(object.max_entries),
+    )
+    .unwrap();
+
+    writeln!(
+        code,
+        "    pub fn path(&self) -> &::cwmp_typed::cwmp::path::ParameterPath {{\n        &self.path\n    }}"
+    )
+    .unwrap();
+
+    for child in children {
+        let (_, segment) = split_parent(&child.name).expect("children have a parent");
+        let child_name = ident(&child.name);
+        let method = method(segment);
+        if child.is_multi_instance() {
+            writeln!(
+                code,
+                "\n    /// `{}`, fails for the instance number 0.\n    pub fn {method}(&self, i: u32) -> Result<{child_name}, ::cwmp_typed::error::Error> {{\n        Ok({child_name} {{ path: self.path.join(&format!(\"{segment}.{{i}}.\"))? }})\n    }}",
+                child.name
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                code,
+                "\n    pub fn {method}(&self) -> {child_name} {{\n        {child_name} {{ path: self.path.join(\"{segment}.\").expect(\"valid object name\") }}\n    }}"
+            )
+            .unwrap();
+        }
+    }
+
+    for (index, parameter) in object.parameters.iter().enumerate() {
+        writeln!(
+            code,
+            "\n    pub fn {}(&self) -> crate::Parameter<{}> {{\n        crate::Parameter::new(self.path.join({:?}).expect(\"valid parameter name\"), &Self::PARAMETERS[{index}])\n    }}",
+            method(&parameter.name),
+            rust_type(parameter),
+            parameter.name,
+        )
+        .unwrap();
+    }
+    writeln!(code, "}}\n").unwrap();
+}
+
+fn generate(xml: &str, objects: &[Object]) -> String {
+    let mut code = format!("// Generated by build.rs from {xml}, do not edit.\n\n");
+
+    for root in objects.iter().filter(|o| split_parent(&o.name).is_none()) {
+        let name = ident(&root.name);
+        writeln!(
+            code,
+            "/// `{}`\npub fn {}() -> {name} {{\n    {name} {{ path: {:?}.parse().expect(\"valid object name\") }}\n}}\n",
+            root.name,
+            method(&name),
+            root.name
+        )
+        .unwrap();
+    }
+
+    for parent in objects {
+        let children: Vec<&Object> = objects
+            .iter()
+            .filter(|o| split_parent(&o.name).is_some_and(|(name, _)| name == parent.name))
+            .collect();
+        object(&mut code, parent, &children);
+    }
+
+    writeln!(
+        code,
+        "/// Every object of the data model, in document order."
+    )
+    .unwrap();
+    writeln!(code, "pub const OBJECTS: &[crate::ObjectInfo] = &[").unwrap();
+    for object in objects {
+        writeln!(code, "    {}::OBJECT,", ident(&object.name)).unwrap();
+    }
+    writeln!(code, "];").unwrap();
+    code
+}
@@ -0,0 +1,193 @@
+//! A data model loaded at runtime, from a `cwmp-datamodel` document or a
+//! serialized export, to look parameter paths up in.
+use crate::dm;
+use crate::model::{self, Object, ParseError, Syntax, ValueType};
+use crate::{ObjectInfo, ParameterInfo};
+use cwmp_typed::cwmp::path::{ParameterPath, Segment};
+use cwmp_typed::cwmp::value::{ParameterType, TypeLookup, XsdType};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct DataModel {
+    objects: Vec<Object>,
+    // object name templates to their index in `objects`
+    index: HashMap<String, usize>,
+}
+
+// `Device.WiFi.SSID.1.` and `Device.WiFi.SSID.[home].` -> `Device.WiFi.SSID.{i}.`
+fn template(path: &ParameterPath) -> String {
+    let mut template = String::new();
+    for segment in path.segments() {
+        match segment {
+            Segment::Name(name) => template.push_str(name),
+            _ => template.push_str("{i}"),
+        }
+        template.push('.');
+    }
+    template
+}
+
+impl DataModel {
+    pub fn new(objects: Vec<Object>) -> Self {
+        let index = objects
+            .iter()
+            .enumerate()
+            .map(|(index, object)| (object.name.clone(), index))
+            .collect();
+        Self { objects, index }
+    }
+
+    pub fn from_xml(xml: &str) -> Result<Self, ParseError> {
+        Ok(Self::new(model::parse(xml)?))
+    }
+
+    /// The data model the typed accessors were generated from.
+    pub fn generated() -> Self {
+        Self::new(dm::OBJECTS.iter().map(Object::from).collect())
+    }
+
+    pub fn objects(&self) -> &[Object] {
+        &self.objects
+    }
+
+    /// The object of an object path, `Device.WiFi.` or an instance
+    /// `Device.WiFi.SSID.1.`.
+    pub fn object(&self, path: &ParameterPath) -> Option<&Object> {
+        if !path.is_object() {
+            return None;
+        }
+        let index = self.index.get(&template(path))?;
+        Some(&self.objects[*index])
+    }
+
+    /// The multi-instance object of a table path, `Device.WiFi.SSID.`.
+    pub fn table(&self, path: &ParameterPath) -> Option<&Object> {
+        if !path.is_object() || path.name().is_none() {
+            return None;
+        }
+        let index = self.index.get(&format!("{}{{i}}.", template(path)))?;
+        Some(&self.objects[*index])
+    }
+
+    pub fn parameter(&self, path: &ParameterPath) -> Option<&model::Parameter> {
+        let name = path.name().filter(|_| !path.is_object())?;
+        self.object(&path.parent()?)?
+            .parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+    }
+}
+
+impl TypeLookup for DataModel {
+    fn parameter_type(&self, path: &ParameterPath) -> Option<ParameterType> {
+        let syntax = &self.parameter(path)?.syntax;
+        Some(ParameterType {
+            base: syntax.type_.into(),
+            list: syntax.list,
+            data_type: syntax.data_type.clone(),
+        })
+    }
+}
+
+impl From<ValueType> for XsdType {
+    fn from(type_: ValueType) -> Self {
+        match type_ {
+            ValueType::String => Self::String,
+            ValueType::Boolean => Self::Boolean,
+            ValueType::Int => Self::Int,
+            ValueType::UnsignedInt => Self::UnsignedInt,
+            ValueType::Long => Self::Long,
+            ValueType::UnsignedLong => Self::UnsignedLong,
+            ValueType::DateTime => Self::DateTime,
+            ValueType::Base64 => Self::Base64,
+            ValueType::HexBinary => Self::HexBinary,
+        }
+    }
+}
+
+impl From<&ParameterInfo> for model::Parameter {
+    fn from(info: &ParameterInfo) -> Self {
+        Self {
+            name: info.name.to_string(),
+            access: info.access,
+            syntax: Syntax {
+                type_: info.type_,
+                list: info.list,
+                ranges: info.ranges.to_vec(),
+                enumeration: info.enumeration.iter().map(|v| v.to_string()).collect(),
+                max_length: info.max_length,
+                data_type: info.data_type.map(str::to_string),
+            },
+        }
+    }
+}
+
+impl From<&ObjectInfo> for Object {
+    fn from(info: &ObjectInfo) -> Self {
+        Self {
+            name: info.path.to_string(),
+            access: info.access,
+            min_entries: info.min_entries,
+            max_entries: info.max_entries,
+            parameters: info.parameters.iter().map(model::Parameter::from).collect(),
+        }
+    }
+}
+
+/// A list of objects, as [`Object`] serializes.
+#[cfg(feature = "serde")]
+impl serde::Serialize for DataModel {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.objects.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for DataModel {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Object>::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let model = DataModel::from_xml(include_str!("../dm/tr-181-excerpt-full.xml")).unwrap();
+        let path = |path: &str| path.parse::<ParameterPath>().unwrap();
+
+        let ssid = model.object(&path("Device.WiFi.SSID.[home].")).unwrap();
+        assert_eq!(ssid.name, "Device.WiFi.SSID.{i}.");
+        assert_eq!(model.table(&path("Device.WiFi.SSID.")), Some(ssid));
+        assert!(model.object(&path("Device.WiFi.SSID.")).is_none());
+        assert!(model.table(&path("Device.WiFi.")).is_none());
+
+        let parameter = model.parameter(&path("Device.WiFi.SSID.1.SSID")).unwrap();
+        assert_eq!(parameter.syntax.max_length, Some(32));
+        assert!(model.parameter(&path("Device.WiFi.SSID.1.")).is_none());
+        assert!(model.parameter(&path("Device.WiFi.SSID.SSID")).is_none());
+
+        assert_eq!(model.objects(), DataModel::generated().objects());
+
+        let mac = model
+            .parameter_type(&path("Device.WiFi.SSID.1.MACAddress"))
+            .unwrap();
+        assert_eq!(mac.data_type.as_deref(), Some("MACAddress"));
+        let sent = model
+            .parameter_type(&path("Device.WiFi.SSID.1.Stats.BytesSent"))
+            .unwrap();
+        assert_eq!(sent.base, XsdType::UnsignedLong);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_export() {
+        let model = DataModel::generated();
+        let json = serde_json::to_string(&model).unwrap();
+        assert!(json.contains(r#""access":"readWrite""#));
+        let parsed: DataModel = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.objects(), model.objects());
+    }
+}
@@ -0,0 +1,220 @@
+//! Typed parameter paths for a Broadband Forum data model, generated at build
+//! time from its `cwmp-datamodel` XML, refer `build.rs`.
+//!
+//! ```ignore
+//! use cwmp_datamodel::dm;
+//!
+//! let ssid = dm::Device().WiFi().SSID(1)?.SSID();
+//! assert_eq!(ssid.path().to_string(), "Device.WiFi.SSID.1.SSID");
+//! let value = ssid.set("home".to_string()); // a ParameterValueStruct, xsd:string
+//! ```
+use cwmp_typed::cwmp::datetime::CwmpDateTime;
+use cwmp_typed::cwmp::path::ParameterPath;
+use cwmp_typed::cwmp::types::{ParameterValueStruct, ParameterValueStructValue};
+use cwmp_typed::cwmp::value::ParameterType;
+use model::{Access, Range, ValueType};
+use std::marker::PhantomData;
+
+mod data_model;
+pub mod model;
+pub mod validate;
+
+pub use data_model::DataModel;
+
+#[allow(non_snake_case, non_camel_case_types)]
+pub mod dm {
+    include!(concat!(env!("OUT_DIR"), "/datamodel.rs"));
+}
+
+/// A data model object, `Device.WiFi.SSID.{i}.`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectInfo {
+    pub path: &'static str,
+    /// `ReadWrite` when the ACS can add and delete instances.
+    pub access: Access,
+    pub min_entries: u32,
+    /// `None` for `unbounded`.
+    pub max_entries: Option<u32>,
+    pub parameters: &'static [ParameterInfo],
+}
+
+impl ObjectInfo {
+    pub fn is_multi_instance(&self) -> bool {
+        self.path.ends_with(".{i}.")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParameterInfo {
+    pub name: &'static str,
+    pub access: Access,
+    pub type_: ValueType,
+    /// A comma separated list of `type_` values.
+    pub list: bool,
+    pub ranges: &'static [Range],
+    pub enumeration: &'static [&'static str],
+    pub max_length: Option<u32>,
+    /// The named data type, `MACAddress`.
+    pub data_type: Option<&'static str>,
+}
+
+impl From<&ParameterInfo> for ParameterType {
+    fn from(info: &ParameterInfo) -> Self {
+        Self {
+            base: info.type_.into(),
+            list: info.list,
+            data_type: info.data_type.map(str::to_string),
+        }
+    }
+}
+
+/// Rust types of the data model primitives. Lists, `base64` and `hexBinary`
+/// values are kept as strings.
+pub trait ParameterValue {
+    fn to_value(&self) -> String;
+}
+
+macro_rules! impl_parameter_value {
+    ($($ty: ty),*) => {
+        $(
+            impl ParameterValue for $ty {
+                fn to_value(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+impl_parameter_value!(String, bool, i32, u32, i64, u64, CwmpDateTime);
+
+/// A parameter of a data model object, typed by its syntax.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameter<T> {
+    path: ParameterPath,
+    info: &'static ParameterInfo,
+    value: PhantomData<fn(T)>,
+}
+
+impl<T: ParameterValue> Parameter<T> {
+    pub(crate) fn new(path: ParameterPath, info: &'static ParameterInfo) -> Self {
+        Self {
+            path,
+            info,
+            value: PhantomData,
+        }
+    }
+
+    pub fn path(&self) -> &ParameterPath {
+        &self.path
+    }
+
+    pub fn info(&self) -> &'static ParameterInfo {
+        self.info
+    }
+
+    /// The `SetParameterValues` entry for `value`, with its data model
+    /// `xsi:type`.
+    pub fn set(&self, value: T) -> ParameterValueStruct {
+        ParameterValueStruct {
+            name: self.path.to_string(),
+            value: ParameterValueStructValue {
+                type_: Some(ParameterType::from(self.info).xsi_type()),
+                value: value.to_value(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn typed_paths() {
+        let ssid = dm::Device().WiFi().SSID(1).unwrap().SSID();
+        assert_eq!(ssid.path().to_string(), "Device.WiFi.SSID.1.SSID");
+        assert_eq!(ssid.info().access, Access::ReadWrite);
+        assert_eq!(ssid.info().max_length, Some(32));
+
+        let enable = dm::Device().WiFi().SSID(2).unwrap().Enable().set(true);
+        assert_eq!(enable.name, "Device.WiFi.SSID.2.Enable");
+        assert_eq!(enable.value.value, "true");
+        assert_eq!(
+            enable.value.type_.map(|type_| type_.to_string()).as_deref(),
+            Some("xsd:boolean")
+        );
+
+        let strength = dm::Device()
+            .WiFi()
+            .AccessPoint(1)
+            .unwrap()
+            .AssociatedDevice(3)
+            .unwrap()
+            .SignalStrength();
+        assert_eq!(
+            strength.path().to_string(),
+            "Device.WiFi.AccessPoint.1.AssociatedDevice.3.SignalStrength"
+        );
+        assert_eq!(
+            strength.info().ranges,
+            &[Range {
+                min: Some(-200),
+                max: Some(0)
+            }]
+        );
+        let _: Parameter<u64> = dm::Device().WiFi().SSID(1).unwrap().Stats().BytesSent();
+
+        // instance numbers start at 1
+        assert!(dm::Device().WiFi().SSID(0).is_err());
+    }
+
+    #[test]
+    fn objects() {
+        let ssid = dm::Device_WiFi_SSID::OBJECT;
+        assert!(ssid.is_multi_instance());
+        assert_eq!(ssid.max_entries, None);
+        assert!(!dm::Device_WiFi::OBJECT.is_multi_instance());
+        assert_eq!(dm::OBJECTS.len(), 9);
+
+        let status = &ssid.parameters[1];
+        assert_eq!(status.name, "Status");
+        assert_eq!(status.access, Access::ReadOnly);
+        assert!(status.enumeration.contains(&"LowerLayerDown"));
+        // imported TR-106 types take their facets from TR-106
+        assert_eq!(ssid.parameters[7].type_, ValueType::String);
+        assert_eq!(ssid.parameters[7].max_length, Some(17));
+        let lower_layers = &ssid.parameters[5];
+        assert!(lower_layers.list);
+        assert_eq!(lower_layers.max_length, None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(
+            model::parse("<document><model><object access=\"readOnly\"/></model></document>")
+                .is_err()
+        );
+        assert!(
+            model::parse(
+                "<document><model><object name=\"Device.\" maxEntries=\"many\"/></model></document>"
+            )
+            .is_err()
+        );
+        assert!(
+            model::parse(
+                "<document><model><object name=\"Device.\"><parameter name=\"Up\"><syntax>\
+                 <dataType ref=\"Undefined\"/></syntax></parameter></object></model></document>"
+            )
+            .is_err()
+        );
+
+        // a component parameter before any object is skipped
+        let objects = model::parse(
+            "<document><component name=\"C\"><parameter name=\"P\"><syntax><boolean/>\
+             </syntax></parameter></component><model><object name=\"Device.\"/></model></document>",
+        )
+        .unwrap();
+        assert!(objects[0].parameters.is_empty());
+    }
+}
@@ -0,0 +1,450 @@
+//! Broadband Forum data model XML (`cwmp-datamodel`, TR-106 Annex A) reader.
+//! Only the fully expanded documents are understood, the `*-full.xml` ones
+//! with every object listed under `<model>`. Components and profiles are
+//! skipped.
+//!
+//! Also compiled into `build.rs`, so it only depends on quick-xml.
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Access {
+    ReadOnly,
+    ReadWrite,
+}
+
+/// The TR-106 primitive types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum ValueType {
+    String,
+    Boolean,
+    Int,
+    UnsignedInt,
+    Long,
+    UnsignedLong,
+    DateTime,
+    Base64,
+    HexBinary,
+}
+
+impl ValueType {
+    fn from_element(name: &[u8]) -> Option<Self> {
+        Some(match name {
+            b"string" => Self::String,
+            b"boolean" => Self::Boolean,
+            b"int" => Self::Int,
+            b"unsignedInt" => Self::UnsignedInt,
+            b"long" => Self::Long,
+            b"unsignedLong" => Self::UnsignedLong,
+            b"dateTime" => Self::DateTime,
+            b"base64" => Self::Base64,
+            b"hexBinary" => Self::HexBinary,
+            _ => return None,
+        })
+    }
+
+    /// The `xsi:type` local name.
+    pub fn xsd_type(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Boolean => "boolean",
+            Self::Int => "int",
+            Self::UnsignedInt => "unsignedInt",
+            Self::Long => "long",
+            Self::UnsignedLong => "unsignedLong",
+            Self::DateTime => "dateTime",
+            Self::Base64 => "base64",
+            Self::HexBinary => "hexBinary",
+        }
+    }
+}
+
+/// An inclusive range, either bound may be open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Range {
+    pub min: Option<i128>,
+    pub max: Option<i128>,
+}
+
+impl Range {
+    pub fn contains(&self, value: i128) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+/// The TR-106 notation, `[1:65535]` or `[1:]`.
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[")?;
+        if let Some(min) = self.min {
+            write!(f, "{min}")?;
+        }
+        f.write_str(":")?;
+        if let Some(max) = self.max {
+            write!(f, "{max}")?;
+        }
+        f.write_str("]")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Syntax {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub type_: ValueType,
+    /// A comma separated list of `type_` values.
+    pub list: bool,
+    pub ranges: Vec<Range>,
+    pub enumeration: Vec<String>,
+    pub max_length: Option<u32>,
+    /// The named data type the syntax refers to, `MACAddress`. The facets
+    /// above include those of the data type.
+    pub data_type: Option<String>,
+}
+
+impl Default for Syntax {
+    fn default() -> Self {
+        Self {
+            type_: ValueType::String,
+            list: false,
+            ranges: Vec::new(),
+            enumeration: Vec::new(),
+            max_length: None,
+            data_type: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Parameter {
+    pub name: String,
+    pub access: Access,
+    pub syntax: Syntax,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Object {
+    /// The path with `{i}` placeholders, `Device.WiFi.SSID.{i}.`.
+    pub name: String,
+    /// `ReadWrite` when the ACS can add and delete instances.
+    pub access: Access,
+    pub min_entries: u32,
+    /// `None` for `unbounded`.
+    pub max_entries: Option<u32>,
+    pub parameters: Vec<Parameter>,
+}
+
+impl Object {
+    pub fn is_multi_instance(&self) -> bool {
+        self.name.ends_with(".{i}.")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError(pub String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid data model document: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn attribute(element: &BytesStart<'_>, name: &str) -> Result<Option<String>, ParseError> {
+    let Some(attribute) = element
+        .try_get_attribute(name)
+        .map_err(|e| ParseError(e.to_string()))?
+    else {
+        return Ok(None);
+    };
+    let value = attribute
+        .unescape_value()
+        .map_err(|e| ParseError(e.to_string()))?;
+    Ok(Some(value.into_owned()))
+}
+
+fn required(element: &BytesStart<'_>, name: &str) -> Result<String, ParseError> {
+    attribute(element, name)?.ok_or_else(|| {
+        ParseError(format!(
+            "<{}> without '{name}'",
+            String::from_utf8_lossy(element.local_name().as_ref())
+        ))
+    })
+}
+
+fn access(element: &BytesStart<'_>) -> Result<Access, ParseError> {
+    match attribute(element, "access")?.as_deref() {
+        None | Some("readOnly") => Ok(Access::ReadOnly),
+        // a write-once parameter is writable until it has been set
+        Some("readWrite" | "writeOnceReadOnly") => Ok(Access::ReadWrite),
+        Some(other) => Err(ParseError(format!("unknown access '{other}'"))),
+    }
+}
+
+fn number<T: std::str::FromStr>(value: &str) -> Result<T, ParseError> {
+    value
+        .parse()
+        .map_err(|_| ParseError(format!("'{value}' is not a number")))
+}
+
+// facets and primitive types inside `<syntax>` or a named `<dataType>`
+fn syntax_element(
+    syntax: &mut Syntax,
+    element: &BytesStart<'_>,
+    parent: &[u8],
+) -> Result<(), ParseError> {
+    let name = element.local_name();
+    if let Some(type_) = ValueType::from_element(name.as_ref()) {
+        syntax.type_ = type_;
+        syntax.data_type = None;
+        return Ok(());
+    }
+    match name.as_ref() {
+        b"list" => syntax.list = true,
+        b"dataType" => syntax.data_type = Some(required(element, "ref")?),
+        b"range" => syntax.ranges.push(Range {
+            min: attribute(element, "minInclusive")?
+                .map(|v| number(&v))
+                .transpose()?,
+            max: attribute(element, "maxInclusive")?
+                .map(|v| number(&v))
+                .transpose()?,
+        }),
+        b"enumeration" => syntax.enumeration.push(required(element, "value")?),
+        // a size under `<list>` limits the whole list string, not the items
+        b"size" if parent != b"list" => {
+            if let Some(max_length) = attribute(element, "maxLength")? {
+                syntax.max_length = Some(number(&max_length)?);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct DataType {
+    base: Option<String>,
+    syntax: Syntax,
+}
+
+// The TR-106 data types the data model documents import, `tr-106-types.xml`,
+// by their primitive type and facets.
+fn tr106_type(name: &str) -> Option<Syntax> {
+    let (type_, max_length, min) = match name {
+        "Alias" => (ValueType::String, Some(64), None),
+        "DiagnosticsState" => (ValueType::String, None, None),
+        "Dbm1000" => (ValueType::Int, None, None),
+        "IEEE_EUI64" => (ValueType::String, Some(23), None),
+        "IPAddress" | "IPv6Address" => (ValueType::String, Some(45), None),
+        "IPv4Address" => (ValueType::String, Some(15), None),
+        "IPPrefix" | "IPv6Prefix" => (ValueType::String, Some(49), None),
+        "IPv4Prefix" => (ValueType::String, Some(18), None),
+        "MACAddress" => (ValueType::String, Some(17), None),
+        "Order" => (ValueType::UnsignedInt, None, Some(1)),
+        "StatsCounter32" => (ValueType::UnsignedInt, None, None),
+        "StatsCounter64" => (ValueType::UnsignedLong, None, None),
+        "URI" | "URL" => (ValueType::String, Some(2048), None),
+        "UUID" => (ValueType::String, Some(36), None),
+        "ZigBeeNetworkAddress" => (ValueType::String, Some(4), None),
+        _ => return None,
+    };
+    Some(Syntax {
+        type_,
+        ranges: min
+            .map(|min| {
+                vec![Range {
+                    min: Some(min),
+                    max: None,
+                }]
+            })
+            .unwrap_or_default(),
+        max_length,
+        ..Syntax::default()
+    })
+}
+
+// facets on the parameter narrow the data type
+fn narrow(syntax: &Syntax, base: Syntax, reference: &str) -> Syntax {
+    Syntax {
+        type_: base.type_,
+        list: syntax.list || base.list,
+        ranges: if syntax.ranges.is_empty() {
+            base.ranges
+        } else {
+            syntax.ranges.clone()
+        },
+        enumeration: if syntax.enumeration.is_empty() {
+            base.enumeration
+        } else {
+            syntax.enumeration.clone()
+        },
+        max_length: syntax.max_length.or(base.max_length),
+        data_type: Some(reference.to_string()),
+    }
+}
+
+fn resolve(
+    syntax: &Syntax,
+    data_types: &HashMap<String, DataType>,
+    depth: usize,
+) -> Result<Syntax, ParseError> {
+    let Some(reference) = &syntax.data_type else {
+        return Ok(syntax.clone());
+    };
+    if depth > 16 {
+        return Err(ParseError(format!("data type '{reference}' is recursive")));
+    }
+    let Some(data_type) = data_types.get(reference) else {
+        let base = tr106_type(reference).ok_or_else(|| {
+            ParseError(format!(
+                "data type '{reference}' is neither defined in the document nor a TR-106 type"
+            ))
+        })?;
+        return Ok(narrow(syntax, base, reference));
+    };
+
+    let mut base = data_type.syntax.clone();
+    if let Some(parent) = &data_type.base {
+        base.data_type = Some(parent.clone());
+    }
+    let base = resolve(&base, data_types, depth + 1)?;
+    let base = Syntax {
+        ranges: if data_type.syntax.ranges.is_empty() {
+            base.ranges
+        } else {
+            data_type.syntax.ranges.clone()
+        },
+        enumeration: if data_type.syntax.enumeration.is_empty() {
+            base.enumeration
+        } else {
+            data_type.syntax.enumeration.clone()
+        },
+        max_length: data_type.syntax.max_length.or(base.max_length),
+        ..base
+    };
+    Ok(narrow(syntax, base, reference))
+}
+
+/// Reads the objects of every `<model>` in the document, in document order.
+pub fn parse(xml: &str) -> Result<Vec<Object>, ParseError> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut stack: Vec<Vec<u8>> = Vec::new();
+    let mut data_types: HashMap<String, DataType> = HashMap::new();
+    let mut data_type: Option<(String, DataType)> = None;
+    let mut objects: Vec<Object> = Vec::new();
+    // only parameters of model objects are read, not those of components
+    let mut in_parameter = false;
+    let mut in_syntax = false;
+
+    loop {
+        let (element, empty) = match reader.read_event().map_err(|e| ParseError(e.to_string()))? {
+            Event::Start(element) => (element, false),
+            Event::Empty(element) => (element, true),
+            Event::End(end) => {
+                let name = stack.pop();
+                match end.local_name().as_ref() {
+                    b"parameter" => in_parameter = false,
+                    b"syntax" => in_syntax = false,
+                    b"dataType" if name.as_deref() == Some(b"dataType") && stack.len() == 1 => {
+                        if let Some((name, data_type)) = data_type.take() {
+                            data_types.insert(name, data_type);
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        let name = element.local_name().as_ref().to_vec();
+        let parent = stack.last().map(Vec::as_slice).unwrap_or_default();
+
+        match (name.as_slice(), parent) {
+            (b"dataType", b"document") => {
+                let definition = DataType {
+                    base: attribute(&element, "base")?,
+                    ..DataType::default()
+                };
+                data_type = Some((required(&element, "name")?, definition));
+                if empty && let Some((name, data_type)) = data_type.take() {
+                    data_types.insert(name, data_type);
+                }
+            }
+            (b"object", b"model") => {
+                let max_entries = match attribute(&element, "maxEntries")?.as_deref() {
+                    None => Some(1),
+                    Some("unbounded") => None,
+                    Some(value) => Some(number(value)?),
+                };
+                let Some(name) = attribute(&element, "name")? else {
+                    return Err(ParseError(
+                        "objects extending a component, only *-full.xml documents are supported"
+                            .to_string(),
+                    ));
+                };
+                objects.push(Object {
+                    name,
+                    access: access(&element)?,
+                    min_entries: attribute(&element, "minEntries")?
+                        .map(|v| number(&v))
+                        .transpose()?
+                        .unwrap_or(1),
+                    max_entries,
+                    parameters: Vec::new(),
+                });
+            }
+            (b"parameter", b"object") if stack.len() >= 2 && stack[stack.len() - 2] == b"model" => {
+                if let Some(object) = objects.last_mut() {
+                    object.parameters.push(Parameter {
+                        name: required(&element, "name")?,
+                        access: access(&element)?,
+                        syntax: Syntax::default(),
+                    });
+                    in_parameter = !empty;
+                }
+            }
+            (b"syntax", b"parameter") => in_syntax = in_parameter,
+            (_, _) if in_syntax => {
+                if let Some(parameter) = objects
+                    .last_mut()
+                    .and_then(|object| object.parameters.last_mut())
+                {
+                    syntax_element(&mut parameter.syntax, &element, parent)?;
+                }
+            }
+            (_, _) if name != b"description" => {
+                if let Some((_, definition)) = data_type.as_mut() {
+                    syntax_element(&mut definition.syntax, &element, parent)?;
+                }
+            }
+            _ => {}
+        }
+
+        if !empty {
+            stack.push(name);
+        }
+    }
+
+    for object in objects.iter_mut() {
+        for parameter in object.parameters.iter_mut() {
+            parameter.syntax = resolve(&parameter.syntax, &data_types, 0)?;
+        }
+    }
+    Ok(objects)
+}
@@ -0,0 +1,309 @@
+//! Checks of outgoing rpcs against a [`DataModel`], reported with the fault
+//! codes a CPE would answer them with.
+use crate::DataModel;
+use crate::model::{Access, Syntax, ValueType};
+use cwmp_typed::cwmp::fault::FaultCode;
+use cwmp_typed::cwmp::path::{ParameterPath, Segment};
+use cwmp_typed::cwmp::rpc::{Rpc, RpcMethod};
+use cwmp_typed::cwmp::types::{AddObject, ParameterValueStruct, ParameterValueType};
+use cwmp_typed::cwmp::value::{ParameterType, TypedValue};
+use cwmp_typed::error::Error;
+
+/// A rejected parameter or object name, like a SetParameterValuesFault.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{name}: {message} ({code})")]
+pub struct ParameterFault {
+    pub name: String,
+    pub code: FaultCode,
+    pub message: String,
+}
+
+impl ParameterFault {
+    fn new<M: Into<String>>(name: &str, code: FaultCode, message: M) -> Self {
+        Self {
+            name: name.to_string(),
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Checks the parameters of a SetParameterValues and the objects of an
+/// AddObject or DeleteObject against the data model, collecting a fault per
+/// rejected name. Other rpcs are not checked.
+pub fn validate_rpc(model: &DataModel, rpc: &Rpc) -> Result<(), Vec<ParameterFault>> {
+    let faults: Vec<ParameterFault> = match rpc.method() {
+        RpcMethod::SetParameterValues(inner) => inner
+            .parameter_list()
+            .0
+            .iter()
+            .filter_map(|parameter| set_parameter_value(model, parameter).err())
+            .collect(),
+        RpcMethod::AddObject(inner) => add_object(model, inner).err().into_iter().collect(),
+        RpcMethod::DeleteObject(inner) => delete_object(model, inner).err().into_iter().collect(),
+        _ => Vec::new(),
+    };
+    if faults.is_empty() {
+        Ok(())
+    } else {
+        Err(faults)
+    }
+}
+
+fn set_parameter_value(
+    model: &DataModel,
+    parameter: &ParameterValueStruct,
+) -> Result<(), ParameterFault> {
+    let name = &parameter.name;
+    let unknown = || {
+        ParameterFault::new(
+            name,
+            FaultCode::InvalidParameterName,
+            "not a parameter of the data model",
+        )
+    };
+    let path: ParameterPath = name.parse().map_err(|_| unknown())?;
+    let definition = model.parameter(&path).ok_or_else(unknown)?;
+
+    if definition.access == Access::ReadOnly {
+        return Err(ParameterFault::new(
+            name,
+            FaultCode::NonWritableParameter,
+            "the parameter is read-only",
+        ));
+    }
+
+    let expected = definition.syntax.type_.xsd_type();
+    if let Some(type_) = &parameter.value.type_ {
+        let type_ = match type_ {
+            ParameterValueType::Qualified { type_, .. } => type_.as_str(),
+            ParameterValueType::Unqualified(type_) => type_.rsplit(':').next().unwrap_or(type_),
+        };
+        // lists are strings on the wire
+        let expected = if definition.syntax.list {
+            "string"
+        } else {
+            expected
+        };
+        if type_ != expected {
+            return Err(ParameterFault::new(
+                name,
+                FaultCode::InvalidParameterType,
+                format!("expected xsd:{expected}, got xsd:{type_}"),
+            ));
+        }
+    }
+
+    check_value(&definition.syntax, &parameter.value.value)
+        .map_err(|message| ParameterFault::new(name, FaultCode::InvalidParameterValue, message))
+}
+
+fn add_object(model: &DataModel, add: &AddObject) -> Result<(), ParameterFault> {
+    let name = &add.object_name;
+    let table = name
+        .parse::<ParameterPath>()
+        .ok()
+        .and_then(|path| model.table(&path))
+        .ok_or_else(|| {
+            ParameterFault::new(
+                name,
+                FaultCode::InvalidParameterName,
+                "not a multi-instance object of the data model",
+            )
+        })?;
+    if table.access == Access::ReadOnly {
+        return Err(ParameterFault::new(
+            name,
+            FaultCode::NonWritableParameter,
+            "instances can not be added",
+        ));
+    }
+    Ok(())
+}
+
+fn delete_object(model: &DataModel, delete: &AddObject) -> Result<(), ParameterFault> {
+    let name = &delete.object_name;
+    let object = name
+        .parse::<ParameterPath>()
+        .ok()
+        .filter(|path| {
+            matches!(
+                path.segments().last(),
+                Some(Segment::Instance(_) | Segment::Alias(_))
+            )
+        })
+        .and_then(|path| model.object(&path))
+        .ok_or_else(|| {
+            ParameterFault::new(
+                name,
+                FaultCode::InvalidParameterName,
+                "not an instance of the data model",
+            )
+        })?;
+    if object.access == Access::ReadOnly {
+        return Err(ParameterFault::new(
+            name,
+            FaultCode::NonWritableParameter,
+            "instances can not be deleted",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks a value against the syntax of its parameter, the reason is the
+/// detail of an invalid parameter value fault (9007).
+pub fn check_value(syntax: &Syntax, value: &str) -> Result<(), String> {
+    if !syntax.list {
+        return check_item(syntax, value);
+    }
+    if value.is_empty() {
+        return Ok(());
+    }
+    value
+        .split(',')
+        .try_for_each(|item| check_item(syntax, item.trim()))
+}
+
+fn check_item(syntax: &Syntax, value: &str) -> Result<(), String> {
+    let type_ = ParameterType {
+        base: syntax.type_.into(),
+        list: false,
+        data_type: syntax.data_type.clone(),
+    };
+    let typed = TypedValue::parse(value, &type_).map_err(|error| match error {
+        Error::InvalidValue(message) => message,
+        other => other.to_string(),
+    })?;
+
+    let number: Option<i128> = match typed {
+        TypedValue::Int(number) => Some(number.into()),
+        TypedValue::UnsignedInt(number) => Some(number.into()),
+        TypedValue::Long(number) => Some(number.into()),
+        TypedValue::UnsignedLong(number) => Some(number.into()),
+        _ => None,
+    };
+    if let Some(number) = number
+        && !syntax.ranges.is_empty()
+        && !syntax.ranges.iter().any(|range| range.contains(number))
+    {
+        let ranges: Vec<String> = syntax.ranges.iter().map(|r| r.to_string()).collect();
+        return Err(format!("{number} is outside {}", ranges.join(", ")));
+    }
+
+    // named types are read as addresses, their facets apply to the string
+    if syntax.type_ == ValueType::String {
+        if let Some(max_length) = syntax.max_length
+            && value.chars().count() > max_length as usize
+        {
+            return Err(format!("longer than {max_length} characters"));
+        }
+        if !syntax.enumeration.is_empty() && !syntax.enumeration.iter().any(|v| v == value) {
+            return Err(format!(
+                "'{value}' is not one of {}",
+                syntax.enumeration.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cwmp_typed::cwmp::CwmpVersion;
+    use cwmp_typed::cwmp::types::{ParameterList, ParameterValueStructValue};
+
+    fn value(name: &str, type_: &str, value: &str) -> ParameterValueStruct {
+        ParameterValueStruct {
+            name: name.to_string(),
+            value: ParameterValueStructValue {
+                type_: Some(ParameterValueType::Unqualified(format!("xsd:{type_}"))),
+                value: value.to_string(),
+            },
+        }
+    }
+
+    fn set_parameter_values(list: Vec<ParameterValueStruct>) -> Rpc {
+        let method = (String::new(), ParameterList(list)).try_into().unwrap();
+        Rpc::from((CwmpVersion::_12, RpcMethod::SetParameterValues(method)))
+    }
+
+    #[test]
+    fn set_parameter_values_faults() {
+        let model = DataModel::generated();
+        let valid = set_parameter_values(vec![
+            value("Device.WiFi.SSID.1.SSID", "string", "home"),
+            value("Device.WiFi.SSID.[home].Enable", "boolean", "1"),
+            value(
+                "Device.ManagementServer.PeriodicInformInterval",
+                "unsignedInt",
+                "300",
+            ),
+            value(
+                "Device.WiFi.SSID.1.LowerLayers",
+                "string",
+                "Device.WiFi.Radio.1.",
+            ),
+        ]);
+        assert!(validate_rpc(&model, &valid).is_ok());
+
+        let invalid = set_parameter_values(vec![
+            value("Device.WiFi.SSID.1.Unknown", "string", ""),
+            value("Device.WiFi.SSID.SSID", "string", "home"),
+            value("Device.WiFi.SSID.1.Status", "string", "Up"),
+            value("Device.WiFi.SSID.1.Enable", "string", "true"),
+            value("Device.WiFi.SSID.1.SSID", "string", &"x".repeat(33)),
+            value("Device.WiFi.SSID.1.Alias", "string", "1home"),
+            value(
+                "Device.ManagementServer.PeriodicInformInterval",
+                "unsignedInt",
+                "0",
+            ),
+            value(
+                "Device.ManagementServer.PeriodicInformEnable",
+                "boolean",
+                "yes",
+            ),
+            value(
+                "Device.WiFi.AccessPoint.1.Security.RekeyingInterval",
+                "unsignedInt",
+                "-1",
+            ),
+        ]);
+        let codes: Vec<u32> = validate_rpc(&model, &invalid)
+            .unwrap_err()
+            .iter()
+            .map(|fault| fault.code.code())
+            .collect();
+        assert_eq!(
+            codes,
+            [9005, 9005, 9008, 9006, 9007, 9007, 9007, 9007, 9007]
+        );
+    }
+
+    #[test]
+    fn object_faults() {
+        let model = DataModel::generated();
+        let rpc = |method: fn(AddObject) -> RpcMethod, name: &str| {
+            let object = (name.to_string(), String::new()).try_into().unwrap();
+            Rpc::from((CwmpVersion::_12, method(object)))
+        };
+        assert!(validate_rpc(&model, &rpc(RpcMethod::AddObject, "Device.WiFi.SSID.")).is_ok());
+        assert!(validate_rpc(&model, &rpc(RpcMethod::DeleteObject, "Device.WiFi.SSID.2.")).is_ok());
+
+        let fault = validate_rpc(&model, &rpc(RpcMethod::AddObject, "Device.WiFi.")).unwrap_err();
+        assert_eq!(fault[0].code, FaultCode::InvalidParameterName);
+        let fault = validate_rpc(
+            &model,
+            &rpc(
+                RpcMethod::AddObject,
+                "Device.WiFi.AccessPoint.1.AssociatedDevice.",
+            ),
+        )
+        .unwrap_err();
+        assert_eq!(fault[0].code, FaultCode::NonWritableParameter);
+        let fault =
+            validate_rpc(&model, &rpc(RpcMethod::DeleteObject, "Device.WiFi.SSID.")).unwrap_err();
+        assert_eq!(fault[0].code, FaultCode::InvalidParameterName);
+    }
+}
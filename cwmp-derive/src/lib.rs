@@ -0,0 +1,197 @@
+//! `#[derive(CwmpObject)]`, mapping a struct to the parameters of a
+//! multi-instance data model object. Refer `cwmp_typed::cwmp::object`.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Field, Fields, GenericArgument, LitStr, PathArguments, Type,
+    parse_macro_input,
+};
+
+#[proc_macro_derive(CwmpObject, attributes(cwmp))]
+pub fn derive_cwmp_object(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let path = object_path(input)?;
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "CwmpObject can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "CwmpObject needs a struct with named fields",
+        ));
+    };
+    if fields.named.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "CwmpObject needs at least one field",
+        ));
+    }
+
+    let object = quote!(::cwmp_typed::cwmp::object);
+    let mut from_values = Vec::new();
+    let mut diff = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named field");
+        let name = parameter_name(field)?;
+        match option_inner(&field.ty) {
+            Some(inner) => {
+                from_values.push(quote! {
+                    #ident: #object::optional_value::<#inner>(values, #name)?
+                });
+                diff.push(quote! {
+                    if self.#ident != old.#ident {
+                        if let ::core::option::Option::Some(value) = &self.#ident {
+                            parameters.push(#object::parameter_value(prefix, #name, value));
+                        }
+                    }
+                });
+            }
+            None => {
+                from_values.push(quote! {
+                    #ident: #object::value(values, #name)?
+                });
+                diff.push(quote! {
+                    if self.#ident != old.#ident {
+                        parameters.push(#object::parameter_value(prefix, #name, &self.#ident));
+                    }
+                });
+            }
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #object::CwmpObject for #ident #type_generics #where_clause {
+            const PATH: &'static str = #path;
+
+            fn from_values(
+                values: &#object::InstanceValues,
+            ) -> ::core::result::Result<Self, ::cwmp_typed::error::Error> {
+                ::core::result::Result::Ok(Self {
+                    #(#from_values,)*
+                })
+            }
+
+            fn diff(
+                &self,
+                old: &Self,
+                prefix: &str,
+            ) -> ::std::vec::Vec<::cwmp_typed::cwmp::types::ParameterValueStruct> {
+                let mut parameters = ::std::vec::Vec::new();
+                #(#diff)*
+                parameters
+            }
+        }
+    })
+}
+
+// the value of `#[cwmp(<key> = "..")]`, other keys are an error
+fn attribute(attrs: &[Attribute], key: &str) -> syn::Result<Option<LitStr>> {
+    let mut value = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("cwmp")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(key) {
+                value = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error(format!("expected `{key} = \"..\"`")))
+            }
+        })?;
+    }
+    Ok(value)
+}
+
+fn object_path(input: &DeriveInput) -> syn::Result<LitStr> {
+    let path = attribute(&input.attrs, "path")?.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "missing #[cwmp(path = \"Device.WiFi.SSID.{i}.\")]",
+        )
+    })?;
+    check_path(&path.value()).map_err(|message| syn::Error::new_spanned(&path, message))?;
+    Ok(path)
+}
+
+fn check_path(path: &str) -> Result<(), &'static str> {
+    let Some(table) = path.strip_suffix(".{i}.") else {
+        return Err("the path must be a multi-instance object ending in `.{i}.`");
+    };
+    if table.is_empty() || table.split('.').any(|name| name.is_empty()) {
+        return Err("the path has an empty name");
+    }
+    if table.contains("{i}") {
+        return Err("objects within a multi-instance object are not supported");
+    }
+    Ok(())
+}
+
+fn parameter_name(field: &Field) -> syn::Result<LitStr> {
+    if let Some(name) = attribute(&field.attrs, "name")? {
+        return Ok(name);
+    }
+    let ident = field.ident.as_ref().expect("named field").to_string();
+    let ident = ident.strip_prefix("r#").unwrap_or(&ident);
+    Ok(LitStr::new(&upper_camel_case(ident), field.span()))
+}
+
+// `lower_layers` -> `LowerLayers`
+fn upper_camel_case(ident: &str) -> String {
+    ident
+        .split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
+
+// `T` of an `Option<T>` field
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(inner) if arguments.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert_eq!(upper_camel_case("lower_layers"), "LowerLayers");
+        assert_eq!(upper_camel_case("enable"), "Enable");
+
+        assert!(check_path("Device.WiFi.SSID.{i}.").is_ok());
+        assert!(check_path("Device.WiFi.").is_err());
+        assert!(check_path("Device.WiFi.SSID.{i}").is_err());
+        assert!(check_path(".{i}.").is_err());
+        assert!(check_path("Device.WiFi.AccessPoint.{i}.AssociatedDevice.{i}.").is_err());
+    }
+}
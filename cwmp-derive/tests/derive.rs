@@ -0,0 +1,51 @@
+use cwmp_typed::cwmp::object::CwmpObject;
+use cwmp_typed::cwmp::types::{ParameterList, ParameterValueStruct, ParameterValueStructValue};
+
+#[derive(CwmpObject, Debug, Clone, PartialEq)]
+#[cwmp(path = "Device.WiFi.SSID.{i}.")]
+struct Ssid {
+    enable: bool,
+    #[cwmp(name = "SSID")]
+    ssid: String,
+    lower_layers: Option<String>,
+}
+
+fn parameter(name: &str, value: &str) -> ParameterValueStruct {
+    ParameterValueStruct {
+        name: name.to_string(),
+        value: ParameterValueStructValue {
+            type_: None,
+            value: value.to_string(),
+        },
+    }
+}
+
+#[test]
+fn derive_cwmp_object() {
+    let list = ParameterList(vec![
+        parameter("Device.WiFi.SSID.1.Enable", "1"),
+        parameter("Device.WiFi.SSID.1.SSID", "home"),
+        parameter("Device.WiFi.SSID.2.Enable", "0"),
+        parameter("Device.WiFi.SSID.2.SSID", "guest"),
+        parameter("Device.WiFi.SSID.2.LowerLayers", "Device.WiFi.Radio.2."),
+    ]);
+    let ssids = Ssid::from_parameter_list(&list).unwrap();
+    assert_eq!(ssids.len(), 2);
+    let (instance, old) = &ssids[1];
+    assert_eq!(*instance, 2);
+    assert_eq!(old.lower_layers.as_deref(), Some("Device.WiFi.Radio.2."));
+
+    let new = Ssid {
+        ssid: "visitors".to_string(),
+        lower_layers: None,
+        ..old.clone()
+    };
+    let spv = new.set_parameter_values(old, 2, "").unwrap();
+    let names: Vec<&str> = spv
+        .parameter_list()
+        .0
+        .iter()
+        .map(|parameter| parameter.name.as_str())
+        .collect();
+    assert_eq!(names, ["Device.WiFi.SSID.2.SSID"]);
+}
@@ -0,0 +1,39 @@
+//! `include/cwmp.h` is generated from the exported functions and committed,
+//! so a regular build does not need cbindgen.
+//!
+//! - `cargo build -p cwmp-ffi --features regenerate` rewrites it;
+//! - with `CWMP_FFI_HEADER_CHECK=1` set as well the build only fails when the
+//!   committed header is outdated.
+fn main() {
+    println!("cargo::rerun-if-changed=build.rs");
+
+    #[cfg(feature = "regenerate")]
+    regenerate();
+}
+
+#[cfg(feature = "regenerate")]
+fn regenerate() {
+    const HEADER: &str = "include/cwmp.h";
+
+    println!("cargo::rerun-if-changed=src");
+    println!("cargo::rerun-if-changed=cbindgen.toml");
+    println!("cargo::rerun-if-env-changed=CWMP_FFI_HEADER_CHECK");
+
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config =
+        cbindgen::Config::from_file("cbindgen.toml").expect("failed to read cbindgen.toml");
+    let mut header = Vec::new();
+    cbindgen::generate_with_config(&crate_dir, config)
+        .expect("failed to generate the header")
+        .write(&mut header);
+    let header = String::from_utf8(header).expect("the header is not utf-8");
+
+    let committed = std::fs::read_to_string(HEADER).unwrap_or_default();
+    if header == committed {
+        return;
+    }
+    if std::env::var_os("CWMP_FFI_HEADER_CHECK").is_some() {
+        panic!("{HEADER} is outdated, rebuild with `--features regenerate` and commit it");
+    }
+    std::fs::write(HEADER, header).expect("failed to write the header");
+}
@@ -0,0 +1,220 @@
+/*
+ * Envelopes are opaque CwmpEnvelope handles owned by the caller until passed
+ * to cwmp_envelope_free. Every function returns a CwmpStatus and
+ * cwmp_last_error has the message of the last failure on the thread.
+ *
+ * Strings are returned by copying into a caller buffer: *len is its size on
+ * input and the length of the result, without the NUL, on output. A buffer
+ * too small, or NULL, fails with CWMP_STATUS_BUFFER_TOO_SMALL and *len set
+ * to the size needed, the NUL of strings included.
+ */
+
+#ifndef CWMP_H
+#define CWMP_H
+
+/* Generated by cbindgen from cwmp-ffi, do not edit. Refer build.rs. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum CwmpRpcKind {
+  // A soap fault rather than an rpc.
+  CWMP_RPC_KIND_FAULT = 0,
+  CWMP_RPC_KIND_GET_RPC_METHODS,
+  CWMP_RPC_KIND_GET_PARAMETER_NAMES,
+  CWMP_RPC_KIND_SET_PARAMETER_VALUES,
+  CWMP_RPC_KIND_GET_PARAMETER_VALUES,
+  CWMP_RPC_KIND_SET_PARAMETER_ATTRIBUTES,
+  CWMP_RPC_KIND_GET_PARAMETER_ATTRIBUTES,
+  CWMP_RPC_KIND_ADD_OBJECT,
+  CWMP_RPC_KIND_DELETE_OBJECT,
+  CWMP_RPC_KIND_REBOOT,
+  CWMP_RPC_KIND_FACTORY_RESET,
+  CWMP_RPC_KIND_DOWNLOAD,
+  CWMP_RPC_KIND_GET_RPC_METHODS_RESPONSE,
+  CWMP_RPC_KIND_GET_PARAMETER_NAMES_RESPONSE,
+  CWMP_RPC_KIND_SET_PARAMETER_VALUES_RESPONSE,
+  CWMP_RPC_KIND_GET_PARAMETER_VALUES_RESPONSE,
+  CWMP_RPC_KIND_SET_PARAMETER_ATTRIBUTES_RESPONSE,
+  CWMP_RPC_KIND_GET_PARAMETER_ATTRIBUTES_RESPONSE,
+  CWMP_RPC_KIND_ADD_OBJECT_RESPONSE,
+  CWMP_RPC_KIND_DELETE_OBJECT_RESPONSE,
+  CWMP_RPC_KIND_REBOOT_RESPONSE,
+  CWMP_RPC_KIND_FACTORY_RESET_RESPONSE,
+  CWMP_RPC_KIND_DOWNLOAD_RESPONSE,
+  CWMP_RPC_KIND_INFORM,
+  CWMP_RPC_KIND_INFORM_RESPONSE,
+} CwmpRpcKind;
+
+typedef enum CwmpStatus {
+  CWMP_STATUS_OK = 0,
+  CWMP_STATUS_NULL_POINTER,
+  CWMP_STATUS_INVALID_UTF8,
+  // An argument the codec rejects, e.g. an unknown xsd type.
+  CWMP_STATUS_INVALID_ARGUMENT,
+  // The envelope could not be parsed.
+  CWMP_STATUS_PARSE,
+  // The envelope could not be serialized.
+  CWMP_STATUS_SERIALIZE,
+  CWMP_STATUS_BUFFER_TOO_SMALL,
+  // A parameter index past the end of the list.
+  CWMP_STATUS_OUT_OF_RANGE,
+  // The envelope holds another rpc than the function expects.
+  CWMP_STATUS_WRONG_KIND,
+  // A bug in the library, the call had no effect.
+  CWMP_STATUS_PANIC,
+} CwmpStatus;
+
+// A parsed or built envelope, freed with `cwmp_envelope_free`.
+typedef struct CwmpEnvelope CwmpEnvelope;
+
+// Copies the message of the last failed call on this thread into `buf`.
+//
+// # Safety
+//
+// `buf` is NULL or points to `*len` writable bytes, `len` is valid.
+CwmpStatus cwmp_last_error(char *buf, size_t *len);
+
+// A static description of a `CwmpStatus` value, never NULL. Values that are
+// not a `CwmpStatus` get "unknown status".
+const char *cwmp_status_message(int status);
+
+// Parses the `len` bytes at `xml` into a new handle stored in `*out`.
+//
+// # Safety
+//
+// `xml` points to `len` readable bytes, `out` is valid for writes.
+CwmpStatus cwmp_envelope_parse(const uint8_t *xml, size_t len, CwmpEnvelope **out);
+
+// Frees a handle, NULL is ignored.
+//
+// # Safety
+//
+// `envelope` is NULL or a live handle, which is invalid afterwards.
+void cwmp_envelope_free(CwmpEnvelope *envelope);
+
+// # Safety
+//
+// `envelope` is a live handle, `kind` is valid for writes.
+CwmpStatus cwmp_envelope_rpc_kind(const CwmpEnvelope *envelope, CwmpRpcKind *kind);
+
+// Copies the `cwmp:ID` header, empty without one.
+//
+// # Safety
+//
+// `envelope` is a live handle, `buf` and `len` as described in the header.
+CwmpStatus cwmp_envelope_id(const CwmpEnvelope *envelope, char *buf, size_t *len);
+
+// The FaultCode of a fault envelope.
+//
+// # Safety
+//
+// `envelope` is a live handle, `code` is valid for writes.
+CwmpStatus cwmp_envelope_fault_code(const CwmpEnvelope *envelope, uint32_t *code);
+
+// Serializes the envelope into `buf`, without a NUL terminator. The rpc is
+// checked against the schema facets first.
+//
+// # Safety
+//
+// `envelope` is a live handle, `buf` and `len` as described in the header.
+CwmpStatus cwmp_envelope_serialize(const CwmpEnvelope *envelope, uint8_t *buf, size_t *len);
+
+// # Safety
+//
+// `envelope` is a live handle, `count` is valid for writes.
+CwmpStatus cwmp_envelope_parameter_count(const CwmpEnvelope *envelope, size_t *count);
+
+// # Safety
+//
+// `envelope` is a live handle, `buf` and `len` as described in the header.
+CwmpStatus cwmp_envelope_parameter_name(const CwmpEnvelope *envelope,
+                                        size_t index,
+                                        char *buf,
+                                        size_t *len);
+
+// Fails with `CWMP_STATUS_WRONG_KIND` for a GetParameterValues.
+//
+// # Safety
+//
+// `envelope` is a live handle, `buf` and `len` as described in the header.
+CwmpStatus cwmp_envelope_parameter_value(const CwmpEnvelope *envelope,
+                                         size_t index,
+                                         char *buf,
+                                         size_t *len);
+
+// The xsi:type without its prefix, `unsignedInt`, empty when not sent.
+//
+// # Safety
+//
+// `envelope` is a live handle, `buf` and `len` as described in the header.
+CwmpStatus cwmp_envelope_parameter_type(const CwmpEnvelope *envelope,
+                                        size_t index,
+                                        char *buf,
+                                        size_t *len);
+
+// The ParameterKey of a SetParameterValues.
+//
+// # Safety
+//
+// `envelope` is a live handle, `buf` and `len` as described in the header.
+CwmpStatus cwmp_envelope_parameter_key(const CwmpEnvelope *envelope, char *buf, size_t *len);
+
+// An empty GetParameterValuesResponse to `request`, filled with
+// `cwmp_get_parameter_values_response_push`.
+//
+// # Safety
+//
+// `request` is a live handle, `out` is valid for writes.
+CwmpStatus cwmp_get_parameter_values_response_new(const CwmpEnvelope *request,
+                                                  CwmpEnvelope **out);
+
+// Adds a parameter, `xsd_type` is the xsi:type without prefix
+// (`unsignedInt`), NULL for `string`.
+//
+// # Safety
+//
+// `response` is a live handle, the strings are NUL terminated or, for
+// `xsd_type`, NULL.
+CwmpStatus cwmp_get_parameter_values_response_push(CwmpEnvelope *response,
+                                                   const char *name,
+                                                   const char *value,
+                                                   const char *xsd_type);
+
+// A SetParameterValuesResponse to `request`, `status` 0 when the changes
+// are applied, 1 when they are committed but applied later.
+//
+// # Safety
+//
+// `request` is a live handle, `out` is valid for writes.
+CwmpStatus cwmp_set_parameter_values_response_new(const CwmpEnvelope *request,
+                                                  uint32_t status,
+                                                  CwmpEnvelope **out);
+
+// A fault answering `request`. `fault_code` is a TR-069 fault code or a
+// vendor code from 9800 to 9899, `fault_string` NULL for the string the
+// specification suggests.
+//
+// # Safety
+//
+// `request` is a live handle, `fault_string` is NULL or NUL terminated,
+// `out` is valid for writes.
+CwmpStatus cwmp_fault_new(const CwmpEnvelope *request,
+                          uint32_t fault_code,
+                          const char *fault_string,
+                          CwmpEnvelope **out);
+
+// Adds a SetParameterValuesFault for parameter `name` to a fault.
+//
+// # Safety
+//
+// `fault` is a live handle, `name` is NUL terminated, `fault_string` is NULL
+// or NUL terminated.
+CwmpStatus cwmp_fault_push_parameter(CwmpEnvelope *fault,
+                                     const char *name,
+                                     uint32_t fault_code,
+                                     const char *fault_string);
+
+#endif  /* CWMP_H */
@@ -0,0 +1,229 @@
+use crate::{CwmpStatus, fail, guard, write_bytes, write_str};
+use cwmp_typed::cwmp::facets::Facets;
+use cwmp_typed::cwmp::rpc::RpcMethod;
+use cwmp_typed::envelope::{Envelope, SerializerProfile};
+use std::ffi::c_char;
+use std::slice;
+
+/// A parsed or built envelope, freed with `cwmp_envelope_free`.
+pub struct CwmpEnvelope(pub(crate) Envelope);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CwmpRpcKind {
+    /// A soap fault rather than an rpc.
+    Fault = 0,
+    GetRpcMethods,
+    GetParameterNames,
+    SetParameterValues,
+    GetParameterValues,
+    SetParameterAttributes,
+    GetParameterAttributes,
+    AddObject,
+    DeleteObject,
+    Reboot,
+    FactoryReset,
+    Download,
+    GetRpcMethodsResponse,
+    GetParameterNamesResponse,
+    SetParameterValuesResponse,
+    GetParameterValuesResponse,
+    SetParameterAttributesResponse,
+    GetParameterAttributesResponse,
+    AddObjectResponse,
+    DeleteObjectResponse,
+    RebootResponse,
+    FactoryResetResponse,
+    DownloadResponse,
+    Inform,
+    InformResponse,
+}
+
+impl From<&RpcMethod> for CwmpRpcKind {
+    fn from(method: &RpcMethod) -> Self {
+        match method {
+            RpcMethod::GetRPCMethods => Self::GetRpcMethods,
+            RpcMethod::GetParameterNames(_) => Self::GetParameterNames,
+            RpcMethod::SetParameterValues(_) => Self::SetParameterValues,
+            RpcMethod::GetParameterValues(_) => Self::GetParameterValues,
+            RpcMethod::SetParameterAttributes(_) => Self::SetParameterAttributes,
+            RpcMethod::GetParameterAttributes(_) => Self::GetParameterAttributes,
+            RpcMethod::AddObject(_) => Self::AddObject,
+            RpcMethod::DeleteObject(_) => Self::DeleteObject,
+            RpcMethod::Reboot(_) => Self::Reboot,
+            RpcMethod::FactoryReset => Self::FactoryReset,
+            RpcMethod::Download(_) => Self::Download,
+            RpcMethod::GetRPCMethodsResponse(_) => Self::GetRpcMethodsResponse,
+            RpcMethod::GetParameterNamesResponse(_) => Self::GetParameterNamesResponse,
+            RpcMethod::SetParameterValuesResponse(_) => Self::SetParameterValuesResponse,
+            RpcMethod::GetParameterValuesResponse(_) => Self::GetParameterValuesResponse,
+            RpcMethod::SetParameterAttributesResponse => Self::SetParameterAttributesResponse,
+            RpcMethod::GetParameterAttributesResponse(_) => Self::GetParameterAttributesResponse,
+            RpcMethod::AddObjectResponse(_) => Self::AddObjectResponse,
+            RpcMethod::DeleteObjectResponse(_) => Self::DeleteObjectResponse,
+            RpcMethod::RebootResponse => Self::RebootResponse,
+            RpcMethod::FactoryResetResponse => Self::FactoryResetResponse,
+            RpcMethod::DownloadResponse(_) => Self::DownloadResponse,
+            RpcMethod::Inform(_) => Self::Inform,
+            RpcMethod::InformResponse(_) => Self::InformResponse,
+        }
+    }
+}
+
+/// # Safety
+///
+/// `envelope` is NULL or a live handle.
+pub(crate) unsafe fn envelope_ref<'a>(
+    envelope: *const CwmpEnvelope,
+) -> Result<&'a Envelope, CwmpStatus> {
+    unsafe { envelope.as_ref() }
+        .map(|envelope| &envelope.0)
+        .ok_or_else(|| fail(CwmpStatus::NullPointer, "envelope is NULL"))
+}
+
+/// # Safety
+///
+/// `envelope` is NULL or a live handle not used elsewhere during the call.
+pub(crate) unsafe fn envelope_mut<'a>(
+    envelope: *mut CwmpEnvelope,
+) -> Result<&'a mut Envelope, CwmpStatus> {
+    unsafe { envelope.as_mut() }
+        .map(|envelope| &mut envelope.0)
+        .ok_or_else(|| fail(CwmpStatus::NullPointer, "envelope is NULL"))
+}
+
+/// Hands `envelope` over to the caller through `out`.
+///
+/// # Safety
+///
+/// `out` is NULL or valid for writes.
+pub(crate) unsafe fn give(
+    envelope: Envelope,
+    out: *mut *mut CwmpEnvelope,
+) -> Result<(), CwmpStatus> {
+    if out.is_null() {
+        return Err(fail(CwmpStatus::NullPointer, "out is NULL"));
+    }
+    unsafe { *out = Box::into_raw(Box::new(CwmpEnvelope(envelope))) };
+    Ok(())
+}
+
+/// Parses the `len` bytes at `xml` into a new handle stored in `*out`.
+///
+/// # Safety
+///
+/// `xml` points to `len` readable bytes, `out` is valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_envelope_parse(
+    xml: *const u8,
+    len: usize,
+    out: *mut *mut CwmpEnvelope,
+) -> CwmpStatus {
+    guard(|| {
+        if xml.is_null() {
+            return Err(fail(CwmpStatus::NullPointer, "xml is NULL"));
+        }
+        let xml = unsafe { slice::from_raw_parts(xml, len) };
+        let envelope = Envelope::parse(xml).map_err(|e| fail(CwmpStatus::Parse, e))?;
+        unsafe { give(envelope, out) }
+    })
+}
+
+/// Frees a handle, NULL is ignored.
+///
+/// # Safety
+///
+/// `envelope` is NULL or a live handle, which is invalid afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_envelope_free(envelope: *mut CwmpEnvelope) {
+    if !envelope.is_null() {
+        drop(unsafe { Box::from_raw(envelope) });
+    }
+}
+
+/// # Safety
+///
+/// `envelope` is a live handle, `kind` is valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_envelope_rpc_kind(
+    envelope: *const CwmpEnvelope,
+    kind: *mut CwmpRpcKind,
+) -> CwmpStatus {
+    guard(|| {
+        let envelope = unsafe { envelope_ref(envelope) }?;
+        if kind.is_null() {
+            return Err(fail(CwmpStatus::NullPointer, "kind is NULL"));
+        }
+        let value = envelope
+            .rpc()
+            .map_or(CwmpRpcKind::Fault, |rpc| rpc.method().into());
+        unsafe { *kind = value };
+        Ok(())
+    })
+}
+
+/// Copies the `cwmp:ID` header, empty without one.
+///
+/// # Safety
+///
+/// `envelope` is a live handle, `buf` and `len` as described in the header.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_envelope_id(
+    envelope: *const CwmpEnvelope,
+    buf: *mut c_char,
+    len: *mut usize,
+) -> CwmpStatus {
+    guard(|| {
+        let envelope = unsafe { envelope_ref(envelope) }?;
+        unsafe { write_str(envelope.id().unwrap_or_default(), buf, len) }
+    })
+}
+
+/// The FaultCode of a fault envelope.
+///
+/// # Safety
+///
+/// `envelope` is a live handle, `code` is valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_envelope_fault_code(
+    envelope: *const CwmpEnvelope,
+    code: *mut u32,
+) -> CwmpStatus {
+    guard(|| {
+        let envelope = unsafe { envelope_ref(envelope) }?;
+        let fault = envelope
+            .fault()
+            .ok_or_else(|| fail(CwmpStatus::WrongKind, "the envelope is not a fault"))?;
+        if code.is_null() {
+            return Err(fail(CwmpStatus::NullPointer, "code is NULL"));
+        }
+        unsafe { *code = fault.fault_code };
+        Ok(())
+    })
+}
+
+/// Serializes the envelope into `buf`, without a NUL terminator. The rpc is
+/// checked against the schema facets first.
+///
+/// # Safety
+///
+/// `envelope` is a live handle, `buf` and `len` as described in the header.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_envelope_serialize(
+    envelope: *const CwmpEnvelope,
+    buf: *mut u8,
+    len: *mut usize,
+) -> CwmpStatus {
+    guard(|| {
+        let envelope = unsafe { envelope_ref(envelope) }?;
+        if let Some(rpc) = envelope.rpc() {
+            rpc.method()
+                .check_facets()
+                .map_err(|e| fail(CwmpStatus::Serialize, e))?;
+        }
+        let xml = envelope
+            .to_xml(&SerializerProfile::default())
+            .map_err(|e| fail(CwmpStatus::Serialize, e))?;
+        unsafe { write_bytes(&xml, false, buf, len) }
+    })
+}
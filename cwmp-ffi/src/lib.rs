@@ -0,0 +1,174 @@
+//! C interface to the cwmp-typed codec, declared in `include/cwmp.h`.
+//!
+//! - Envelopes are opaque `CwmpEnvelope` handles, owned by the caller from
+//!   `cwmp_envelope_parse` or one of the response constructors until passed
+//!   to `cwmp_envelope_free`.
+//! - Every function returns a `CwmpStatus`, `CWMP_STATUS_OK` on success.
+//!   `cwmp_last_error` has the message of the last failure on the thread.
+//! - Strings are passed in as NUL terminated UTF-8. Strings and bytes are
+//!   returned by copying into a caller buffer: `*len` is its size on input
+//!   and the length of the result, without the NUL, on output. A buffer too
+//!   small, or NULL, fails with `CWMP_STATUS_BUFFER_TOO_SMALL` and `*len` set
+//!   to the size needed, the NUL of strings included.
+use std::cell::RefCell;
+use std::ffi::{CStr, c_char, c_int};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+mod envelope;
+mod parameters;
+mod response;
+
+pub use envelope::*;
+pub use parameters::*;
+pub use response::*;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CwmpStatus {
+    Ok = 0,
+    NullPointer,
+    InvalidUtf8,
+    /// An argument the codec rejects, e.g. an unknown xsd type.
+    InvalidArgument,
+    /// The envelope could not be parsed.
+    Parse,
+    /// The envelope could not be serialized.
+    Serialize,
+    BufferTooSmall,
+    /// A parameter index past the end of the list.
+    OutOfRange,
+    /// The envelope holds another rpc than the function expects.
+    WrongKind,
+    /// A bug in the library, the call had no effect.
+    Panic,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Records the message for `cwmp_last_error` and returns `status`.
+pub(crate) fn fail(status: CwmpStatus, message: impl fmt::Display) -> CwmpStatus {
+    LAST_ERROR.with(|last| *last.borrow_mut() = message.to_string());
+    status
+}
+
+/// Runs the body of an exported function, turning a panic into
+/// [`CwmpStatus::Panic`] rather than unwinding into C.
+pub(crate) fn guard<F: FnOnce() -> Result<(), CwmpStatus>>(body: F) -> CwmpStatus {
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => CwmpStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => fail(CwmpStatus::Panic, "panic in cwmp-ffi"),
+    }
+}
+
+/// # Safety
+///
+/// `text` is NULL or a NUL terminated string that outlives `'a`.
+pub(crate) unsafe fn read_str<'a>(text: *const c_char) -> Result<&'a str, CwmpStatus> {
+    if text.is_null() {
+        return Err(fail(CwmpStatus::NullPointer, "string argument is NULL"));
+    }
+    unsafe { CStr::from_ptr(text) }
+        .to_str()
+        .map_err(|e| fail(CwmpStatus::InvalidUtf8, e))
+}
+
+/// Like [`read_str`], NULL is `None`.
+///
+/// # Safety
+///
+/// As for [`read_str`].
+pub(crate) unsafe fn read_optional_str<'a>(
+    text: *const c_char,
+) -> Result<Option<&'a str>, CwmpStatus> {
+    if text.is_null() {
+        return Ok(None);
+    }
+    unsafe { read_str(text) }.map(Some)
+}
+
+/// # Safety
+///
+/// `buf` is NULL or points to `*len` writable bytes, `len` is NULL or valid.
+pub(crate) unsafe fn write_bytes(
+    bytes: &[u8],
+    nul: bool,
+    buf: *mut u8,
+    len: *mut usize,
+) -> Result<(), CwmpStatus> {
+    if len.is_null() {
+        return Err(fail(CwmpStatus::NullPointer, "len is NULL"));
+    }
+    let capacity = unsafe { *len };
+    let needed = bytes.len() + usize::from(nul);
+    if buf.is_null() || capacity < needed {
+        unsafe { *len = needed };
+        return Err(fail(
+            CwmpStatus::BufferTooSmall,
+            format!("{needed} bytes needed, the buffer has {capacity}"),
+        ));
+    }
+    unsafe {
+        ptr::copy_nonoverlapping(bytes.as_ptr(), buf, bytes.len());
+        if nul {
+            *buf.add(bytes.len()) = 0;
+        }
+        *len = bytes.len();
+    }
+    Ok(())
+}
+
+/// # Safety
+///
+/// As for [`write_bytes`].
+pub(crate) unsafe fn write_str(
+    text: &str,
+    buf: *mut c_char,
+    len: *mut usize,
+) -> Result<(), CwmpStatus> {
+    unsafe { write_bytes(text.as_bytes(), true, buf.cast(), len) }
+}
+
+/// Copies the message of the last failed call on this thread into `buf`.
+///
+/// # Safety
+///
+/// `buf` is NULL or points to `*len` writable bytes, `len` is valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_last_error(buf: *mut c_char, len: *mut usize) -> CwmpStatus {
+    guard(|| {
+        let message = LAST_ERROR.with(|last| last.borrow().clone());
+        let result = unsafe { write_str(&message, buf, len) };
+        // keep the message for the retry with a larger buffer
+        LAST_ERROR.with(|last| *last.borrow_mut() = message);
+        result
+    })
+}
+
+const STATUS_MESSAGES: [(CwmpStatus, &CStr); 10] = [
+    (CwmpStatus::Ok, c"ok"),
+    (CwmpStatus::NullPointer, c"NULL pointer argument"),
+    (CwmpStatus::InvalidUtf8, c"string argument is not UTF-8"),
+    (CwmpStatus::InvalidArgument, c"invalid argument"),
+    (CwmpStatus::Parse, c"envelope could not be parsed"),
+    (CwmpStatus::Serialize, c"envelope could not be serialized"),
+    (CwmpStatus::BufferTooSmall, c"buffer too small"),
+    (CwmpStatus::OutOfRange, c"index out of range"),
+    (CwmpStatus::WrongKind, c"not supported for this rpc"),
+    (CwmpStatus::Panic, c"internal error"),
+];
+
+/// A static description of a `CwmpStatus` value, never NULL. Values that are
+/// not a `CwmpStatus` get "unknown status".
+#[unsafe(no_mangle)]
+pub extern "C" fn cwmp_status_message(status: c_int) -> *const c_char {
+    STATUS_MESSAGES
+        .iter()
+        .find(|(known, _)| *known as c_int == status)
+        .map_or(c"unknown status", |(_, message)| message)
+        .as_ptr()
+}
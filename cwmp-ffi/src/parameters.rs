@@ -0,0 +1,168 @@
+//! Walking the parameter list of a SetParameterValues, GetParameterValues or
+//! GetParameterValuesResponse by index.
+use crate::envelope::{CwmpEnvelope, envelope_ref};
+use crate::{CwmpStatus, fail, guard, write_str};
+use cwmp_typed::cwmp::rpc::{Rpc, RpcMethod};
+use cwmp_typed::cwmp::types::{ParameterName, ParameterValueStruct, ParameterValueType};
+use std::ffi::c_char;
+
+enum Entries<'a> {
+    Values(&'a [ParameterValueStruct]),
+    // GetParameterValues has names only
+    Names(&'a [ParameterName]),
+}
+
+impl Entries<'_> {
+    fn len(&self) -> usize {
+        match self {
+            Self::Values(values) => values.len(),
+            Self::Names(names) => names.len(),
+        }
+    }
+
+    fn check(&self, index: usize) -> Result<(), CwmpStatus> {
+        if index >= self.len() {
+            return Err(fail(
+                CwmpStatus::OutOfRange,
+                format!("index {index} is past the {} parameters", self.len()),
+            ));
+        }
+        Ok(())
+    }
+
+    fn value(&self, index: usize) -> Result<&ParameterValueStruct, CwmpStatus> {
+        self.check(index)?;
+        match self {
+            Self::Values(values) => Ok(&values[index]),
+            Self::Names(_) => Err(fail(
+                CwmpStatus::WrongKind,
+                "GetParameterValues has no values",
+            )),
+        }
+    }
+}
+
+/// # Safety
+///
+/// `envelope` is NULL or a live handle.
+unsafe fn entries<'a>(envelope: *const CwmpEnvelope) -> Result<Entries<'a>, CwmpStatus> {
+    let envelope = unsafe { envelope_ref(envelope) }?;
+    match envelope.rpc().map(Rpc::method) {
+        Some(RpcMethod::SetParameterValues(inner)) => {
+            Ok(Entries::Values(&inner.parameter_list().0))
+        }
+        Some(RpcMethod::GetParameterValuesResponse(inner)) => {
+            Ok(Entries::Values(&inner.parameter_list.0))
+        }
+        Some(RpcMethod::GetParameterValues(inner)) => {
+            Ok(Entries::Names(&inner.parameter_names().0))
+        }
+        _ => Err(fail(CwmpStatus::WrongKind, "the rpc has no parameter list")),
+    }
+}
+
+/// # Safety
+///
+/// `envelope` is a live handle, `count` is valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_envelope_parameter_count(
+    envelope: *const CwmpEnvelope,
+    count: *mut usize,
+) -> CwmpStatus {
+    guard(|| {
+        let entries = unsafe { entries(envelope) }?;
+        if count.is_null() {
+            return Err(fail(CwmpStatus::NullPointer, "count is NULL"));
+        }
+        unsafe { *count = entries.len() };
+        Ok(())
+    })
+}
+
+/// # Safety
+///
+/// `envelope` is a live handle, `buf` and `len` as described in the header.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_envelope_parameter_name(
+    envelope: *const CwmpEnvelope,
+    index: usize,
+    buf: *mut c_char,
+    len: *mut usize,
+) -> CwmpStatus {
+    guard(|| {
+        let entries = unsafe { entries(envelope) }?;
+        entries.check(index)?;
+        let name = match entries {
+            Entries::Values(values) => values[index].name.clone(),
+            Entries::Names(names) => names[index].to_string(),
+        };
+        unsafe { write_str(&name, buf, len) }
+    })
+}
+
+/// Fails with `CWMP_STATUS_WRONG_KIND` for a GetParameterValues.
+///
+/// # Safety
+///
+/// `envelope` is a live handle, `buf` and `len` as described in the header.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_envelope_parameter_value(
+    envelope: *const CwmpEnvelope,
+    index: usize,
+    buf: *mut c_char,
+    len: *mut usize,
+) -> CwmpStatus {
+    guard(|| {
+        let entries = unsafe { entries(envelope) }?;
+        let value = entries.value(index)?;
+        unsafe { write_str(&value.value.value, buf, len) }
+    })
+}
+
+/// The xsi:type without its prefix, `unsignedInt`, empty when not sent.
+///
+/// # Safety
+///
+/// `envelope` is a live handle, `buf` and `len` as described in the header.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_envelope_parameter_type(
+    envelope: *const CwmpEnvelope,
+    index: usize,
+    buf: *mut c_char,
+    len: *mut usize,
+) -> CwmpStatus {
+    guard(|| {
+        let entries = unsafe { entries(envelope) }?;
+        let type_ = match &entries.value(index)?.value.type_ {
+            Some(ParameterValueType::Qualified { type_, .. }) => type_.as_str(),
+            Some(ParameterValueType::Unqualified(type_)) => {
+                type_.rsplit(':').next().unwrap_or(type_)
+            }
+            None => "",
+        };
+        unsafe { write_str(type_, buf, len) }
+    })
+}
+
+/// The ParameterKey of a SetParameterValues.
+///
+/// # Safety
+///
+/// `envelope` is a live handle, `buf` and `len` as described in the header.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_envelope_parameter_key(
+    envelope: *const CwmpEnvelope,
+    buf: *mut c_char,
+    len: *mut usize,
+) -> CwmpStatus {
+    guard(|| {
+        let envelope = unsafe { envelope_ref(envelope) }?;
+        let Some(RpcMethod::SetParameterValues(inner)) = envelope.rpc().map(Rpc::method) else {
+            return Err(fail(
+                CwmpStatus::WrongKind,
+                "the rpc is not a SetParameterValues",
+            ));
+        };
+        unsafe { write_str(inner.parameter_key(), buf, len) }
+    })
+}
@@ -0,0 +1,380 @@
+//! Responses to a request of the ACS. A response repeats the cwmp version
+//! and `cwmp:ID` of its request.
+use crate::envelope::{CwmpEnvelope, envelope_mut, envelope_ref, give};
+use crate::{CwmpStatus, fail, guard, read_optional_str, read_str};
+use cwmp_typed::cwmp::facets;
+use cwmp_typed::cwmp::fault::suggested_fault_string;
+use cwmp_typed::cwmp::rpc::{Rpc, RpcMethod};
+use cwmp_typed::cwmp::types::{
+    GetParameterValuesResponse, ParameterList, ParameterValueStruct, ParameterValueStructValue,
+    SetParameterValuesResponse, SetParameterValuesResponseStatus,
+};
+use cwmp_typed::cwmp::value::{ParameterType, XsdType};
+use cwmp_typed::envelope::{Envelope, Fault, SetParameterValuesFault};
+use std::ffi::c_char;
+
+/// # Safety
+///
+/// `request` is NULL or a live handle, `out` is NULL or valid for writes.
+unsafe fn respond(
+    request: *const CwmpEnvelope,
+    body: impl FnOnce(&Envelope) -> Envelope,
+    out: *mut *mut CwmpEnvelope,
+) -> Result<(), CwmpStatus> {
+    let request = unsafe { envelope_ref(request) }?;
+    let mut response = body(request);
+    if let Some(id) = request.id() {
+        response.set_id(id);
+    }
+    unsafe { give(response, out) }
+}
+
+fn check_length(field: &str, value: &str, limit: usize) -> Result<(), CwmpStatus> {
+    if value.len() > limit {
+        return Err(fail(
+            CwmpStatus::InvalidArgument,
+            format!("{field} is longer than {limit} bytes"),
+        ));
+    }
+    Ok(())
+}
+
+// the code and FaultString of a fault, the suggested string unless given
+fn fault_of(code: u32, fault_string: Option<&str>) -> Result<(u32, String), CwmpStatus> {
+    let suggested =
+        suggested_fault_string(code).map_err(|e| fail(CwmpStatus::InvalidArgument, e))?;
+    let fault_string = fault_string.unwrap_or(suggested);
+    check_length("FaultString", fault_string, facets::FAULT_STRING)?;
+    Ok((code, fault_string.to_string()))
+}
+
+/// An empty GetParameterValuesResponse to `request`, filled with
+/// `cwmp_get_parameter_values_response_push`.
+///
+/// # Safety
+///
+/// `request` is a live handle, `out` is valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_get_parameter_values_response_new(
+    request: *const CwmpEnvelope,
+    out: *mut *mut CwmpEnvelope,
+) -> CwmpStatus {
+    guard(|| unsafe {
+        respond(
+            request,
+            |request| {
+                let method = RpcMethod::GetParameterValuesResponse(GetParameterValuesResponse {
+                    parameter_list: ParameterList(Vec::new()),
+                });
+                Rpc::from((*request.version(), method)).into()
+            },
+            out,
+        )
+    })
+}
+
+/// Adds a parameter, `xsd_type` is the xsi:type without prefix
+/// (`unsignedInt`), NULL for `string`.
+///
+/// # Safety
+///
+/// `response` is a live handle, the strings are NUL terminated or, for
+/// `xsd_type`, NULL.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_get_parameter_values_response_push(
+    response: *mut CwmpEnvelope,
+    name: *const c_char,
+    value: *const c_char,
+    xsd_type: *const c_char,
+) -> CwmpStatus {
+    guard(|| {
+        let response = unsafe { envelope_mut(response) }?;
+        let name = unsafe { read_str(name) }?;
+        let value = unsafe { read_str(value) }?;
+        let base = match unsafe { read_optional_str(xsd_type) }? {
+            Some(xsd_type) => XsdType::from_name(xsd_type).ok_or_else(|| {
+                fail(
+                    CwmpStatus::InvalidArgument,
+                    format!("unknown xsd type '{xsd_type}'"),
+                )
+            })?,
+            None => XsdType::String,
+        };
+        check_length("Name", name, facets::PARAMETER_NAME)?;
+
+        let Some(RpcMethod::GetParameterValuesResponse(inner)) =
+            response.rpc_mut().map(Rpc::method_mut)
+        else {
+            return Err(fail(
+                CwmpStatus::WrongKind,
+                "the envelope is not a GetParameterValuesResponse",
+            ));
+        };
+        inner.parameter_list.0.push(ParameterValueStruct {
+            name: name.to_string(),
+            value: ParameterValueStructValue {
+                type_: Some(ParameterType::new(base).xsi_type()),
+                value: value.to_string(),
+            },
+        });
+        Ok(())
+    })
+}
+
+/// A SetParameterValuesResponse to `request`, `status` 0 when the changes
+/// are applied, 1 when they are committed but applied later.
+///
+/// # Safety
+///
+/// `request` is a live handle, `out` is valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_set_parameter_values_response_new(
+    request: *const CwmpEnvelope,
+    status: u32,
+    out: *mut *mut CwmpEnvelope,
+) -> CwmpStatus {
+    guard(|| {
+        let status = match status {
+            0 => SetParameterValuesResponseStatus::_0,
+            1 => SetParameterValuesResponseStatus::_1,
+            other => {
+                return Err(fail(
+                    CwmpStatus::InvalidArgument,
+                    format!("status {other} is neither 0 nor 1"),
+                ));
+            }
+        };
+        unsafe {
+            respond(
+                request,
+                |request| {
+                    let method =
+                        RpcMethod::SetParameterValuesResponse(SetParameterValuesResponse {
+                            status,
+                        });
+                    Rpc::from((*request.version(), method)).into()
+                },
+                out,
+            )
+        }
+    })
+}
+
+/// A fault answering `request`. `fault_code` is a TR-069 fault code or a
+/// vendor code from 9800 to 9899, `fault_string` NULL for the string the
+/// specification suggests.
+///
+/// # Safety
+///
+/// `request` is a live handle, `fault_string` is NULL or NUL terminated,
+/// `out` is valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_fault_new(
+    request: *const CwmpEnvelope,
+    fault_code: u32,
+    fault_string: *const c_char,
+    out: *mut *mut CwmpEnvelope,
+) -> CwmpStatus {
+    guard(|| {
+        let (fault_code, fault_string) =
+            fault_of(fault_code, unsafe { read_optional_str(fault_string) }?)?;
+        let fault = Fault {
+            fault_code,
+            fault_string,
+            set_parameter_values_faults: Vec::new(),
+        };
+        unsafe { respond(request, |request| (*request.version(), fault).into(), out) }
+    })
+}
+
+/// Adds a SetParameterValuesFault for parameter `name` to a fault.
+///
+/// # Safety
+///
+/// `fault` is a live handle, `name` is NUL terminated, `fault_string` is NULL
+/// or NUL terminated.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cwmp_fault_push_parameter(
+    fault: *mut CwmpEnvelope,
+    name: *const c_char,
+    fault_code: u32,
+    fault_string: *const c_char,
+) -> CwmpStatus {
+    guard(|| {
+        let envelope = unsafe { envelope_mut(fault) }?;
+        let name = unsafe { read_str(name) }?;
+        check_length("ParameterName", name, facets::PARAMETER_NAME)?;
+        let (fault_code, fault_string) =
+            fault_of(fault_code, unsafe { read_optional_str(fault_string) }?)?;
+        let fault = envelope
+            .fault_mut()
+            .ok_or_else(|| fail(CwmpStatus::WrongKind, "the envelope is not a fault"))?;
+        fault
+            .set_parameter_values_faults
+            .push(SetParameterValuesFault {
+                parameter_name: name.to_string(),
+                fault_code,
+                fault_string,
+            });
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::envelope::*;
+    use crate::parameters::*;
+    use std::ffi::{CStr, c_int};
+    use std::ptr;
+
+    const SET_PARAMETER_VALUES: &[u8] = br#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:soapenc="http://schemas.xmlsoap.org/soap/encoding/" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><soap:Header><cwmp:ID soap:mustUnderstand="1">42</cwmp:ID></soap:Header><soap:Body><cwmp:SetParameterValues><ParameterList soapenc:arrayType="cwmp:ParameterValueStruct[1]"><ParameterValueStruct><Name>Device.WiFi.SSID.1.SSID</Name><Value xsi:type="xsd:string">home</Value></ParameterValueStruct></ParameterList><ParameterKey>key</ParameterKey></cwmp:SetParameterValues></soap:Body></soap:Envelope>"#;
+
+    fn text(read: impl Fn(*mut c_char, *mut usize) -> CwmpStatus) -> String {
+        let mut len = 0;
+        assert_eq!(read(ptr::null_mut(), &mut len), CwmpStatus::BufferTooSmall);
+        let mut buf = vec![0u8; len];
+        assert_eq!(read(buf.as_mut_ptr().cast(), &mut len), CwmpStatus::Ok);
+        buf.truncate(len);
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn answer_set_parameter_values() {
+        unsafe {
+            let mut request = ptr::null_mut();
+            let status = cwmp_envelope_parse(
+                SET_PARAMETER_VALUES.as_ptr(),
+                SET_PARAMETER_VALUES.len(),
+                &mut request,
+            );
+            assert_eq!(status, CwmpStatus::Ok);
+
+            let mut kind = CwmpRpcKind::Fault;
+            assert_eq!(cwmp_envelope_rpc_kind(request, &mut kind), CwmpStatus::Ok);
+            assert_eq!(kind, CwmpRpcKind::SetParameterValues);
+            let mut count = 0;
+            assert_eq!(
+                cwmp_envelope_parameter_count(request, &mut count),
+                CwmpStatus::Ok
+            );
+            assert_eq!(count, 1);
+            assert_eq!(
+                text(|buf, len| cwmp_envelope_parameter_name(request, 0, buf, len)),
+                "Device.WiFi.SSID.1.SSID"
+            );
+            assert_eq!(
+                text(|buf, len| cwmp_envelope_parameter_type(request, 0, buf, len)),
+                "string"
+            );
+            assert_eq!(
+                text(|buf, len| cwmp_envelope_parameter_key(request, buf, len)),
+                "key"
+            );
+            let mut len = 64;
+            let mut buf = [0u8; 64];
+            assert_eq!(
+                cwmp_envelope_parameter_value(request, 1, buf.as_mut_ptr().cast(), &mut len),
+                CwmpStatus::OutOfRange
+            );
+
+            let mut fault = ptr::null_mut();
+            assert_eq!(
+                cwmp_fault_new(request, 9003, ptr::null(), &mut fault),
+                CwmpStatus::Ok
+            );
+            assert_eq!(
+                cwmp_fault_push_parameter(
+                    fault,
+                    c"Device.WiFi.SSID.1.SSID".as_ptr(),
+                    9007,
+                    ptr::null()
+                ),
+                CwmpStatus::Ok
+            );
+            assert_eq!(
+                cwmp_fault_new(request, 1234, ptr::null(), &mut ptr::null_mut()),
+                CwmpStatus::InvalidArgument
+            );
+            let xml = text(|buf, len| cwmp_envelope_serialize(fault, buf.cast(), len));
+            assert!(xml.contains(r#"<cwmp:ID soap:mustUnderstand="1">42</cwmp:ID>"#));
+            assert!(xml.contains("<FaultCode>9007</FaultCode>"));
+
+            let mut response = ptr::null_mut();
+            assert_eq!(
+                cwmp_set_parameter_values_response_new(request, 0, &mut response),
+                CwmpStatus::Ok
+            );
+            assert_eq!(
+                cwmp_get_parameter_values_response_push(
+                    response,
+                    c"Device.WiFi.SSID.1.SSID".as_ptr(),
+                    c"home".as_ptr(),
+                    ptr::null()
+                ),
+                CwmpStatus::WrongKind
+            );
+
+            cwmp_envelope_free(request);
+            cwmp_envelope_free(fault);
+            cwmp_envelope_free(response);
+        }
+    }
+
+    #[test]
+    fn get_parameter_values_response() {
+        unsafe {
+            let mut request = ptr::null_mut();
+            cwmp_envelope_parse(
+                SET_PARAMETER_VALUES.as_ptr(),
+                SET_PARAMETER_VALUES.len(),
+                &mut request,
+            );
+            let mut response = ptr::null_mut();
+            assert_eq!(
+                cwmp_get_parameter_values_response_new(request, &mut response),
+                CwmpStatus::Ok
+            );
+            let push = |name: &CStr, value: &CStr, type_: *const c_char| {
+                cwmp_get_parameter_values_response_push(
+                    response,
+                    name.as_ptr(),
+                    value.as_ptr(),
+                    type_,
+                )
+            };
+            assert_eq!(
+                push(c"Device.DeviceInfo.UpTime", c"42", c"unsignedInt".as_ptr()),
+                CwmpStatus::Ok
+            );
+            assert_eq!(
+                push(c"Device.DeviceInfo.UpTime", c"42", c"uint".as_ptr()),
+                CwmpStatus::InvalidArgument
+            );
+            assert!(text(cwmp_last_error_text).contains("uint"));
+            assert_eq!(
+                text(|buf, len| cwmp_envelope_parameter_type(response, 0, buf, len)),
+                "unsignedInt"
+            );
+            assert_eq!(text(|buf, len| cwmp_envelope_id(response, buf, len)), "42");
+
+            cwmp_envelope_free(request);
+            cwmp_envelope_free(response);
+        }
+    }
+
+    #[test]
+    fn status_messages() {
+        let message = |status| unsafe { CStr::from_ptr(crate::cwmp_status_message(status)) };
+        assert_eq!(
+            message(CwmpStatus::BufferTooSmall as c_int),
+            c"buffer too small"
+        );
+        assert_eq!(message(-1), c"unknown status");
+        assert_eq!(message(42), c"unknown status");
+    }
+
+    fn cwmp_last_error_text(buf: *mut c_char, len: *mut usize) -> CwmpStatus {
+        unsafe { crate::cwmp_last_error(buf, len) }
+    }
+}
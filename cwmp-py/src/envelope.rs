@@ -0,0 +1,186 @@
+//! The `Envelope` class and the cwmp Fault.
+use crate::error;
+use crate::rpc::{rpc_from_py, rpc_to_py};
+use cwmp_typed::cwmp::CwmpVersion;
+use cwmp_typed::cwmp::fault::suggested_fault_string;
+use cwmp_typed::envelope::{self, SerializerProfile};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+classes! {
+    /// A rejected parameter of a SetParameterValues.
+    SetParameterValuesFault {
+        parameter_name: String,
+        fault_code: u32,
+        fault_string: String,
+    }
+}
+
+/// A cwmp Fault, sent as a soap Fault.
+#[pyclass(module = "cwmp", eq, get_all, set_all)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fault {
+    pub fault_code: u32,
+    pub fault_string: String,
+    pub set_parameter_values_faults: Vec<SetParameterValuesFault>,
+}
+
+#[pymethods]
+impl Fault {
+    /// `fault_string` defaults to the string the specification suggests, empty
+    /// for a vendor code from 9800 to 9899.
+    #[new]
+    #[pyo3(signature = (fault_code, fault_string = None, set_parameter_values_faults = Vec::new()))]
+    fn new(
+        fault_code: u32,
+        fault_string: Option<String>,
+        set_parameter_values_faults: Vec<SetParameterValuesFault>,
+    ) -> PyResult<Self> {
+        let fault_string = match fault_string {
+            Some(fault_string) => fault_string,
+            None => suggested_fault_string(fault_code)
+                .map_err(error)?
+                .to_string(),
+        };
+        Ok(Self {
+            fault_code,
+            fault_string,
+            set_parameter_values_faults,
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl TryFrom<&Fault> for envelope::Fault {
+    type Error = PyErr;
+
+    fn try_from(fault: &Fault) -> Result<Self, Self::Error> {
+        serde_json::to_value(fault)
+            .and_then(serde_json::from_value)
+            .map_err(error)
+    }
+}
+
+impl TryFrom<&envelope::Fault> for Fault {
+    type Error = PyErr;
+
+    fn try_from(fault: &envelope::Fault) -> Result<Self, Self::Error> {
+        serde_json::to_value(fault)
+            .and_then(serde_json::from_value)
+            .map_err(error)
+    }
+}
+
+fn parse_version(version: &str) -> PyResult<CwmpVersion> {
+    serde_json::from_value(Value::from(version)).map_err(error)
+}
+
+/// A soap envelope carrying an rpc or a `Fault`.
+#[pyclass(module = "cwmp")]
+pub struct Envelope(envelope::Envelope);
+
+#[pymethods]
+impl Envelope {
+    /// An envelope for `body`, an rpc object or a `Fault`, checked against
+    /// the schema facets.
+    #[new]
+    #[pyo3(signature = (body, id = None, version = "1.0"))]
+    fn new(body: &Bound<'_, PyAny>, id: Option<String>, version: &str) -> PyResult<Self> {
+        let mut envelope = match body.downcast::<Fault>() {
+            Ok(fault) => envelope::Envelope::from((
+                parse_version(version)?,
+                envelope::Fault::try_from(&*fault.borrow())?,
+            )),
+            Err(_) => envelope::Envelope::from(rpc_from_py(body, version)?),
+        };
+        if let Some(id) = id {
+            envelope.set_id(id);
+        }
+        Ok(Self(envelope))
+    }
+
+    #[staticmethod]
+    fn parse(xml: &[u8]) -> PyResult<Self> {
+        envelope::Envelope::parse(xml).map(Self).map_err(error)
+    }
+
+    /// `"1.0"`, `"1.1"` or `"1.2"`.
+    #[getter]
+    fn version(&self) -> PyResult<String> {
+        match serde_json::to_value(self.0.version()).map_err(error)? {
+            Value::String(version) => Ok(version),
+            _ => unreachable!("a cwmp version is serialized as a string"),
+        }
+    }
+
+    /// The `cwmp:ID` header.
+    #[getter]
+    fn id(&self) -> Option<&str> {
+        self.0.id()
+    }
+
+    #[setter]
+    fn set_id(&mut self, id: String) {
+        self.0.set_id(id);
+    }
+
+    /// A copy of the rpc, None for a fault.
+    #[getter]
+    fn rpc(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        self.0.rpc().map(|rpc| rpc_to_py(py, rpc)).transpose()
+    }
+
+    /// A copy of the fault, None for an rpc.
+    #[getter]
+    fn fault(&self) -> PyResult<Option<Fault>> {
+        self.0.fault().map(Fault::try_from).transpose()
+    }
+
+    /// The envelope as xml, indented for reading when `pretty`.
+    #[pyo3(signature = (pretty = false))]
+    fn to_xml<'py>(&self, py: Python<'py>, pretty: bool) -> PyResult<Bound<'py, PyBytes>> {
+        let profile = match pretty {
+            true => SerializerProfile::pretty(),
+            false => SerializerProfile::default(),
+        };
+        let xml = self.0.to_xml(&profile).map_err(error)?;
+        Ok(PyBytes::new(py, &xml))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        let body = match self.0.rpc() {
+            Some(rpc) => rpc.method().name().to_string(),
+            None => "Fault".to_string(),
+        };
+        Ok(format!(
+            "Envelope(version={:?}, id={:?}, body={body})",
+            self.version()?,
+            self.id()
+        ))
+    }
+}
+
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Envelope>()?;
+    m.add_class::<Fault>()?;
+    m.add_class::<SetParameterValuesFault>()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fault_strings() {
+        let fault = Fault::new(9001, None, Vec::new()).unwrap();
+        assert_eq!(fault.fault_string, "Request denied (no reason specified)");
+        // vendor codes have no suggested string, as in cwmp_fault_new
+        let fault = Fault::new(9850, None, Vec::new()).unwrap();
+        assert_eq!(fault.fault_string, "");
+    }
+}
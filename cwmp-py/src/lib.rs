@@ -0,0 +1,69 @@
+//! Python bindings for cwmp-typed, built with maturin as the `cwmp` module.
+//!
+//! ```python
+//! import cwmp
+//!
+//! request = cwmp.Envelope.parse(body)
+//! if isinstance(request.rpc, cwmp.SetParameterValues):
+//!     reply = cwmp.Envelope(cwmp.SetParameterValuesResponse(0), id=request.id)
+//!     send(reply.to_xml())
+//! ```
+//!
+//! Each `RpcMethod` has a class with the fields of its serde representation,
+//! refer `cwmp_typed::cwmp::repr`. Building an envelope goes through that
+//! representation, so it checks the same facets as parsing. Attributes are
+//! copied on access: a list has to be assigned back after changing it.
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use std::fmt;
+
+/// Declares plain classes: a constructor taking the fields, with their
+/// defaults, and a `__repr__`.
+macro_rules! classes {
+    ($($(#[$attr:meta])* $name:ident { $($field:ident: $ty:ty $(= $default:expr)?),* $(,)? })+) => {
+        $(
+            #[pyclass(module = "cwmp", eq, get_all, set_all)]
+            #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+            $(#[$attr])*
+            pub struct $name {
+                $(pub $field: $ty,)*
+            }
+
+            #[pymethods]
+            impl $name {
+                #[new]
+                #[allow(clippy::too_many_arguments)]
+                #[pyo3(signature = ($($field $(= $default)?),*))]
+                fn new($($field: $ty),*) -> Self {
+                    Self { $($field),* }
+                }
+
+                fn __repr__(&self) -> String {
+                    format!("{self:?}")
+                }
+            }
+        )+
+    };
+}
+
+mod envelope;
+mod rpc;
+
+create_exception!(
+    cwmp,
+    CwmpError,
+    PyException,
+    "An envelope or rpc rejected by the codec."
+);
+
+pub(crate) fn error(e: impl fmt::Display) -> PyErr {
+    CwmpError::new_err(e.to_string())
+}
+
+#[pymodule]
+fn cwmp(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("CwmpError", m.py().get_type::<CwmpError>())?;
+    envelope::register(m)?;
+    rpc::register(m)
+}
@@ -0,0 +1,359 @@
+//! A class per `RpcMethod` variant, named after it.
+use crate::error;
+use cwmp_typed::cwmp::rpc::Rpc;
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+const UNKNOWN_TIME: &str = "0001-01-01T00:00:00Z";
+
+/// A ParameterValueStruct, `type` is the xsi:type without the xsd prefix.
+#[pyclass(module = "cwmp", eq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "ParameterValueRepr", into = "ParameterValueRepr")]
+pub struct ParameterValue {
+    #[pyo3(get, set)]
+    pub name: String,
+    #[pyo3(get, set)]
+    pub value: String,
+    #[pyo3(get, set, name = "type")]
+    pub type_: Option<String>,
+}
+
+#[pymethods]
+impl ParameterValue {
+    #[new]
+    #[pyo3(signature = (name, value, r#type = Some("string".to_string())))]
+    fn new(name: String, value: String, r#type: Option<String>) -> Self {
+        Self {
+            name,
+            value,
+            type_: r#type,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ParameterValueRepr {
+    name: String,
+    value: ValueRepr,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ValueRepr {
+    #[serde(rename = "type")]
+    type_: Option<TypeRepr>,
+    value: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum TypeRepr {
+    // the namespace is always xsd for the types cwmp-typed qualifies
+    Qualified {
+        #[serde(rename = "type")]
+        type_: String,
+    },
+    Unqualified(String),
+}
+
+impl From<ParameterValueRepr> for ParameterValue {
+    fn from(repr: ParameterValueRepr) -> Self {
+        Self {
+            name: repr.name,
+            value: repr.value.value,
+            type_: repr.value.type_.map(|type_| match type_ {
+                TypeRepr::Qualified { type_ } | TypeRepr::Unqualified(type_) => type_,
+            }),
+        }
+    }
+}
+
+impl From<ParameterValue> for ParameterValueRepr {
+    fn from(parameter: ParameterValue) -> Self {
+        Self {
+            name: parameter.name,
+            value: ValueRepr {
+                type_: parameter.type_.map(TypeRepr::Unqualified),
+                value: parameter.value,
+            },
+        }
+    }
+}
+
+classes! {
+    /// A ParameterInfoStruct of a GetParameterNamesResponse.
+    ParameterInfo { name: String, writable: bool }
+
+    /// A ParameterAttributeStruct of a GetParameterAttributesResponse.
+    ParameterAttribute {
+        name: String,
+        notification: u8 = 0,
+        access_list: Vec<String> = Vec::new(),
+    }
+
+    /// A SetParameterAttributesStruct, `name` None for every parameter.
+    SetParameterAttribute {
+        name: Option<String>,
+        notification_change: bool = false,
+        notification: u8 = 0,
+        access_list_change: bool = false,
+        access_list: Vec<String> = Vec::new(),
+    }
+
+    /// The DeviceIdStruct of an Inform.
+    DeviceId {
+        manufacturer: String,
+        oui: String,
+        product_class: String = String::new(),
+        serial_number: String = String::new(),
+    }
+
+    /// An EventStruct of an Inform, `event_code` like `"1 BOOT"`.
+    EventStruct {
+        event_code: String,
+        command_key: String = String::new(),
+    }
+}
+
+/// Declares the rpc classes along with the conversions from and to their
+/// serde representation.
+macro_rules! rpcs {
+    ($($(#[$attr:meta])* $name:ident { $($field:ident: $ty:ty $(= $default:expr)?),* $(,)? })+) => {
+        classes! {
+            $($(#[$attr])* $name { $($field: $ty $(= $default)?),* })+
+        }
+
+        pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+            m.add_class::<ParameterValue>()?;
+            m.add_class::<ParameterInfo>()?;
+            m.add_class::<ParameterAttribute>()?;
+            m.add_class::<SetParameterAttribute>()?;
+            m.add_class::<DeviceId>()?;
+            m.add_class::<EventStruct>()?;
+            $(m.add_class::<$name>()?;)+
+            Ok(())
+        }
+
+        fn to_class(py: Python<'_>, method: &str, params: Value) -> PyResult<Py<PyAny>> {
+            match method {
+                $(stringify!($name) => {
+                    let rpc: $name = serde_json::from_value(params).map_err(error)?;
+                    Ok(Py::new(py, rpc)?.into_any())
+                })+
+                other => Err(error(format!("{other} has no class"))),
+            }
+        }
+
+        fn from_class(rpc: &Bound<'_, PyAny>) -> PyResult<(&'static str, Value)> {
+            $(if let Ok(rpc) = rpc.downcast::<$name>() {
+                let params = serde_json::to_value(&*rpc.borrow()).map_err(error)?;
+                return Ok((stringify!($name), params));
+            })+
+            Err(PyTypeError::new_err(format!(
+                "expected an rpc, got {}",
+                rpc.get_type().name()?
+            )))
+        }
+    };
+}
+
+rpcs! {
+    GetRPCMethods {}
+
+    /// `parameter_path` None for the whole data model.
+    GetParameterNames {
+        parameter_path: Option<String> = None,
+        next_level: bool = false,
+    }
+
+    SetParameterValues {
+        parameter_list: Vec<ParameterValue>,
+        parameter_key: String = String::new(),
+    }
+
+    GetParameterValues { parameter_names: Vec<String> }
+
+    SetParameterAttributes { parameter_list: Vec<SetParameterAttribute> }
+
+    #[serde(transparent)]
+    GetParameterAttributes { parameter_names: Vec<String> }
+
+    AddObject {
+        object_name: String,
+        parameter_key: String = String::new(),
+    }
+
+    DeleteObject {
+        object_name: String,
+        parameter_key: String = String::new(),
+    }
+
+    Reboot { command_key: String = String::new() }
+
+    FactoryReset {}
+
+    Download {
+        file_type: String,
+        url: String,
+        command_key: String = String::new(),
+        username: String = String::new(),
+        password: String = String::new(),
+        file_size: u32 = 0,
+        target_file_name: String = String::new(),
+        delay_seconds: u32 = 0,
+        success_url: String = String::new(),
+        failure_url: String = String::new(),
+    }
+
+    GetRPCMethodsResponse { method_list: Vec<String> }
+
+    GetParameterNamesResponse { parameter_list: Vec<ParameterInfo> }
+
+    SetParameterValuesResponse { status: u8 = 0 }
+
+    GetParameterValuesResponse { parameter_list: Vec<ParameterValue> }
+
+    SetParameterAttributesResponse {}
+
+    GetParameterAttributesResponse { parameter_list: Vec<ParameterAttribute> }
+
+    AddObjectResponse {
+        instance_number: u32,
+        status: u8 = 0,
+    }
+
+    DeleteObjectResponse { status: u8 = 0 }
+
+    RebootResponse {}
+
+    FactoryResetResponse {}
+
+    /// The times are `xs:dateTime` strings, unknown by default.
+    DownloadResponse {
+        status: u8,
+        start_time: String = UNKNOWN_TIME.to_string(),
+        complete_time: String = UNKNOWN_TIME.to_string(),
+    }
+
+    /// `current_time` is an `xs:dateTime` string, unknown by default.
+    Inform {
+        device_id: DeviceId,
+        event: Vec<EventStruct>,
+        max_envelopes: u32 = 1,
+        current_time: String = UNKNOWN_TIME.to_string(),
+        retry_count: u32 = 0,
+        parameter_list: Vec<ParameterValue> = Vec::new(),
+    }
+
+    InformResponse { max_envelopes: u32 = 1 }
+}
+
+/// Splits the serde representation of `rpc` into its method and params, an
+/// empty object for rpcs without arguments.
+fn split(rpc: &Rpc) -> Result<(String, Value), serde_json::Error> {
+    let mut repr = match serde_json::to_value(rpc)? {
+        Value::Object(repr) => repr,
+        _ => unreachable!("an rpc is serialized as an object"),
+    };
+    let method = match repr.remove("method") {
+        Some(Value::String(method)) => method,
+        _ => unreachable!("an rpc is serialized with its method"),
+    };
+    let params = repr
+        .remove("params")
+        .unwrap_or_else(|| Value::Object(Map::new()));
+    Ok((method, params))
+}
+
+/// Joins a method and its params back into an rpc, checking the facets.
+fn join(method: &str, params: Value, version: &str) -> Result<Rpc, serde_json::Error> {
+    let mut repr = json!({ "version": version, "method": method });
+    if params != Value::Object(Map::new()) {
+        repr["params"] = params;
+    }
+    serde_json::from_value(repr)
+}
+
+pub(crate) fn rpc_to_py(py: Python<'_>, rpc: &Rpc) -> PyResult<Py<PyAny>> {
+    let (method, params) = split(rpc).map_err(error)?;
+    to_class(py, &method, params)
+}
+
+pub(crate) fn rpc_from_py(rpc: &Bound<'_, PyAny>, version: &str) -> PyResult<Rpc> {
+    let (method, params) = from_class(rpc)?;
+    join(method, params, version).map_err(error)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cwmp_typed::cwmp::rpc::RpcMethod;
+
+    #[test]
+    fn classes_follow_the_rpc_representation() {
+        let json = json!({
+            "version": "1.0",
+            "method": "SetParameterValues",
+            "params": {
+                "parameter_list": [{
+                    "name": "Device.WiFi.SSID.1.SSID",
+                    "value": {
+                        "type": { "namespace": "http://www.w3.org/2001/XMLSchema", "prefix": "xsd", "type": "string" },
+                        "value": "home"
+                    }
+                }],
+                "parameter_key": "key"
+            }
+        });
+        let rpc: Rpc = serde_json::from_value(json).unwrap();
+        let (method, params) = split(&rpc).unwrap();
+        assert_eq!(method, "SetParameterValues");
+        let class: SetParameterValues = serde_json::from_value(params).unwrap();
+        assert_eq!(
+            class.parameter_list,
+            [ParameterValue::new(
+                "Device.WiFi.SSID.1.SSID".to_string(),
+                "home".to_string(),
+                Some("string".to_string())
+            )]
+        );
+
+        let rpc = join(&method, serde_json::to_value(&class).unwrap(), "1.0").unwrap();
+        assert!(matches!(rpc.method(), RpcMethod::SetParameterValues(_)));
+
+        let (method, params) = split(&join("FactoryReset", json!({}), "1.2").unwrap()).unwrap();
+        assert_eq!(method, "FactoryReset");
+        assert_eq!(params, json!({}));
+
+        let class = GetParameterAttributes::new(vec!["Device.DeviceInfo.".to_string()]);
+        let rpc = join(
+            "GetParameterAttributes",
+            serde_json::to_value(&class).unwrap(),
+            "1.0",
+        )
+        .unwrap();
+        assert!(matches!(rpc.method(), RpcMethod::GetParameterAttributes(_)));
+    }
+
+    #[test]
+    fn classes_are_validated() {
+        let class = Reboot::new("k".repeat(33));
+        assert!(join("Reboot", serde_json::to_value(&class).unwrap(), "1.0").is_err());
+        let class = GetParameterValues::new(vec!["Device.*".to_string()]);
+        assert!(
+            join(
+                "GetParameterValues",
+                serde_json::to_value(&class).unwrap(),
+                "1.0"
+            )
+            .is_err()
+        );
+        assert!(join("FactoryReset", json!({}), "2.0").is_err());
+    }
+}
@@ -0,0 +1,173 @@
+//! Instance alias based addressing, added in CWMP 1.4 (TR-069 A.2.2.1 and
+//! A.3.2.6). `Device.IP.Interface.[wan].Enable` refers to the instance of
+//! `Device.IP.Interface.` whose `Alias` parameter is `wan`.
+use super::facets;
+use super::path::{ParameterPath, Segment};
+use super::types::AddObject;
+use crate::error::Error;
+use std::collections::HashMap;
+
+/// Checks an alias value: at most 64 characters, starting with a letter and
+/// without the characters reserved by the path syntax.
+pub fn validate_alias(alias: &str) -> Result<(), Error> {
+    facets::max_length("Alias", alias, facets::ALIAS)?;
+    if !alias.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Err(Error::InvalidParameterName(format!(
+            "Alias '{alias}' must start with a letter."
+        )));
+    }
+    if alias.contains(['.', '[', ']', '*']) {
+        return Err(Error::InvalidParameterName(format!(
+            "Alias '{alias}' contains a reserved character."
+        )));
+    }
+    Ok(())
+}
+
+impl AddObject {
+    /// The alias requested for the new instance, for an alias based
+    /// `AddObject` like `Device.IP.Interface.[wan].`.
+    pub fn alias(&self) -> Result<Option<String>, Error> {
+        let path: ParameterPath = self.object_name.parse()?;
+        Ok(match path.segments().last() {
+            Some(Segment::Alias(alias)) if path.is_object() => Some(alias.clone()),
+            _ => None,
+        })
+    }
+}
+
+/// Known instances of multi-instance objects by alias, keyed by the table
+/// path with instance numbers (`Device.IP.Interface.`).
+#[derive(Debug, Clone, Default)]
+pub struct AliasMap {
+    tables: HashMap<ParameterPath, HashMap<String, u32>>,
+}
+
+impl AliasMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, table: ParameterPath, alias: String, instance: u32) {
+        self.tables
+            .entry(table)
+            .or_default()
+            .insert(alias, instance);
+    }
+
+    /// Records an `<table>.<instance>.Alias` parameter value, as read from a
+    /// `GetParameterValuesResponse`. Returns `false` for any other parameter.
+    pub fn insert_parameter(&mut self, name: &ParameterPath, value: &str) -> bool {
+        let [table @ .., Segment::Instance(instance), Segment::Name(leaf)] = name.segments() else {
+            return false;
+        };
+        if leaf != "Alias" || name.is_object() || value.is_empty() {
+            return false;
+        }
+        let table = ParameterPath::from_segments(table.to_vec(), true);
+        self.insert(table, value.to_string(), *instance);
+        true
+    }
+
+    pub fn instance(&self, table: &ParameterPath, alias: &str) -> Option<u32> {
+        self.tables.get(table)?.get(alias).copied()
+    }
+}
+
+impl ParameterPath {
+    /// Replaces every alias segment with the instance number it refers to.
+    pub fn resolve_aliases(&self, aliases: &AliasMap) -> Result<ParameterPath, Error> {
+        let mut resolved: Vec<Segment> = Vec::with_capacity(self.segments().len());
+        for segment in self.segments() {
+            let segment = match segment {
+                Segment::Alias(alias) => {
+                    let table = ParameterPath::from_segments(resolved.clone(), true);
+                    let instance = aliases.instance(&table, alias).ok_or_else(|| {
+                        Error::InvalidParameterName(format!(
+                            "'{self}': no instance of '{table}' has the alias '{alias}'."
+                        ))
+                    })?;
+                    Segment::Instance(instance)
+                }
+                other => other.clone(),
+            };
+            resolved.push(segment);
+        }
+        Ok(ParameterPath::from_segments(resolved, self.is_object()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cwmp::types::ParameterName;
+
+    fn path(input: &str) -> ParameterPath {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn alias_values() {
+        assert!(validate_alias("cpe-1").is_ok());
+        assert!(validate_alias("wan").is_ok());
+        assert!(validate_alias("1wan").is_err());
+        assert!(validate_alias("wan.1").is_err());
+        assert!(validate_alias(&"a".repeat(65)).is_err());
+        assert!(
+            "Device.IP.Interface.[1wan]."
+                .parse::<ParameterPath>()
+                .is_err()
+        );
+
+        // rpc parameter names follow the same rules
+        let name = |input: &str| ParameterName::try_from(input.to_string());
+        assert!(matches!(
+            name("Device.IP.Interface.[wan].Enable"),
+            Ok(ParameterName::Full(_))
+        ));
+        assert!(name("Device.IP.Interface.[1wan].Enable").is_err());
+        assert!(name("Device.IP.Interface.[wan.Enable").is_err());
+    }
+
+    #[test]
+    fn add_object_alias() {
+        let add = |object_name: &str| AddObject {
+            object_name: object_name.to_string(),
+            parameter_key: String::new(),
+        };
+        assert_eq!(
+            add("Device.IP.Interface.[wan].").alias().unwrap(),
+            Some("wan".to_string())
+        );
+        assert_eq!(add("Device.IP.Interface.").alias().unwrap(), None);
+        assert_eq!(
+            add("Device.IP.Interface.[wan].IPv4Address.")
+                .alias()
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn resolve_aliases() {
+        let mut aliases = AliasMap::new();
+        assert!(aliases.insert_parameter(&path("Device.IP.Interface.3.Alias"), "wan"));
+        assert!(aliases.insert_parameter(
+            &path("Device.IP.Interface.3.IPv4Address.2.Alias"),
+            "primary"
+        ));
+        assert!(!aliases.insert_parameter(&path("Device.IP.Interface.3.Name"), "eth0"));
+
+        assert_eq!(
+            path("Device.IP.Interface.[wan].IPv4Address.[primary].IPAddress")
+                .resolve_aliases(&aliases)
+                .unwrap(),
+            path("Device.IP.Interface.3.IPv4Address.2.IPAddress")
+        );
+        assert!(
+            path("Device.IP.Interface.[lan].Enable")
+                .resolve_aliases(&aliases)
+                .is_err()
+        );
+    }
+}
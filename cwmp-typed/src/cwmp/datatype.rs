@@ -0,0 +1,442 @@
+//! TR-106 named data types (`tr-106-types.xml`) and data types with facets in
+//! the notation of the data model reports:
+//!
+//! - `MACAddress`, `IPv6Prefix`, `unsignedInt`: a named or primitive type;
+//! - `string(:64)`, `hexBinary(6:6)`: a length in characters (bytes for
+//!   `hexBinary`) between min and max, either may be left out;
+//! - `unsignedInt[1:65535]`, `int[-200:0]`: an inclusive range;
+//! - `IPv4Address[]`: a comma separated list, followed by `(:1024)` for the
+//!   length of the whole list and `[1:4]` for the number of items.
+//!
+//! Invalid values are [`Error::InvalidValue`], fault 9007.
+use super::alias::validate_alias;
+use super::value::{ParameterType, TypedValue, XsdType};
+use crate::error::Error;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NamedType {
+    IpAddress,
+    Ipv4Address,
+    Ipv6Address,
+    IpPrefix,
+    Ipv4Prefix,
+    Ipv6Prefix,
+    MacAddress,
+    Alias,
+    Uuid,
+    Url,
+}
+
+fn invalid(value: &str, type_: impl fmt::Display) -> Error {
+    Error::InvalidValue(format!("'{value}' is not a valid {type_}."))
+}
+
+fn prefix<A: FromStr>(value: &str, max: u8) -> bool {
+    value.split_once('/').is_some_and(|(address, length)| {
+        address.parse::<A>().is_ok()
+            && !length.starts_with('+')
+            && length.parse::<u8>().is_ok_and(|length| length <= max)
+    })
+}
+
+fn is_mac(value: &str) -> bool {
+    value.len() == 17
+        && value.split(':').count() == 6
+        && value
+            .split(':')
+            .all(|part| part.len() == 2 && part.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+fn is_uuid(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    groups.iter().map(|group| group.len()).eq([8, 4, 4, 4, 12])
+        && groups
+            .iter()
+            .all(|group| group.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+// an absolute URI, refer RFC 3986 section 3
+fn is_url(value: &str) -> bool {
+    let Some((scheme, rest)) = value.split_once(':') else {
+        return false;
+    };
+    let mut scheme_chars = scheme.chars();
+    scheme_chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && scheme_chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        && !rest.is_empty()
+        && !rest.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+impl NamedType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::IpAddress => "IPAddress",
+            Self::Ipv4Address => "IPv4Address",
+            Self::Ipv6Address => "IPv6Address",
+            Self::IpPrefix => "IPPrefix",
+            Self::Ipv4Prefix => "IPv4Prefix",
+            Self::Ipv6Prefix => "IPv6Prefix",
+            Self::MacAddress => "MACAddress",
+            Self::Alias => "Alias",
+            Self::Uuid => "UUID",
+            Self::Url => "URL",
+        }
+    }
+
+    pub fn max_length(&self) -> usize {
+        match self {
+            Self::IpAddress | Self::Ipv6Address => 45,
+            Self::Ipv4Address => 15,
+            Self::IpPrefix | Self::Ipv6Prefix => 49,
+            Self::Ipv4Prefix => 18,
+            Self::MacAddress => 17,
+            Self::Alias => 64,
+            Self::Uuid => 36,
+            Self::Url => 2048,
+        }
+    }
+
+    /// Checks a value of the type. The address and prefix types may be
+    /// empty, meaning not set.
+    pub fn validate(&self, value: &str) -> Result<(), Error> {
+        if value.chars().count() > self.max_length() {
+            return Err(Error::InvalidValue(format!(
+                "'{value}' is longer than the {} characters of a {self}.",
+                self.max_length()
+            )));
+        }
+        let valid = match self {
+            Self::Alias => {
+                return validate_alias(value).map_err(|e| Error::InvalidValue(e.to_string()));
+            }
+            Self::Uuid => is_uuid(value),
+            Self::Url => is_url(value),
+            _ if value.is_empty() => true,
+            Self::IpAddress => value.parse::<IpAddr>().is_ok(),
+            Self::Ipv4Address => value.parse::<Ipv4Addr>().is_ok(),
+            Self::Ipv6Address => value.parse::<Ipv6Addr>().is_ok(),
+            Self::IpPrefix => prefix::<Ipv4Addr>(value, 32) || prefix::<Ipv6Addr>(value, 128),
+            Self::Ipv4Prefix => prefix::<Ipv4Addr>(value, 32),
+            Self::Ipv6Prefix => prefix::<Ipv6Addr>(value, 128),
+            Self::MacAddress => is_mac(value),
+        };
+        if !valid {
+            return Err(invalid(value, self));
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for NamedType {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(match name {
+            "IPAddress" => Self::IpAddress,
+            "IPv4Address" => Self::Ipv4Address,
+            "IPv6Address" => Self::Ipv6Address,
+            "IPPrefix" => Self::IpPrefix,
+            "IPv4Prefix" => Self::Ipv4Prefix,
+            "IPv6Prefix" => Self::Ipv6Prefix,
+            "MACAddress" => Self::MacAddress,
+            "Alias" => Self::Alias,
+            "UUID" => Self::Uuid,
+            "URL" => Self::Url,
+            _ => {
+                return Err(Error::InvalidValue(format!(
+                    "'{name}' is not a known data type."
+                )));
+            }
+        })
+    }
+}
+
+impl fmt::Display for NamedType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseType {
+    Primitive(XsdType),
+    Named(NamedType),
+}
+
+/// Inclusive bounds, either may be open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Bounds<T> {
+    pub min: Option<T>,
+    pub max: Option<T>,
+}
+
+impl<T: PartialOrd + Copy> Bounds<T> {
+    pub fn contains(&self, value: T) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Bounds<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(min) = &self.min {
+            write!(f, "{min}")?;
+        }
+        f.write_str(":")?;
+        if let Some(max) = &self.max {
+            write!(f, "{max}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListFacets {
+    /// Length of the whole list string.
+    pub length: Option<Bounds<usize>>,
+    pub items: Option<Bounds<usize>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataType {
+    pub base: BaseType,
+    pub length: Option<Bounds<usize>>,
+    pub range: Option<Bounds<i128>>,
+    pub list: Option<ListFacets>,
+}
+
+impl DataType {
+    pub fn validate(&self, value: &str) -> Result<(), Error> {
+        let Some(list) = &self.list else {
+            return self.validate_item(value);
+        };
+        if let Some(length) = list.length
+            && !length.contains(value.chars().count())
+        {
+            return Err(invalid(value, self));
+        }
+        let items: Vec<&str> = if value.is_empty() {
+            Vec::new()
+        } else {
+            value.split(',').map(str::trim).collect()
+        };
+        if let Some(count) = list.items
+            && !count.contains(items.len())
+        {
+            return Err(Error::InvalidValue(format!(
+                "'{value}' has {} items, expected [{count}].",
+                items.len()
+            )));
+        }
+        items
+            .into_iter()
+            .try_for_each(|item| self.validate_item(item))
+    }
+
+    fn validate_item(&self, value: &str) -> Result<(), Error> {
+        if let Some(length) = self.length {
+            let length_of = match self.base {
+                BaseType::Primitive(XsdType::HexBinary) => value.len() / 2,
+                _ => value.chars().count(),
+            };
+            if !length.contains(length_of) {
+                return Err(invalid(value, self));
+            }
+        }
+        let base = match self.base {
+            BaseType::Named(named) => return named.validate(value),
+            BaseType::Primitive(base) => base,
+        };
+        let number = match TypedValue::parse(value, &ParameterType::new(base))
+            .map_err(|_| invalid(value, self))?
+        {
+            TypedValue::Int(number) => i128::from(number),
+            TypedValue::UnsignedInt(number) => i128::from(number),
+            TypedValue::Long(number) => i128::from(number),
+            TypedValue::UnsignedLong(number) => i128::from(number),
+            _ => return Ok(()),
+        };
+        if self.range.is_some_and(|range| !range.contains(number)) {
+            return Err(invalid(value, self));
+        }
+        Ok(())
+    }
+}
+
+fn bounds<T: FromStr>(spec: &str, inner: &str) -> Result<Bounds<T>, Error> {
+    let invalid = || Error::InvalidValue(format!("'{spec}' is not a valid data type."));
+    let (min, max) = inner.split_once(':').ok_or_else(invalid)?;
+    let bound = |value: &str| {
+        if value.is_empty() {
+            Ok(None)
+        } else {
+            value.parse().map(Some).map_err(|_| invalid())
+        }
+    };
+    Ok(Bounds {
+        min: bound(min)?,
+        max: bound(max)?,
+    })
+}
+
+// the facet at the start of `rest`, `(1:64)` or `[1:4]`
+fn facet<'a>(rest: &mut &'a str, open: char, close: char) -> Option<&'a str> {
+    let inner = rest.strip_prefix(open)?;
+    let (inner, tail) = inner.split_once(close)?;
+    *rest = tail;
+    Some(inner)
+}
+
+impl FromStr for DataType {
+    type Err = Error;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let end = spec.find(['(', '[']).unwrap_or(spec.len());
+        let (name, mut rest) = spec.split_at(end);
+        let base = match XsdType::from_name(name).filter(|_| !name.contains(':')) {
+            Some(primitive) => BaseType::Primitive(primitive),
+            None => BaseType::Named(name.parse()?),
+        };
+
+        let length = facet(&mut rest, '(', ')')
+            .map(|inner| bounds(spec, inner))
+            .transpose()?;
+        // `[]` marks a list, anything else in brackets is a range
+        let range = if rest.starts_with("[]") {
+            None
+        } else {
+            facet(&mut rest, '[', ']')
+                .map(|inner| bounds(spec, inner))
+                .transpose()?
+        };
+        let numeric = matches!(
+            base,
+            BaseType::Primitive(
+                XsdType::Int | XsdType::UnsignedInt | XsdType::Long | XsdType::UnsignedLong
+            )
+        );
+        if range.is_some() && !numeric {
+            return Err(Error::InvalidValue(format!(
+                "'{spec}': ranges only apply to numbers."
+            )));
+        }
+
+        let list = match rest.strip_prefix("[]") {
+            Some(tail) => {
+                rest = tail;
+                Some(ListFacets {
+                    length: facet(&mut rest, '(', ')')
+                        .map(|inner| bounds(spec, inner))
+                        .transpose()?,
+                    items: facet(&mut rest, '[', ']')
+                        .map(|inner| bounds(spec, inner))
+                        .transpose()?,
+                })
+            }
+            None => None,
+        };
+        if !rest.is_empty() {
+            return Err(Error::InvalidValue(format!(
+                "'{spec}' is not a valid data type."
+            )));
+        }
+        Ok(Self {
+            base,
+            length,
+            range,
+            list,
+        })
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.base {
+            BaseType::Primitive(primitive) => f.write_str(primitive.name())?,
+            BaseType::Named(named) => f.write_str(named.name())?,
+        }
+        if let Some(length) = self.length {
+            write!(f, "({length})")?;
+        }
+        if let Some(range) = self.range {
+            write!(f, "[{range}]")?;
+        }
+        if let Some(list) = &self.list {
+            f.write_str("[]")?;
+            if let Some(length) = list.length {
+                write!(f, "({length})")?;
+            }
+            if let Some(items) = list.items {
+                write!(f, "[{items}]")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn validate(spec: &str, value: &str) -> Result<(), Error> {
+        spec.parse::<DataType>().unwrap().validate(value)
+    }
+
+    #[test]
+    fn named_types() {
+        assert!(validate("IPv4Address", "192.168.1.1").is_ok());
+        assert!(validate("IPv4Address", "").is_ok());
+        assert!(validate("IPv4Address", "192.168.1.256").is_err());
+        assert!(validate("IPv4Address", "::1").is_err());
+        assert!(validate("IPAddress", "fe80::1").is_ok());
+        assert!(validate("IPv6Prefix", "2001:db8::/32").is_ok());
+        assert!(validate("IPv6Prefix", "2001:db8::/129").is_err());
+        assert!(validate("IPv6Prefix", "2001:db8::").is_err());
+        assert!(validate("MACAddress", "00:D0:9e:01:02:03").is_ok());
+        assert!(validate("MACAddress", "00-D0-9E-01-02-03").is_err());
+        assert!(validate("Alias", "cpe-1").is_ok());
+        assert!(validate("Alias", "1st").is_err());
+        assert!(validate("UUID", "f81d4fae-7dec-11d0-a765-00a0c91e6bf6").is_ok());
+        assert!(validate("UUID", "f81d4fae7dec11d0a76500a0c91e6bf6").is_err());
+        assert!(validate("URL", "http://acs.example.com:7547/cwmp").is_ok());
+        assert!(validate("URL", "acs.example.com").is_err());
+
+        let error = validate("MACAddress", "nope").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid value: 'nope' is not a valid MACAddress."
+        );
+    }
+
+    #[test]
+    fn facets() {
+        assert!(validate("string(:64)", &"x".repeat(64)).is_ok());
+        assert!(validate("string(:64)", &"x".repeat(65)).is_err());
+        assert!(validate("hexBinary(3:3)", "00d09e").is_ok());
+        assert!(validate("unsignedInt[1:65535]", "7547").is_ok());
+        assert!(validate("unsignedInt[1:65535]", "0").is_err());
+        assert!(validate("unsignedInt[1:65535]", "x").is_err());
+        assert!(validate("int[-200:0]", "-40").is_ok());
+
+        assert!(validate("IPv4Address[]", "10.0.0.1, 10.0.0.2").is_ok());
+        assert!(validate("IPv4Address[]", "").is_ok());
+        assert!(validate("IPv4Address[][1:2]", "").is_err());
+        assert!(validate("IPv4Address[][1:2]", "10.0.0.1,10.0.0.2,10.0.0.3").is_err());
+        assert!(validate("string(:4)[](:8)", "abcd,efgh").is_err());
+        assert!(validate("unsignedInt[1:10][]", "1,5,11").is_err());
+
+        for spec in [
+            "string(:64)",
+            "unsignedInt[1:65535]",
+            "IPv4Address[](:256)[1:4]",
+            "string[]",
+        ] {
+            assert_eq!(spec.parse::<DataType>().unwrap().to_string(), spec);
+        }
+        for spec in ["Unknown", "string[1:2]", "string(:x)", "int[1:2", "xsd:int"] {
+            assert!(spec.parse::<DataType>().is_err(), "{spec}");
+        }
+    }
+}
@@ -0,0 +1,358 @@
+//! `xs:dateTime` values as CWMP uses them (TR-069 A.2.2, TR-106 3.2.1):
+//! `0001-01-01T00:00:00Z` is the unknown time and any other value before
+//! the year 1000 counts from CPE boot, `0001-01-01T00:00:00Z` being the boot.
+use crate::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32,
+    /// Minutes east of UTC, `None` when the CPE sent no timezone.
+    pub offset: Option<i16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CwmpDateTime {
+    Absolute(DateTime),
+    Unknown,
+    RelativeToBoot(Duration),
+}
+
+// days between 0000-03-01 based civil dates and 1970-01-01, refer
+// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i32, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = (year_of_era + era * 400 + i64::from(month <= 2)) as i32;
+    (year, month, day)
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+const BOOT_DAYS: i64 = -719_162; // 0001-01-01
+
+impl DateTime {
+    /// Seconds since 1970-01-01T00:00:00Z, `None` without a timezone.
+    pub fn unix_timestamp(&self) -> Option<i64> {
+        let days = days_from_civil(self.year, self.month, self.day);
+        let seconds = days * 86_400
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second);
+        Some(seconds - i64::from(self.offset?) * 60)
+    }
+
+    // the time elapsed since 0001-01-01T00:00:00, ignoring the timezone
+    fn since_boot(&self) -> Duration {
+        let days = days_from_civil(self.year, self.month, self.day) - BOOT_DAYS;
+        let seconds = days as u64 * 86_400
+            + u64::from(self.hour) * 3600
+            + u64::from(self.minute) * 60
+            + u64::from(self.second);
+        Duration::new(seconds, self.nanosecond)
+    }
+
+    fn from_boot(duration: Duration) -> Self {
+        let seconds = duration.as_secs();
+        let (year, month, day) = civil_from_days(BOOT_DAYS + (seconds / 86_400) as i64);
+        Self {
+            year,
+            month,
+            day,
+            hour: (seconds % 86_400 / 3600) as u8,
+            minute: (seconds % 3600 / 60) as u8,
+            second: (seconds % 60) as u8,
+            nanosecond: duration.subsec_nanos(),
+            offset: Some(0),
+        }
+    }
+}
+
+impl CwmpDateTime {
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown)
+    }
+}
+
+fn invalid(input: &str) -> Error {
+    Error::InvalidValue(format!("'{input}' is not a valid dateTime."))
+}
+
+fn number<T: FromStr>(input: &str, digits: &str, len: usize) -> Result<T, Error> {
+    if digits.len() != len || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid(input));
+    }
+    digits.parse().map_err(|_| invalid(input))
+}
+
+fn parse_offset(input: &str, zone: &str) -> Result<Option<i16>, Error> {
+    if zone.is_empty() {
+        return Ok(None);
+    }
+    if zone.eq_ignore_ascii_case("z") {
+        return Ok(Some(0));
+    }
+    // the zone is sliced by byte below
+    if !zone.is_ascii() {
+        return Err(invalid(input));
+    }
+    let (sign, zone) = match zone.split_at(1) {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return Err(invalid(input)),
+    };
+    // `hh:mm`, plus the `hhmm` and `hh` forms some CPEs send
+    let (hours, minutes) = match zone.len() {
+        5 if &zone[2..3] == ":" => (&zone[..2], &zone[3..]),
+        4 => zone.split_at(2),
+        2 => (zone, "00"),
+        _ => return Err(invalid(input)),
+    };
+    let hours: i16 = number(input, hours, 2)?;
+    let minutes: i16 = number(input, minutes, 2)?;
+    if hours > 14 || minutes > 59 {
+        return Err(invalid(input));
+    }
+    Ok(Some(sign * (hours * 60 + minutes)))
+}
+
+impl FromStr for CwmpDateTime {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let value = input.trim();
+        // some CPEs leave unknown times empty
+        if value.is_empty() {
+            return Ok(Self::Unknown);
+        }
+
+        let (date, time) = value
+            .split_once(['T', 't', ' '])
+            .ok_or_else(|| invalid(input))?;
+
+        let (year, rest) = date
+            .rsplit_once('-')
+            .and_then(|(ym, day)| {
+                let (year, month) = ym.rsplit_once('-')?;
+                Some((year, (month, day)))
+            })
+            .ok_or_else(|| invalid(input))?;
+        let (month, day) = rest;
+        if year.len() < 4 {
+            return Err(invalid(input));
+        }
+        let year: i32 = number(input, year, year.len())?;
+        let month: u8 = number(input, month, 2)?;
+        let day: u8 = number(input, day, 2)?;
+
+        let zone_start = time.find(['Z', 'z', '+', '-']).unwrap_or(time.len());
+        let (clock, zone) = time.split_at(zone_start);
+        let offset = parse_offset(input, zone)?;
+
+        let (clock, fraction) = match clock.split_once('.') {
+            Some((clock, fraction)) => (clock, Some(fraction)),
+            None => (clock, None),
+        };
+        let mut parts = clock.split(':');
+        let (Some(hour), Some(minute), Some(second), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid(input));
+        };
+        let hour: u8 = number(input, hour, 2)?;
+        let minute: u8 = number(input, minute, 2)?;
+        let second: u8 = number(input, second, 2)?;
+        let nanosecond = match fraction {
+            None => 0,
+            Some(fraction) => {
+                if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(invalid(input));
+                }
+                // precision beyond nanoseconds is dropped
+                let digits = &fraction[..fraction.len().min(9)];
+                digits.parse::<u32>().map_err(|_| invalid(input))?
+                    * 10u32.pow(9 - digits.len() as u32)
+            }
+        };
+
+        // there is no year 0, and the days before 0001-01-01 can't be since boot
+        if year == 0
+            || !(1..=12).contains(&month)
+            || day == 0
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return Err(invalid(input));
+        }
+
+        let date_time = DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond,
+            offset,
+        };
+
+        if year < 1000 {
+            let since_boot = date_time.since_boot();
+            return Ok(if since_boot.is_zero() {
+                Self::Unknown
+            } else {
+                Self::RelativeToBoot(since_boot)
+            });
+        }
+        Ok(Self::Absolute(date_time))
+    }
+}
+
+impl TryFrom<String> for CwmpDateTime {
+    type Error = Error;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        input.parse()
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )?;
+        if self.nanosecond > 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        match self.offset {
+            None => Ok(()),
+            Some(0) => f.write_str("Z"),
+            Some(offset) => {
+                let sign = if offset < 0 { '-' } else { '+' };
+                let offset = offset.unsigned_abs();
+                write!(f, "{sign}{:02}:{:02}", offset / 60, offset % 60)
+            }
+        }
+    }
+}
+
+/// Canonical `xs:dateTime`.
+impl fmt::Display for CwmpDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Absolute(date_time) => write!(f, "{date_time}"),
+            Self::Unknown => f.write_str("0001-01-01T00:00:00Z"),
+            Self::RelativeToBoot(duration) => write!(f, "{}", DateTime::from_boot(*duration)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(input: &str) -> CwmpDateTime {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn special_values() {
+        assert_eq!(parse("0001-01-01T00:00:00Z"), CwmpDateTime::Unknown);
+        assert_eq!(parse("0001-01-01T00:00:00"), CwmpDateTime::Unknown);
+        assert_eq!(parse(""), CwmpDateTime::Unknown);
+        assert_eq!(
+            parse("0001-01-02T00:01:30Z"),
+            CwmpDateTime::RelativeToBoot(Duration::from_secs(86_400 + 90))
+        );
+        assert_eq!(
+            CwmpDateTime::RelativeToBoot(Duration::from_secs(86_400 + 90)).to_string(),
+            "0001-01-02T00:01:30Z"
+        );
+        assert_eq!(CwmpDateTime::Unknown.to_string(), "0001-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn absolute_values() {
+        let CwmpDateTime::Absolute(date_time) = parse("2024-02-29T23:59:59.5+05:30") else {
+            panic!();
+        };
+        assert_eq!(date_time.offset, Some(330));
+        assert_eq!(date_time.nanosecond, 500_000_000);
+        assert_eq!(date_time.to_string(), "2024-02-29T23:59:59.5+05:30");
+
+        // forms seen from CPEs, written back canonically
+        assert_eq!(
+            parse("2024-01-02 03:04:05z").to_string(),
+            "2024-01-02T03:04:05Z"
+        );
+        assert_eq!(
+            parse("2024-01-02T03:04:05-0500").to_string(),
+            "2024-01-02T03:04:05-05:00"
+        );
+        assert_eq!(
+            parse("2024-01-02T03:04:05+02").to_string(),
+            "2024-01-02T03:04:05+02:00"
+        );
+        assert_eq!(
+            parse("2024-01-02T03:04:05").to_string(),
+            "2024-01-02T03:04:05"
+        );
+
+        let CwmpDateTime::Absolute(date_time) = parse("1970-01-01T01:00:00+01:00") else {
+            panic!();
+        };
+        assert_eq!(date_time.unix_timestamp(), Some(0));
+
+        for invalid in [
+            "2023-02-29T00:00:00Z",
+            "2024-13-01T00:00:00Z",
+            "2024-01-01T24:00:00Z",
+            "2024-01-01T23:59:60Z",
+            "0000-01-01T00:00:00Z",
+            "2024-01-01",
+            "2024-01-01T00:00Z",
+            "24-01-01T00:00:00Z",
+            "2024-01-01T00:00:00+5",
+            "2024-01-01T00:00:00+1é00",
+        ] {
+            assert!(invalid.parse::<CwmpDateTime>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! CPE identity from the Inform `DeviceId` (TR-069 A.3.3.1), and the
+//! `OUI-[ProductClass-]SerialNumber` keys ACSs usually store devices under.
+use super::facets;
+use crate::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceId {
+    pub(crate) manufacturer: String,
+    pub(crate) oui: String,
+    pub(crate) product_class: String,
+    pub(crate) serial_number: String,
+}
+
+/// Checks an organizationally unique identifier: six uppercase hex digits.
+pub fn validate_oui(oui: &str) -> Result<(), Error> {
+    if oui.len() != facets::OUI
+        || !oui
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'A'..=b'F').contains(&b))
+    {
+        return Err(Error::InvalidValue(format!(
+            "'{oui}' is not a valid OUI, expected six uppercase hex digits."
+        )));
+    }
+    Ok(())
+}
+
+// everything but the unreserved characters of RFC 3986, and `-` so the key
+// parts stay separable
+fn escape(value: &str, key: &mut String) {
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'_' | b'.' | b'~') {
+            key.push(b as char);
+        } else {
+            key.push_str(&format!("%{b:02X}"));
+        }
+    }
+}
+
+fn unescape(key: &str, part: &str) -> Result<String, Error> {
+    let invalid = || Error::InvalidValue(format!("'{key}' is not a valid device key."));
+    let mut bytes = Vec::with_capacity(part.len());
+    let mut rest = part.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = tail.get(..2).ok_or_else(invalid)?;
+            // from_str_radix would take a sign as well
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return Err(invalid());
+            }
+            let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+            bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+impl DeviceId {
+    pub fn new(
+        manufacturer: String,
+        oui: String,
+        product_class: String,
+        serial_number: String,
+    ) -> Result<Self, Error> {
+        facets::max_length("Manufacturer", &manufacturer, facets::MANUFACTURER)?;
+        validate_oui(&oui)?;
+        facets::max_length("ProductClass", &product_class, facets::PRODUCT_CLASS)?;
+        facets::max_length("SerialNumber", &serial_number, facets::SERIAL_NUMBER)?;
+        Ok(Self {
+            manufacturer,
+            oui,
+            product_class,
+            serial_number,
+        })
+    }
+
+    pub fn manufacturer(&self) -> &str {
+        &self.manufacturer
+    }
+
+    pub fn oui(&self) -> &str {
+        &self.oui
+    }
+
+    /// Empty when the CPE has no product class.
+    pub fn product_class(&self) -> &str {
+        &self.product_class
+    }
+
+    pub fn serial_number(&self) -> &str {
+        &self.serial_number
+    }
+
+    /// `OUI-ProductClass-SerialNumber`, or `OUI-SerialNumber` without a
+    /// product class. This is also the `Display` form.
+    pub fn key(&self) -> String {
+        if self.product_class.is_empty() {
+            return self.short_key();
+        }
+        let mut key = String::new();
+        escape(&self.oui, &mut key);
+        key.push('-');
+        escape(&self.product_class, &mut key);
+        key.push('-');
+        escape(&self.serial_number, &mut key);
+        key
+    }
+
+    /// `OUI-SerialNumber`, unique as long as serial numbers are unique per OUI.
+    pub fn short_key(&self) -> String {
+        let mut key = String::new();
+        escape(&self.oui, &mut key);
+        key.push('-');
+        escape(&self.serial_number, &mut key);
+        key
+    }
+}
+
+/// Parses either key form. The manufacturer is not part of a key and is left empty.
+impl FromStr for DeviceId {
+    type Err = Error;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = key.split('-').collect();
+        let (oui, product_class, serial_number) = match parts[..] {
+            [oui, serial_number] => (oui, "", serial_number),
+            [oui, product_class, serial_number] => (oui, product_class, serial_number),
+            _ => {
+                return Err(Error::InvalidValue(format!(
+                    "'{key}' is not a valid device key."
+                )));
+            }
+        };
+        Self::new(
+            String::new(),
+            unescape(key, oui)?,
+            unescape(key, product_class)?,
+            unescape(key, serial_number)?,
+        )
+    }
+}
+
+impl TryFrom<String> for DeviceId {
+    type Error = Error;
+
+    fn try_from(key: String) -> Result<Self, Self::Error> {
+        key.parse()
+    }
+}
+
+impl fmt::Display for DeviceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.key())
+    }
+}
+
+macro_rules! impl_try_from_device_id {
+    ($(($device_id:ty, $inform:ty)),*) => {
+        $(
+            impl TryFrom<$device_id> for DeviceId {
+                type Error = Error;
+                fn try_from(v: $device_id) -> Result<Self, Self::Error> {
+                    Self::new(v.manufacturer, v.oui, v.product_class, v.serial_number)
+                }
+            }
+
+            impl TryFrom<&$inform> for DeviceId {
+                type Error = Error;
+                fn try_from(inform: &$inform) -> Result<Self, Self::Error> {
+                    inform.device_id.clone().try_into()
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_device_id!(
+    (
+        cwmp_xsd_schema::DeviceIdStructType,
+        cwmp_xsd_schema::InformElementType
+    ),
+    (
+        cwmp_xsd_schema::tns::DeviceIdStructType,
+        cwmp_xsd_schema::tns::InformElementType
+    ),
+    (
+        cwmp_xsd_schema::cwmp_12::DeviceIdStructType,
+        cwmp_xsd_schema::cwmp_12::InformElementType
+    )
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn device(product_class: &str, serial_number: &str) -> DeviceId {
+        DeviceId::new(
+            "Example".to_string(),
+            "00D09E".to_string(),
+            product_class.to_string(),
+            serial_number.to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn keys() {
+        let id = device("IGD", "SN-0001");
+        assert_eq!(id.key(), "00D09E-IGD-SN%2D0001");
+        assert_eq!(id.short_key(), "00D09E-SN%2D0001");
+
+        let parsed: DeviceId = id.key().parse().unwrap();
+        assert_eq!(parsed.product_class(), "IGD");
+        assert_eq!(parsed.serial_number(), "SN-0001");
+        assert_eq!(parsed.manufacturer(), "");
+
+        let id = device("", "A B/1");
+        assert_eq!(id.key(), "00D09E-A%20B%2F1");
+        let parsed: DeviceId = id.key().parse().unwrap();
+        assert_eq!(parsed.serial_number(), "A B/1");
+
+        for invalid in [
+            "00D09E",
+            "00d09e-SN",
+            "00D09E-a-b-c",
+            "00D09E-SN%2",
+            "00D09E-SN%+1",
+        ] {
+            assert!(invalid.parse::<DeviceId>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn oui() {
+        assert!(validate_oui("00D09E").is_ok());
+        assert!(validate_oui("00d09e").is_err());
+        assert!(validate_oui("00D09").is_err());
+        assert!(validate_oui("00D09G").is_err());
+    }
+}
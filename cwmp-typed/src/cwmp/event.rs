@@ -0,0 +1,295 @@
+//! Inform event codes (TR-069 3.7.1.5, Table 7) and the rules the CPE
+//! follows when an Inform carrying them is not delivered.
+use super::CwmpVersion;
+use super::device::validate_oui;
+use super::facets;
+use crate::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EventCode {
+    Bootstrap,
+    Boot,
+    Periodic,
+    Scheduled,
+    ValueChange,
+    Kicked,
+    ConnectionRequest,
+    TransferComplete,
+    DiagnosticsComplete,
+    RequestDownload,
+    AutonomousTransferComplete,
+    DuStateChangeComplete,
+    AutonomousDuStateChangeComplete,
+    Wakeup,
+    MReboot,
+    MScheduleInform,
+    MDownload,
+    MUpload,
+    MScheduleDownload,
+    MChangeDuState,
+    /// `M <method>` for any other method, vendor methods included (`M X_...`).
+    MOther(String),
+    /// `X <OUI> <event>`.
+    Vendor {
+        oui: String,
+        event: String,
+    },
+    /// A standard event code defined after 1.4 (`<number> <name>`).
+    Other(String),
+}
+
+/// Whether several events of the same code are kept apart or merged into one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cumulative {
+    Single,
+    Multiple,
+}
+
+/// When an undelivered event stops being sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscardPolicy {
+    /// Retried in every Inform until an InformResponse acknowledges it.
+    OnInformResponse,
+    /// Retried until the CPE method it announces (`TransferComplete`,
+    /// `DUStateChangeComplete`, ...) has been delivered in the session.
+    OnMethodDelivered(&'static str),
+    /// Never retried, dropped when the session it was sent in fails.
+    OnSessionFailure,
+}
+
+const STANDARD: [(EventCode, &str); 20] = [
+    (EventCode::Bootstrap, "0 BOOTSTRAP"),
+    (EventCode::Boot, "1 BOOT"),
+    (EventCode::Periodic, "2 PERIODIC"),
+    (EventCode::Scheduled, "3 SCHEDULED"),
+    (EventCode::ValueChange, "4 VALUE CHANGE"),
+    (EventCode::Kicked, "5 KICKED"),
+    (EventCode::ConnectionRequest, "6 CONNECTION REQUEST"),
+    (EventCode::TransferComplete, "7 TRANSFER COMPLETE"),
+    (EventCode::DiagnosticsComplete, "8 DIAGNOSTICS COMPLETE"),
+    (EventCode::RequestDownload, "9 REQUEST DOWNLOAD"),
+    (
+        EventCode::AutonomousTransferComplete,
+        "10 AUTONOMOUS TRANSFER COMPLETE",
+    ),
+    (
+        EventCode::DuStateChangeComplete,
+        "11 DU STATE CHANGE COMPLETE",
+    ),
+    (
+        EventCode::AutonomousDuStateChangeComplete,
+        "12 AUTONOMOUS DU STATE CHANGE COMPLETE",
+    ),
+    (EventCode::Wakeup, "13 WAKEUP"),
+    (EventCode::MReboot, "M Reboot"),
+    (EventCode::MScheduleInform, "M ScheduleInform"),
+    (EventCode::MDownload, "M Download"),
+    (EventCode::MUpload, "M Upload"),
+    (EventCode::MScheduleDownload, "M ScheduleDownload"),
+    (EventCode::MChangeDuState, "M ChangeDUState"),
+];
+
+impl EventCode {
+    /// The first cwmp version whose schema allows this event code.
+    pub fn introduced_in(&self) -> CwmpVersion {
+        match self {
+            Self::AutonomousTransferComplete | Self::Other(_) => CwmpVersion::_11,
+            Self::MOther(method) if !method.starts_with("X_") => CwmpVersion::_11,
+            Self::DuStateChangeComplete
+            | Self::AutonomousDuStateChangeComplete
+            | Self::Wakeup
+            | Self::MScheduleDownload
+            | Self::MChangeDuState => CwmpVersion::_12,
+            _ => CwmpVersion::_10,
+        }
+    }
+
+    /// `M` events report a method the ACS called.
+    pub fn is_method(&self) -> bool {
+        matches!(
+            self,
+            Self::MReboot
+                | Self::MScheduleInform
+                | Self::MDownload
+                | Self::MUpload
+                | Self::MScheduleDownload
+                | Self::MChangeDuState
+                | Self::MOther(_)
+        )
+    }
+
+    pub fn cumulative(&self) -> Cumulative {
+        match self {
+            Self::AutonomousTransferComplete | Self::AutonomousDuStateChangeComplete => {
+                Cumulative::Multiple
+            }
+            code if code.is_method() => Cumulative::Multiple,
+            _ => Cumulative::Single,
+        }
+    }
+
+    /// Whether a `0 BOOTSTRAP` makes the CPE drop an undelivered event of this code.
+    pub fn reset_on_bootstrap(&self) -> bool {
+        !matches!(
+            self,
+            Self::Bootstrap
+                | Self::TransferComplete
+                | Self::AutonomousTransferComplete
+                | Self::DuStateChangeComplete
+                | Self::AutonomousDuStateChangeComplete
+                | Self::MDownload
+                | Self::MUpload
+                | Self::MScheduleDownload
+                | Self::MChangeDuState
+        )
+    }
+
+    pub fn discard_policy(&self) -> DiscardPolicy {
+        match self {
+            Self::ConnectionRequest => DiscardPolicy::OnSessionFailure,
+            Self::TransferComplete | Self::MDownload | Self::MUpload | Self::MScheduleDownload => {
+                DiscardPolicy::OnMethodDelivered("TransferComplete")
+            }
+            Self::AutonomousTransferComplete => {
+                DiscardPolicy::OnMethodDelivered("AutonomousTransferComplete")
+            }
+            Self::DuStateChangeComplete | Self::MChangeDuState => {
+                DiscardPolicy::OnMethodDelivered("DUStateChangeComplete")
+            }
+            Self::AutonomousDuStateChangeComplete => {
+                DiscardPolicy::OnMethodDelivered("AutonomousDUStateChangeComplete")
+            }
+            Self::RequestDownload => DiscardPolicy::OnMethodDelivered("RequestDownload"),
+            _ => DiscardPolicy::OnInformResponse,
+        }
+    }
+}
+
+impl FromStr for EventCode {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        facets::max_length("EventCode", input, facets::EVENT_CODE)?;
+        let invalid = || Error::InvalidValue(format!("'{input}' is not a valid event code."));
+
+        if let Some((code, _)) = STANDARD.iter().find(|(_, name)| *name == input) {
+            return Ok(code.clone());
+        }
+
+        if let Some(method) = input.strip_prefix("M ") {
+            if method.is_empty() || method.contains(char::is_whitespace) {
+                return Err(invalid());
+            }
+            return Ok(Self::MOther(method.to_string()));
+        }
+
+        if let Some(rest) = input.strip_prefix("X ") {
+            let (oui, event) = rest.split_once(' ').ok_or_else(invalid)?;
+            if validate_oui(oui).is_err() {
+                return Err(invalid());
+            }
+            return Ok(Self::Vendor {
+                oui: oui.to_string(),
+                event: event.to_string(),
+            });
+        }
+
+        let (number, name) = input.split_once(' ').ok_or_else(invalid)?;
+        if number.is_empty()
+            || !number.bytes().all(|b| b.is_ascii_digit())
+            || name.split(' ').any(str::is_empty)
+        {
+            return Err(invalid());
+        }
+        Ok(Self::Other(input.to_string()))
+    }
+}
+
+impl TryFrom<String> for EventCode {
+    type Error = Error;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        input.parse()
+    }
+}
+
+impl fmt::Display for EventCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MOther(method) => write!(f, "M {method}"),
+            Self::Vendor { oui, event } => write!(f, "X {oui} {event}"),
+            Self::Other(code) => f.write_str(code),
+            code => {
+                let (_, name) = STANDARD
+                    .iter()
+                    .find(|(standard, _)| standard == code)
+                    .expect("every standard event code is listed");
+                f.write_str(name)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for (code, name) in STANDARD.iter() {
+            assert_eq!(&name.parse::<EventCode>().unwrap(), code);
+            assert_eq!(&code.to_string(), name);
+        }
+
+        let vendor: EventCode = "X 00D09E MyEvent happened".parse().unwrap();
+        assert_eq!(
+            vendor,
+            EventCode::Vendor {
+                oui: "00D09E".to_string(),
+                event: "MyEvent happened".to_string()
+            }
+        );
+        assert_eq!(vendor.to_string(), "X 00D09E MyEvent happened");
+
+        let method: EventCode = "M X_00D09E_Reset".parse().unwrap();
+        assert_eq!(method.introduced_in(), CwmpVersion::_10);
+        assert_eq!(method.to_string(), "M X_00D09E_Reset");
+        // the 1.0 schema only lists the named M events and M X_ ones
+        let method: EventCode = "M GetParameterValues".parse().unwrap();
+        assert_eq!(method.introduced_in(), CwmpVersion::_11);
+
+        assert!(matches!(
+            "14 FUTURE EVENT".parse::<EventCode>(),
+            Ok(EventCode::Other(_))
+        ));
+        for invalid in [
+            "BOOT",
+            "X 00d09e Event",
+            "X 00G09E Event",
+            "M ",
+            "M Two Words",
+            "1  BOOT",
+        ] {
+            assert!(invalid.parse::<EventCode>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn delivery_rules() {
+        assert_eq!(
+            EventCode::ConnectionRequest.discard_policy(),
+            DiscardPolicy::OnSessionFailure
+        );
+        assert_eq!(
+            EventCode::MDownload.discard_policy(),
+            DiscardPolicy::OnMethodDelivered("TransferComplete")
+        );
+        assert_eq!(EventCode::MReboot.cumulative(), Cumulative::Multiple);
+        assert_eq!(EventCode::ValueChange.cumulative(), Cumulative::Single);
+        assert!(EventCode::ValueChange.reset_on_bootstrap());
+        assert!(!EventCode::TransferComplete.reset_on_bootstrap());
+        assert_eq!(EventCode::Wakeup.introduced_in(), CwmpVersion::_12);
+    }
+}
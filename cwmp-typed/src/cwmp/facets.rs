@@ -0,0 +1,295 @@
+//! Length facets declared by the cwmp schemas. The generated types carry plain
+//! `String`s, so the limits are re-declared here and checked by the typed
+//! conversions (when parsing) and by [`Facets::check_facets`] (before sending).
+use super::device::{self, DeviceId};
+use super::rpc::{GetParameterValues, RpcMethod, SetParameterAttributes, SetParameterValues};
+use super::types::{
+    AddObject, Download, GetParameterAttributesResponse, GetParameterNames,
+    GetParameterNamesResponse, GetParameterValuesResponse, GetRPCMethodsResponse, Inform,
+    ParameterName, ParameterNames, ParameterValueStruct, Reboot, SetParameterAttributesStruct,
+};
+use crate::envelope::Fault;
+use crate::error::Error;
+
+pub const COMMAND_KEY: usize = 32;
+pub const PARAMETER_KEY: usize = 32;
+pub const PARAMETER_NAME: usize = 256;
+pub const OBJECT_NAME: usize = 256;
+pub const FAULT_STRING: usize = 256;
+pub const METHOD_NAME: usize = 64;
+pub const ACCESS_LIST_ENTRY: usize = 64;
+pub const FILE_TYPE: usize = 64;
+pub const URL: usize = 256;
+pub const USERNAME: usize = 256;
+pub const PASSWORD: usize = 256;
+pub const TARGET_FILE_NAME: usize = 256;
+pub const EVENT_CODE: usize = 64;
+pub const MANUFACTURER: usize = 64;
+pub const OUI: usize = 6;
+pub const PRODUCT_CLASS: usize = 64;
+pub const SERIAL_NUMBER: usize = 64;
+pub const ALIAS: usize = 64;
+
+/// Checks `value` against an `xs:maxLength` facet, counted in characters.
+pub fn max_length(field: &str, value: &str, limit: usize) -> Result<(), Error> {
+    let length = value.chars().count();
+    if length > limit {
+        return Err(Error::MaxLengthExceeded {
+            field: field.to_string(),
+            limit,
+            length,
+        });
+    }
+    Ok(())
+}
+
+pub trait Facets {
+    fn check_facets(&self) -> Result<(), Error>;
+}
+
+impl Facets for RpcMethod {
+    fn check_facets(&self) -> Result<(), Error> {
+        match self {
+            Self::GetParameterNames(inner) => inner.check_facets(),
+            Self::SetParameterValues(inner) => inner.check_facets(),
+            Self::GetParameterValues(inner) => inner.check_facets(),
+            Self::SetParameterAttributes(inner) => inner.check_facets(),
+            Self::GetParameterAttributes(inner) => inner.check_facets(),
+            Self::AddObject(inner) | Self::DeleteObject(inner) => inner.check_facets(),
+            Self::Reboot(inner) => inner.check_facets(),
+            Self::Download(inner) => inner.check_facets(),
+            Self::GetRPCMethodsResponse(inner) => inner.check_facets(),
+            Self::GetParameterNamesResponse(inner) => inner.check_facets(),
+            Self::GetParameterValuesResponse(inner) => inner.check_facets(),
+            Self::GetParameterAttributesResponse(inner) => inner.check_facets(),
+            Self::Inform(inner) => inner.check_facets(),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Facets for ParameterName {
+    fn check_facets(&self) -> Result<(), Error> {
+        max_length("ParameterName", &self.to_string(), PARAMETER_NAME)
+    }
+}
+
+impl Facets for ParameterNames {
+    fn check_facets(&self) -> Result<(), Error> {
+        self.0.iter().try_for_each(|name| name.check_facets())
+    }
+}
+
+impl Facets for GetParameterNames {
+    fn check_facets(&self) -> Result<(), Error> {
+        max_length(
+            "ParameterPath",
+            &self.parameter_path.to_string(),
+            PARAMETER_NAME,
+        )
+    }
+}
+
+impl Facets for GetParameterValues {
+    fn check_facets(&self) -> Result<(), Error> {
+        self.parameter_names.check_facets()
+    }
+}
+
+impl Facets for ParameterValueStruct {
+    fn check_facets(&self) -> Result<(), Error> {
+        max_length("Name", &self.name, PARAMETER_NAME)
+    }
+}
+
+impl Facets for SetParameterValues {
+    fn check_facets(&self) -> Result<(), Error> {
+        max_length("ParameterKey", &self.parameter_key, PARAMETER_KEY)?;
+        self.parameter_list
+            .0
+            .iter()
+            .try_for_each(|item| item.check_facets())
+    }
+}
+
+impl Facets for SetParameterAttributesStruct {
+    fn check_facets(&self) -> Result<(), Error> {
+        if let Some(name) = &self.name {
+            max_length("Name", name, PARAMETER_NAME)?;
+        }
+        self.access_list
+            .0
+            .iter()
+            .try_for_each(|member| max_length("AccessList", member.as_str(), ACCESS_LIST_ENTRY))
+    }
+}
+
+impl Facets for SetParameterAttributes {
+    fn check_facets(&self) -> Result<(), Error> {
+        self.parameter_list
+            .0
+            .iter()
+            .try_for_each(|item| item.check_facets())
+    }
+}
+
+impl Facets for AddObject {
+    fn check_facets(&self) -> Result<(), Error> {
+        max_length("ObjectName", &self.object_name, OBJECT_NAME)?;
+        max_length("ParameterKey", &self.parameter_key, PARAMETER_KEY)
+    }
+}
+
+impl Facets for Reboot {
+    fn check_facets(&self) -> Result<(), Error> {
+        max_length("CommandKey", &self.command_key, COMMAND_KEY)
+    }
+}
+
+impl Facets for Download {
+    fn check_facets(&self) -> Result<(), Error> {
+        max_length("CommandKey", &self.command_key, COMMAND_KEY)?;
+        max_length("FileType", &self.file_type, FILE_TYPE)?;
+        max_length("URL", &self.url, URL)?;
+        max_length("Username", &self.username, USERNAME)?;
+        max_length("Password", &self.password, PASSWORD)?;
+        max_length("TargetFileName", &self.target_file_name, TARGET_FILE_NAME)?;
+        max_length("SuccessURL", &self.success_url, URL)?;
+        max_length("FailureURL", &self.failure_url, URL)
+    }
+}
+
+impl Facets for GetRPCMethodsResponse {
+    fn check_facets(&self) -> Result<(), Error> {
+        self.method_list
+            .0
+            .iter()
+            .try_for_each(|method| max_length("MethodList", method, METHOD_NAME))
+    }
+}
+
+impl Facets for GetParameterNamesResponse {
+    fn check_facets(&self) -> Result<(), Error> {
+        self.parameter_list
+            .0
+            .iter()
+            .try_for_each(|item| max_length("Name", &item.name, PARAMETER_NAME))
+    }
+}
+
+impl Facets for GetParameterValuesResponse {
+    fn check_facets(&self) -> Result<(), Error> {
+        self.parameter_list
+            .0
+            .iter()
+            .try_for_each(|item| item.check_facets())
+    }
+}
+
+impl Facets for GetParameterAttributesResponse {
+    fn check_facets(&self) -> Result<(), Error> {
+        self.parameter_list
+            .0
+            .iter()
+            .try_for_each(|item| max_length("Name", &item.name, PARAMETER_NAME))
+    }
+}
+
+impl Facets for DeviceId {
+    fn check_facets(&self) -> Result<(), Error> {
+        max_length("Manufacturer", &self.manufacturer, MANUFACTURER)?;
+        device::validate_oui(&self.oui)?;
+        max_length("ProductClass", &self.product_class, PRODUCT_CLASS)?;
+        max_length("SerialNumber", &self.serial_number, SERIAL_NUMBER)
+    }
+}
+
+impl Facets for Inform {
+    fn check_facets(&self) -> Result<(), Error> {
+        self.device_id.check_facets()?;
+        for event in &self.event.0 {
+            max_length("EventCode", &event.event_code.to_string(), EVENT_CODE)?;
+            max_length("CommandKey", &event.command_key, COMMAND_KEY)?;
+        }
+        self.parameter_list
+            .0
+            .iter()
+            .try_for_each(|item| item.check_facets())
+    }
+}
+
+impl Facets for Fault {
+    fn check_facets(&self) -> Result<(), Error> {
+        max_length("FaultString", &self.fault_string, FAULT_STRING)?;
+        self.set_parameter_values_faults
+            .iter()
+            .try_for_each(|item| max_length("FaultString", &item.fault_string, FAULT_STRING))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn max_length_counts_characters() {
+        assert!(max_length("CommandKey", &"a".repeat(32), COMMAND_KEY).is_ok());
+        // multi-byte characters count once each, like the schema facet
+        assert!(max_length("CommandKey", &"ä".repeat(32), COMMAND_KEY).is_ok());
+
+        let err = max_length("CommandKey", &"a".repeat(33), COMMAND_KEY).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "'CommandKey' exceeds the maximum length of 32 (got 33)."
+        );
+    }
+
+    #[test]
+    fn reboot_command_key() {
+        let reboot: Result<Reboot, Error> = "x".repeat(33).try_into();
+        assert!(matches!(
+            reboot,
+            Err(Error::MaxLengthExceeded { limit: 32, .. })
+        ));
+
+        let reboot: Reboot = "reboot-1".to_string().try_into().unwrap();
+        assert!(reboot.check_facets().is_ok());
+    }
+
+    #[test]
+    fn download_facets() {
+        let mut download = Download {
+            command_key: "fw".to_string(),
+            file_type: "1 Firmware Upgrade Image".to_string(),
+            url: "http://acs.example.com/fw.bin".to_string(),
+            username: String::new(),
+            password: String::new(),
+            file_size: 0,
+            target_file_name: String::new(),
+            delay_seconds: 0,
+            success_url: String::new(),
+            failure_url: String::new(),
+        };
+        assert!(download.check_facets().is_ok());
+
+        download.url = format!("http://acs.example.com/{}", "a".repeat(256));
+        let Err(Error::MaxLengthExceeded { field, limit, .. }) = download.check_facets() else {
+            panic!();
+        };
+        assert_eq!(field, "URL");
+        assert_eq!(limit, URL);
+    }
+
+    #[test]
+    fn fault_string() {
+        let mut fault = Fault::new(crate::cwmp::fault::FaultCode::InternalError);
+        assert!(fault.check_facets().is_ok());
+
+        fault.fault_string = "x".repeat(257);
+        let Err(Error::MaxLengthExceeded { field, limit, .. }) = fault.check_facets() else {
+            panic!();
+        };
+        assert_eq!(field, "FaultString");
+        assert_eq!(limit, FAULT_STRING);
+    }
+}
@@ -0,0 +1,160 @@
+//! Fault codes, refer TR-069 A.5.1 (CPE) and A.5.2 (ACS).
+use crate::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// The CPE fault codes left to vendors, without a suggested FaultString.
+pub const VENDOR_FAULT_CODES: RangeInclusive<u32> = 9800..=9899;
+
+macro_rules! fault_codes {
+    ($($variant: ident = $code: literal => $message: literal),+ $(,)?) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum FaultCode {
+            $($variant,)+
+        }
+
+        impl FaultCode {
+            pub fn code(&self) -> u32 {
+                match self {
+                    $(Self::$variant => $code,)+
+                }
+            }
+
+            /// The FaultString suggested by the specification.
+            pub fn message(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $message,)+
+                }
+            }
+        }
+
+        impl TryFrom<u32> for FaultCode {
+            type Error = Error;
+            fn try_from(code: u32) -> Result<Self, Self::Error> {
+                match code {
+                    $($code => Ok(Self::$variant),)+
+                    other => Err(Error::InvalidValue(format!("unknown fault code {other}"))),
+                }
+            }
+        }
+    };
+}
+
+fault_codes!(
+    AcsMethodNotSupported = 8000 => "Method not supported",
+    AcsRequestDenied = 8001 => "Request denied (no reason specified)",
+    AcsInternalError = 8002 => "Internal error",
+    AcsInvalidArguments = 8003 => "Invalid arguments",
+    AcsResourcesExceeded = 8004 => "Resources exceeded",
+    RetryRequest = 8005 => "Retry request",
+    MethodNotSupported = 9000 => "Method not supported",
+    RequestDenied = 9001 => "Request denied (no reason specified)",
+    InternalError = 9002 => "Internal error",
+    InvalidArguments = 9003 => "Invalid arguments",
+    ResourcesExceeded = 9004 => "Resources exceeded",
+    InvalidParameterName = 9005 => "Invalid parameter name",
+    InvalidParameterType = 9006 => "Invalid parameter type",
+    InvalidParameterValue = 9007 => "Invalid parameter value",
+    NonWritableParameter = 9008 => "Attempt to set a non-writable parameter",
+    NotificationRequestRejected = 9009 => "Notification request rejected",
+    DownloadFailure = 9010 => "File transfer failure",
+    UploadFailure = 9011 => "Upload failure",
+    FileTransferAuthenticationFailure = 9012 => "File transfer server authentication failure",
+    UnsupportedTransferProtocol = 9013 => "Unsupported protocol for file transfer",
+    MulticastJoinFailure = 9014 => "File transfer failure: unable to join multicast group",
+    FileServerUnreachable = 9015 => "File transfer failure: unable to contact file server",
+    FileAccessFailure = 9016 => "File transfer failure: unable to access file",
+    DownloadIncomplete = 9017 => "File transfer failure: unable to complete download",
+    FileCorrupted = 9018 => "File transfer failure: file corrupted or otherwise unusable",
+    FileAuthenticationFailure = 9019 => "File transfer failure: file authentication failure",
+    DownloadWindowExceeded = 9020 => "File transfer failure: unable to complete download within specified time windows",
+    CancelNotPermitted = 9021 => "Cancelation of file transfer not permitted in current transfer state",
+    InvalidUuid = 9022 => "Invalid UUID Format",
+    UnknownExecutionEnvironment = 9023 => "Unknown Execution Environment",
+    DisabledExecutionEnvironment = 9024 => "Disabled Execution Environment",
+    ExecutionEnvironmentMismatch = 9025 => "Deployment Unit to Execution Environment Mismatch",
+    DuplicateDeploymentUnit = 9026 => "Duplicate Deployment Unit",
+    SystemResourcesExceeded = 9027 => "System Resources Exceeded",
+    UnknownDeploymentUnit = 9028 => "Unknown Deployment Unit",
+    InvalidDeploymentUnitState = 9029 => "Invalid Deployment Unit State",
+    DowngradeNotPermitted = 9030 => "Invalid Deployment Unit Update - Downgrade not permitted",
+    VersionNotSpecified = 9031 => "Invalid Deployment Unit Update - Version not specified",
+    VersionExists = 9032 => "Invalid Deployment Unit Update - Version already exists",
+);
+
+impl FaultCode {
+    /// Whether the soap `faultcode` is `Client`, the request being at fault,
+    /// rather than `Server`.
+    pub fn is_client(&self) -> bool {
+        matches!(
+            self,
+            Self::AcsInvalidArguments
+                | Self::InvalidArguments
+                | Self::InvalidParameterName
+                | Self::InvalidParameterType
+                | Self::InvalidParameterValue
+                | Self::NonWritableParameter
+        ) || self.code() >= FaultCode::CancelNotPermitted.code()
+    }
+}
+
+/// The FaultString suggested for `code`, empty for a vendor fault code.
+pub fn suggested_fault_string(code: u32) -> Result<&'static str, Error> {
+    match FaultCode::try_from(code) {
+        Ok(known) => Ok(known.message()),
+        Err(_) if VENDOR_FAULT_CODES.contains(&code) => Ok(""),
+        Err(e) => Err(e),
+    }
+}
+
+/// The fault for an rpc rejected with `error`.
+impl From<&Error> for FaultCode {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::InvalidParameterName(_) => Self::InvalidParameterName,
+            Error::InvalidValue(_) | Error::ParseIntError(_) => Self::InvalidParameterValue,
+            Error::UnsupportedRpcVersion(_) | Error::RpcVersionMismatch | Error::UnknownRpc => {
+                Self::MethodNotSupported
+            }
+            _ => Self::InvalidArguments,
+        }
+    }
+}
+
+impl fmt::Display for FaultCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.code(), self.message())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn codes() {
+        assert_eq!(FaultCode::InvalidParameterValue.code(), 9007);
+        assert_eq!(
+            FaultCode::try_from(9008).unwrap(),
+            FaultCode::NonWritableParameter
+        );
+        assert_eq!(
+            FaultCode::InvalidParameterName.to_string(),
+            "9005 Invalid parameter name"
+        );
+        assert!(FaultCode::try_from(9999).is_err());
+        assert_eq!(
+            suggested_fault_string(9001).unwrap(),
+            "Request denied (no reason specified)"
+        );
+        assert_eq!(suggested_fault_string(9850).unwrap(), "");
+        assert!(suggested_fault_string(9999).is_err());
+        assert!(FaultCode::InvalidParameterName.is_client());
+        assert!(!FaultCode::InternalError.is_client());
+        assert!(FaultCode::VersionExists.is_client());
+        assert_eq!(
+            FaultCode::from(&Error::InvalidValue(String::new())),
+            FaultCode::InvalidParameterValue
+        );
+    }
+}
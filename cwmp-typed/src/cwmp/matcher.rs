@@ -0,0 +1,224 @@
+//! Matching of parameter names against the patterns an ACS can send: partial
+//! paths select a whole subtree, `*` selects every instance of a table
+//! (TR-069 A.2.1 and A.3.2.1).
+use super::path::{ParameterPath, Segment};
+use super::types::ParameterNames;
+use crate::error::Error;
+use std::collections::HashMap;
+
+fn segment_matches(pattern: &Segment, name: &Segment) -> bool {
+    match (pattern, name) {
+        (Segment::Wildcard, Segment::Instance(_) | Segment::Alias(_) | Segment::Wildcard) => true,
+        (pattern, name) => pattern == name,
+    }
+}
+
+impl ParameterPath {
+    /// Whether `name` is selected by `self`. A partial path matches itself and
+    /// everything below it, a parameter name only the parameter itself.
+    pub fn matches(&self, name: &ParameterPath) -> bool {
+        let (pattern, name_segments) = (self.segments(), name.segments());
+        if self.is_object() {
+            if name_segments.len() < pattern.len()
+                || (name_segments.len() == pattern.len() && !name.is_object())
+            {
+                return false;
+            }
+        } else if name.is_object() || name_segments.len() != pattern.len() {
+            return false;
+        }
+        pattern
+            .iter()
+            .zip(name_segments)
+            .all(|(pattern, name)| segment_matches(pattern, name))
+    }
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    names: HashMap<String, Node>,
+    instances: HashMap<u32, Node>,
+    aliases: HashMap<String, Node>,
+    wildcard: Option<Box<Node>>,
+    // patterns ending here, by index
+    objects: Vec<usize>,
+    parameters: Vec<usize>,
+}
+
+impl Node {
+    fn child_mut(&mut self, segment: &Segment) -> &mut Node {
+        match segment {
+            Segment::Name(name) => self.names.entry(name.clone()).or_default(),
+            Segment::Instance(number) => self.instances.entry(*number).or_default(),
+            Segment::Alias(alias) => self.aliases.entry(alias.clone()).or_default(),
+            Segment::Wildcard => self.wildcard.get_or_insert_with(Default::default),
+        }
+    }
+
+    fn collect(&self, name: &ParameterPath, depth: usize, matches: &mut Vec<usize>) {
+        let segments = name.segments();
+        let Some(segment) = segments.get(depth) else {
+            if name.is_object() {
+                matches.extend_from_slice(&self.objects);
+            } else {
+                matches.extend_from_slice(&self.parameters);
+            }
+            return;
+        };
+
+        // the name continues below this node, so partial paths ending here select it
+        matches.extend_from_slice(&self.objects);
+
+        let exact = match segment {
+            Segment::Name(inner) => self.names.get(inner),
+            Segment::Instance(number) => self.instances.get(number),
+            Segment::Alias(alias) => self.aliases.get(alias),
+            Segment::Wildcard => None,
+        };
+        if let Some(child) = exact {
+            child.collect(name, depth + 1, matches);
+        }
+        if !matches!(segment, Segment::Name(_))
+            && let Some(child) = &self.wildcard
+        {
+            child.collect(name, depth + 1, matches);
+        }
+    }
+}
+
+/// Many patterns compiled into one segment tree, so matching a name costs
+/// about the same whether there are ten patterns or ten thousand.
+#[derive(Debug, Default)]
+pub struct PatternSet {
+    root: Node,
+    patterns: Vec<ParameterPath>,
+}
+
+impl PatternSet {
+    pub fn new<I>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = ParameterPath>,
+    {
+        let mut set = Self::default();
+        for pattern in patterns {
+            set.insert(pattern);
+        }
+        set
+    }
+
+    /// Adds a pattern and returns its index.
+    pub fn insert(&mut self, pattern: ParameterPath) -> usize {
+        let index = self.patterns.len();
+        let mut node = &mut self.root;
+        for segment in pattern.segments() {
+            node = node.child_mut(segment);
+        }
+        if pattern.is_object() {
+            node.objects.push(index);
+        } else {
+            node.parameters.push(index);
+        }
+        self.patterns.push(pattern);
+        index
+    }
+
+    pub fn patterns(&self) -> &[ParameterPath] {
+        &self.patterns
+    }
+
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Indices of every pattern matching `name`, in insertion order.
+    pub fn matches(&self, name: &ParameterPath) -> Vec<usize> {
+        let mut matches = Vec::new();
+        self.root.collect(name, 0, &mut matches);
+        matches.sort_unstable();
+        matches.dedup();
+        matches
+    }
+
+    pub fn is_match(&self, name: &ParameterPath) -> bool {
+        !self.matches(name).is_empty()
+    }
+}
+
+impl TryFrom<&ParameterNames> for PatternSet {
+    type Error = Error;
+
+    fn try_from(input: &ParameterNames) -> Result<Self, Self::Error> {
+        let patterns = input
+            .0
+            .iter()
+            .map(ParameterPath::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(patterns))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn path(input: &str) -> ParameterPath {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn pattern_matches() {
+        let name = path("Device.WiFi.SSID.3.SSID");
+
+        assert!(path("Device.WiFi.SSID.*.SSID").matches(&name));
+        assert!(path("Device.WiFi.").matches(&name));
+        assert!(path("Device.WiFi.SSID.*.").matches(&name));
+        assert!(path("").matches(&name));
+        assert!(path("Device.WiFi.SSID.3.SSID").matches(&name));
+
+        assert!(!path("Device.WiFi.SSID.*.Enable").matches(&name));
+        assert!(!path("Device.WiFi.SSID.3.SSID.").matches(&name));
+        assert!(!path("Device.WiFi.SSID").matches(&path("Device.WiFi.SSID.")));
+        assert!(!path("Device.IP.").matches(&name));
+
+        // partial paths include the object itself
+        assert!(path("Device.WiFi.").matches(&path("Device.WiFi.")));
+    }
+
+    #[test]
+    fn pattern_set_agrees_with_matches() {
+        let patterns = vec![
+            path("Device.WiFi.SSID.*.SSID"),
+            path("Device.WiFi."),
+            path("Device.WiFi.SSID.2.SSID"),
+            path("Device.IP.Interface.*."),
+            path("Device.DeviceInfo.SoftwareVersion"),
+        ];
+        let set = PatternSet::new(patterns.clone());
+
+        for name in [
+            "Device.WiFi.SSID.2.SSID",
+            "Device.WiFi.SSID.7.SSID",
+            "Device.WiFi.Radio.1.Channel",
+            "Device.IP.Interface.1.IPv4Address.1.IPAddress",
+            "Device.IP.Interface.",
+            "Device.DeviceInfo.SoftwareVersion",
+            "Device.DeviceInfo.HardwareVersion",
+        ] {
+            let name = path(name);
+            let expected: Vec<usize> = patterns
+                .iter()
+                .enumerate()
+                .filter(|(_, pattern)| pattern.matches(&name))
+                .map(|(index, _)| index)
+                .collect();
+            assert_eq!(set.matches(&name), expected, "{name}");
+        }
+
+        assert_eq!(set.matches(&path("Device.WiFi.SSID.2.SSID")), vec![0, 1, 2]);
+        assert!(!set.is_match(&path("Device.DeviceInfo.HardwareVersion")));
+    }
+}
@@ -1,7 +1,22 @@
+pub mod alias;
+pub mod datatype;
+pub mod datetime;
+pub mod device;
+pub mod event;
+pub mod facets;
+pub mod fault;
+pub mod matcher;
+pub mod object;
+pub mod path;
+pub mod redact;
+#[cfg(feature = "serde")]
+mod repr;
 pub mod rpc;
 pub mod types;
+pub mod validate;
+pub mod value;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum CwmpVersion {
     _10,
     _11,
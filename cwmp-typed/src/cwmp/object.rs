@@ -0,0 +1,306 @@
+//! Structs mapped to the parameters of a multi-instance data model object,
+//! usually through the derive of the `cwmp-derive` crate:
+//!
+//! ```ignore
+//! #[derive(CwmpObject, Debug, Clone, PartialEq)]
+//! #[cwmp(path = "Device.WiFi.SSID.{i}.")]
+//! struct Ssid {
+//!     enable: bool,
+//!     #[cwmp(name = "SSID")]
+//!     ssid: String,
+//!     lower_layers: Option<String>,
+//! }
+//! ```
+//!
+//! A field maps to the parameter named like the field in UpperCamelCase,
+//! unless renamed with `#[cwmp(name = "..")]`. `Option` fields may be missing
+//! from a response and are not set while `None`.
+use super::datetime::CwmpDateTime;
+use super::rpc::SetParameterValues;
+use super::types::{ParameterList, ParameterValueStruct, ParameterValueStructValue};
+use super::value::{ParameterType, XsdType};
+use crate::error::Error;
+use std::collections::{BTreeMap, HashMap};
+
+#[cfg(feature = "derive")]
+pub use cwmp_derive::CwmpObject;
+
+/// A field type of a [`CwmpObject`], converted from and to the value of its
+/// parameter.
+pub trait ObjectValue: Sized {
+    /// The xsi:type the value is set with.
+    const TYPE: XsdType;
+    fn from_value(value: &str) -> Result<Self, Error>;
+    fn to_value(&self) -> String;
+}
+
+macro_rules! object_value {
+    ($($ty: ty => $type_: ident),+ $(,)?) => {
+        $(
+            impl ObjectValue for $ty {
+                const TYPE: XsdType = XsdType::$type_;
+
+                fn from_value(value: &str) -> Result<Self, Error> {
+                    value.parse().map_err(|_| {
+                        Error::InvalidValue(format!(
+                            "'{value}' is not a valid {}.",
+                            XsdType::$type_.name()
+                        ))
+                    })
+                }
+
+                fn to_value(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )+
+    };
+}
+
+object_value!(
+    String => String,
+    i32 => Int,
+    u32 => UnsignedInt,
+    i64 => Long,
+    u64 => UnsignedLong,
+    CwmpDateTime => DateTime,
+);
+
+impl ObjectValue for bool {
+    const TYPE: XsdType = XsdType::Boolean;
+
+    fn from_value(value: &str) -> Result<Self, Error> {
+        match value {
+            "1" | "true" => Ok(true),
+            "0" | "false" => Ok(false),
+            _ => Err(Error::InvalidValue(format!(
+                "'{value}' is not a valid boolean."
+            ))),
+        }
+    }
+
+    fn to_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// The values of one object instance by parameter name, `Enable`.
+pub type InstanceValues<'a> = HashMap<&'a str, &'a str>;
+
+pub trait CwmpObject: Sized {
+    /// The object name with `{i}` for the instance number,
+    /// `Device.WiFi.SSID.{i}.`.
+    const PATH: &'static str;
+
+    fn from_values(values: &InstanceValues) -> Result<Self, Error>;
+
+    /// The parameters of instance `prefix`, `Device.WiFi.SSID.1.`, that
+    /// differ between `old` and `self`.
+    fn diff(&self, old: &Self, prefix: &str) -> Vec<ParameterValueStruct>;
+
+    /// The instances in the parameter list of a GetParameterValuesResponse,
+    /// ordered by instance number. Parameters of other objects are ignored.
+    fn from_parameter_list(
+        list: &ParameterList<ParameterValueStruct>,
+    ) -> Result<Vec<(u32, Self)>, Error> {
+        let table = table(Self::PATH)?;
+        let mut instances: BTreeMap<u32, InstanceValues> = BTreeMap::new();
+        for parameter in &list.0 {
+            let Some((instance, name)) = parameter
+                .name
+                .strip_prefix(table)
+                .and_then(|rest| rest.split_once('.'))
+            else {
+                continue;
+            };
+            let Ok(instance) = instance.parse() else {
+                continue;
+            };
+            if name.is_empty() || name.contains('.') {
+                continue;
+            }
+            instances
+                .entry(instance)
+                .or_default()
+                .insert(name, &parameter.value.value);
+        }
+        instances
+            .into_iter()
+            .map(|(instance, values)| Ok((instance, Self::from_values(&values)?)))
+            .collect()
+    }
+
+    /// A SetParameterValues changing `instance` from `old` to `self`.
+    fn set_parameter_values(
+        &self,
+        old: &Self,
+        instance: u32,
+        parameter_key: &str,
+    ) -> Result<SetParameterValues, Error> {
+        let prefix = format!("{}{instance}.", table(Self::PATH)?);
+        (
+            parameter_key.to_string(),
+            ParameterList(self.diff(old, &prefix)),
+        )
+            .try_into()
+    }
+}
+
+// `Device.WiFi.SSID.{i}.` -> `Device.WiFi.SSID.`
+fn table(path: &'static str) -> Result<&'static str, Error> {
+    path.strip_suffix("{i}.").ok_or_else(|| {
+        Error::InvalidParameterName(format!("'{path}' is not a multi-instance object."))
+    })
+}
+
+/// The value of parameter `name`, used by the derive.
+pub fn value<T: ObjectValue>(values: &InstanceValues, name: &str) -> Result<T, Error> {
+    optional_value(values, name)?
+        .ok_or_else(|| Error::InvalidValue(format!("Parameter '{name}' is missing.")))
+}
+
+/// The value of parameter `name` if present, used by the derive.
+pub fn optional_value<T: ObjectValue>(
+    values: &InstanceValues,
+    name: &str,
+) -> Result<Option<T>, Error> {
+    values
+        .get(name)
+        .map(|value| T::from_value(value))
+        .transpose()
+}
+
+/// Parameter `name` of instance `prefix` set to `value`, used by the derive.
+pub fn parameter_value<T: ObjectValue>(
+    prefix: &str,
+    name: &str,
+    value: &T,
+) -> ParameterValueStruct {
+    ParameterValueStruct {
+        name: format!("{prefix}{name}"),
+        value: ParameterValueStructValue {
+            type_: Some(ParameterType::new(T::TYPE).xsi_type()),
+            value: value.to_value(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cwmp::types::ParameterValueType;
+
+    // what the derive generates for `enable: bool, ssid: String,
+    // lower_layers: Option<String>`
+    #[derive(Debug, Clone, PartialEq)]
+    struct Ssid {
+        enable: bool,
+        ssid: String,
+        lower_layers: Option<String>,
+    }
+
+    impl CwmpObject for Ssid {
+        const PATH: &'static str = "Device.WiFi.SSID.{i}.";
+
+        fn from_values(values: &InstanceValues) -> Result<Self, Error> {
+            Ok(Self {
+                enable: value(values, "Enable")?,
+                ssid: value(values, "SSID")?,
+                lower_layers: optional_value(values, "LowerLayers")?,
+            })
+        }
+
+        fn diff(&self, old: &Self, prefix: &str) -> Vec<ParameterValueStruct> {
+            let mut parameters = Vec::new();
+            if self.enable != old.enable {
+                parameters.push(parameter_value(prefix, "Enable", &self.enable));
+            }
+            if self.ssid != old.ssid {
+                parameters.push(parameter_value(prefix, "SSID", &self.ssid));
+            }
+            if let Some(lower_layers) = &self.lower_layers
+                && self.lower_layers != old.lower_layers
+            {
+                parameters.push(parameter_value(prefix, "LowerLayers", lower_layers));
+            }
+            parameters
+        }
+    }
+
+    fn parameter(name: &str, value: &str) -> ParameterValueStruct {
+        ParameterValueStruct {
+            name: name.to_string(),
+            value: ParameterValueStructValue {
+                type_: None,
+                value: value.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn instances() {
+        let list = ParameterList(vec![
+            parameter("Device.WiFi.SSID.2.Enable", "0"),
+            parameter("Device.WiFi.SSID.2.SSID", "guest"),
+            parameter("Device.WiFi.SSID.1.Enable", "true"),
+            parameter("Device.WiFi.SSID.1.SSID", "home"),
+            parameter("Device.WiFi.SSID.1.LowerLayers", "Device.WiFi.Radio.1."),
+            parameter("Device.WiFi.SSID.1.Stats.BytesSent", "42"),
+            parameter("Device.WiFi.SSIDNumberOfEntries", "2"),
+        ]);
+        let ssids = Ssid::from_parameter_list(&list).unwrap();
+        assert_eq!(
+            ssids,
+            [
+                (
+                    1,
+                    Ssid {
+                        enable: true,
+                        ssid: "home".to_string(),
+                        lower_layers: Some("Device.WiFi.Radio.1.".to_string()),
+                    }
+                ),
+                (
+                    2,
+                    Ssid {
+                        enable: false,
+                        ssid: "guest".to_string(),
+                        lower_layers: None,
+                    }
+                ),
+            ]
+        );
+
+        let missing = ParameterList(vec![parameter("Device.WiFi.SSID.1.Enable", "1")]);
+        assert!(Ssid::from_parameter_list(&missing).is_err());
+        let invalid = ParameterList(vec![
+            parameter("Device.WiFi.SSID.1.Enable", "yes"),
+            parameter("Device.WiFi.SSID.1.SSID", "home"),
+        ]);
+        assert!(Ssid::from_parameter_list(&invalid).is_err());
+    }
+
+    #[test]
+    fn set_parameter_values() {
+        let old = Ssid {
+            enable: true,
+            ssid: "home".to_string(),
+            lower_layers: None,
+        };
+        let new = Ssid {
+            enable: false,
+            ..old.clone()
+        };
+        let spv = new.set_parameter_values(&old, 3, "key").unwrap();
+        assert_eq!(spv.parameter_key(), "key");
+        let [parameter] = &spv.parameter_list().0[..] else {
+            panic!("expected a single parameter");
+        };
+        assert_eq!(parameter.name, "Device.WiFi.SSID.3.Enable");
+        assert_eq!(parameter.value.value, "false");
+        assert!(matches!(
+            &parameter.value.type_,
+            Some(ParameterValueType::Qualified { type_, .. }) if type_ == "boolean"
+        ));
+    }
+}
@@ -0,0 +1,343 @@
+//! Parameter paths split into their segments, refer TR-069 A.2.1 (names and
+//! partial paths), A.3.2.1 (wildcards) and A.2.2.1 (alias based addressing).
+use super::alias::validate_alias;
+use super::facets;
+use super::types::ParameterName;
+use crate::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Segment {
+    Name(String),
+    Instance(u32),
+    /// `*`, any instance of a multi-instance object.
+    Wildcard,
+    /// `[alias]`, an instance addressed by its `Alias` value.
+    Alias(String),
+}
+
+/// A parameter name (`Device.WiFi.SSID.1.SSID`) or a partial path
+/// (`Device.WiFi.SSID.1.`). The empty path is the partial path of the whole
+/// data model.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ParameterPath {
+    segments: Vec<Segment>,
+    object: bool,
+}
+
+impl ParameterPath {
+    pub(crate) fn from_segments(segments: Vec<Segment>, object: bool) -> Self {
+        Self { segments, object }
+    }
+
+    pub fn root() -> Self {
+        Self {
+            segments: Vec::new(),
+            object: true,
+        }
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Partial paths (ending in `.`) address objects.
+    pub fn is_object(&self) -> bool {
+        self.object
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn has_wildcard(&self) -> bool {
+        self.segments.contains(&Segment::Wildcard)
+    }
+
+    pub fn has_alias(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Alias(_)))
+    }
+
+    /// The last segment's name, `SSID` for both `Device.WiFi.SSID.` and
+    /// `Device.WiFi.SSID`. `None` for the root and for instance paths.
+    pub fn name(&self) -> Option<&str> {
+        match self.segments.last() {
+            Some(Segment::Name(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn instance_numbers(&self) -> Vec<u32> {
+        self.segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Instance(number) => Some(*number),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The enclosing object, `None` for the root.
+    pub fn parent(&self) -> Option<Self> {
+        let (_, segments) = self.segments.split_last()?;
+        Some(Self {
+            segments: segments.to_vec(),
+            object: true,
+        })
+    }
+
+    /// Appends a relative path (`SSID`, `1.`, `[cpe-1].Name`) to this object path.
+    pub fn join(&self, relative: &str) -> Result<Self, Error> {
+        if !self.object {
+            return Err(Error::InvalidParameterName(format!(
+                "'{self}' is not an object path."
+            )));
+        }
+        let relative = parse(relative, self.segments.is_empty())?;
+        if relative.segments.is_empty() {
+            return Err(Error::InvalidParameterName(
+                "Cannot join an empty path.".to_string(),
+            ));
+        }
+
+        let mut segments = self.segments.clone();
+        segments.extend(relative.segments);
+        Ok(Self {
+            segments,
+            object: relative.object,
+        })
+    }
+
+    /// Whether `self` is `other` or one of its descendants.
+    pub fn starts_with(&self, other: &Self) -> bool {
+        self.segments.starts_with(&other.segments)
+            && (other.object || self.segments.len() == other.segments.len())
+    }
+}
+
+fn parse(input: &str, first: bool) -> Result<ParameterPath, Error> {
+    if input.is_empty() {
+        return Ok(ParameterPath::root());
+    }
+
+    let (body, object) = match input.strip_suffix('.') {
+        Some(body) => (body, true),
+        None => (input, false),
+    };
+
+    let mut segments = Vec::new();
+    for (index, part) in body.split('.').enumerate() {
+        let segment = parse_segment(part)
+            .map_err(|reason| Error::InvalidParameterName(format!("'{input}': {reason}")))?;
+        if first && index == 0 && !matches!(segment, Segment::Name(_)) {
+            return Err(Error::InvalidParameterName(format!(
+                "'{input}': a path must start with an object name."
+            )));
+        }
+        segments.push(segment);
+    }
+
+    if !object && !matches!(segments.last(), Some(Segment::Name(_))) {
+        return Err(Error::InvalidParameterName(format!(
+            "'{input}': only parameter names can omit the trailing '.'."
+        )));
+    }
+
+    Ok(ParameterPath { segments, object })
+}
+
+fn parse_segment(part: &str) -> Result<Segment, String> {
+    if part.is_empty() {
+        return Err("empty segment".to_string());
+    }
+    if part == "*" {
+        return Ok(Segment::Wildcard);
+    }
+    if let Some(alias) = part.strip_prefix('[') {
+        let alias = alias
+            .strip_suffix(']')
+            .ok_or_else(|| format!("unterminated alias '{part}'"))?;
+        validate_alias(alias).map_err(|e| e.to_string())?;
+        return Ok(Segment::Alias(alias.to_string()));
+    }
+    if part.bytes().all(|b| b.is_ascii_digit()) {
+        // instance numbers start at 1 and have no leading zeros
+        if part.starts_with('0') {
+            return Err(format!("invalid instance number '{part}'"));
+        }
+        return part
+            .parse()
+            .map(Segment::Instance)
+            .map_err(|_| format!("invalid instance number '{part}'"));
+    }
+
+    let mut chars = part.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if !valid_start || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(format!("invalid name '{part}'"));
+    }
+    Ok(Segment::Name(part.to_string()))
+}
+
+impl FromStr for ParameterPath {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        facets::max_length("ParameterName", input, facets::PARAMETER_NAME)?;
+        parse(input, true)
+    }
+}
+
+impl TryFrom<String> for ParameterPath {
+    type Error = Error;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        input.parse()
+    }
+}
+
+impl TryFrom<&ParameterName> for ParameterPath {
+    type Error = Error;
+
+    fn try_from(input: &ParameterName) -> Result<Self, Self::Error> {
+        input.to_string().parse()
+    }
+}
+
+impl TryFrom<&ParameterPath> for ParameterName {
+    type Error = Error;
+
+    fn try_from(input: &ParameterPath) -> Result<Self, Self::Error> {
+        if input.is_root() {
+            return Ok(ParameterName::Path(String::new()));
+        }
+        input.to_string().try_into()
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => f.write_str(name),
+            Self::Instance(number) => write!(f, "{number}"),
+            Self::Wildcard => f.write_str("*"),
+            Self::Alias(alias) => write!(f, "[{alias}]"),
+        }
+    }
+}
+
+impl fmt::Display for ParameterPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, segment) in self.segments.iter().enumerate() {
+            if index > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{segment}")?;
+        }
+        if self.object && !self.segments.is_empty() {
+            f.write_str(".")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn path(input: &str) -> ParameterPath {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_segments() {
+        let ssid = path("Device.WiFi.SSID.1.SSID");
+        assert!(!ssid.is_object());
+        assert_eq!(ssid.name(), Some("SSID"));
+        assert_eq!(ssid.instance_numbers(), vec![1]);
+        assert_eq!(ssid.to_string(), "Device.WiFi.SSID.1.SSID");
+
+        let wildcard = path("Device.WiFi.SSID.*.");
+        assert!(wildcard.is_object() && wildcard.has_wildcard());
+        assert_eq!(wildcard.to_string(), "Device.WiFi.SSID.*.");
+
+        let alias = path("Device.WiFi.SSID.[cpe-guest].Enable");
+        assert!(alias.has_alias());
+        assert_eq!(alias.segments()[3], Segment::Alias("cpe-guest".to_string()));
+
+        assert!(path("").is_root());
+        for invalid in [
+            "Device..WiFi",
+            "1.WiFi.",
+            "Device.WiFi.SSID.01.",
+            "Device.WiFi.SSID.1",
+            "Device.[cpe.",
+            "Device.Wi Fi.",
+        ] {
+            assert!(invalid.parse::<ParameterPath>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn parent_and_join() {
+        let ssid = path("Device.WiFi.SSID.1.SSID");
+        assert_eq!(ssid.parent(), Some(path("Device.WiFi.SSID.1.")));
+        assert_eq!(
+            ssid.parent().and_then(|p| p.parent()),
+            Some(path("Device.WiFi.SSID."))
+        );
+        assert_eq!(path("Device.").parent(), Some(ParameterPath::root()));
+        assert_eq!(ParameterPath::root().parent(), None);
+
+        let table = path("Device.WiFi.SSID.");
+        assert_eq!(
+            table.join("2.SSID").unwrap(),
+            path("Device.WiFi.SSID.2.SSID")
+        );
+        assert_eq!(
+            table.join("[cpe-1].").unwrap(),
+            path("Device.WiFi.SSID.[cpe-1].")
+        );
+        assert!(ssid.join("Name").is_err());
+        assert!(ParameterPath::root().join("1.").is_err());
+
+        assert!(ssid.starts_with(&table));
+        assert!(!table.starts_with(&ssid));
+    }
+
+    #[test]
+    fn ordering_uses_instance_numbers() {
+        let mut paths = vec![
+            path("Device.WiFi.SSID.10.SSID"),
+            path("Device.WiFi.SSID.2.SSID"),
+            path("Device.WiFi.SSID.2."),
+        ];
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                path("Device.WiFi.SSID.2."),
+                path("Device.WiFi.SSID.2.SSID"),
+                path("Device.WiFi.SSID.10.SSID"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parameter_name_conversion() {
+        let name: ParameterName = (&path("Device.WiFi.SSID.*.SSID")).try_into().unwrap();
+        assert!(matches!(name, ParameterName::WildCard(_)));
+
+        let name: ParameterName = (&path("Device.WiFi.")).try_into().unwrap();
+        assert!(matches!(name, ParameterName::Path(_)));
+        assert_eq!(
+            ParameterPath::try_from(&name).unwrap(),
+            path("Device.WiFi.")
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Masking of credentials and sensitive parameter values, so RPCs can be
+//! logged and `dbg!`ed without leaking passwords or WiFi passphrases.
+use super::matcher::PatternSet;
+use super::path::ParameterPath;
+use super::types::{Download, ParameterValueStruct};
+use crate::error::Error;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::LazyLock;
+
+/// Written in place of a masked value.
+pub const REDACTED: &str = "********";
+
+/// Parameters whose values are masked. The default set covers the
+/// passwords, passphrases and keys of the Device:2 and InternetGatewayDevice:1
+/// data models.
+const DEFAULT_PATTERNS: [&str; 8] = [
+    "*.Password",
+    "*.KeyPassphrase",
+    "*.PreSharedKey",
+    "*.SAEPassphrase",
+    "*.WEPKey",
+    "*.ConnectionRequestPassword",
+    "*.STUNPassword",
+    "*.Secret",
+];
+
+static DEFAULT: LazyLock<SensitiveParameters> = LazyLock::new(SensitiveParameters::default);
+
+/// A configurable set of sensitive parameter name patterns: `*.<Name>`
+/// matches the parameter `Name` anywhere in the data model, anything else is
+/// read as a [`ParameterPath`] pattern (`Device.Users.User.*.Password`,
+/// `Device.X_ACME_Secrets.`).
+#[derive(Debug)]
+pub struct SensitiveParameters {
+    names: HashSet<String>,
+    patterns: PatternSet,
+}
+
+impl SensitiveParameters {
+    pub fn empty() -> Self {
+        Self {
+            names: HashSet::new(),
+            patterns: PatternSet::default(),
+        }
+    }
+
+    pub fn insert(&mut self, pattern: &str) -> Result<(), Error> {
+        match pattern.strip_prefix("*.") {
+            Some(name) if !name.is_empty() && !name.contains(['.', '*', '[']) => {
+                self.names.insert(name.to_string());
+            }
+            _ => {
+                self.patterns.insert(pattern.parse()?);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_sensitive(&self, name: &str) -> bool {
+        let leaf = name.rsplit('.').next().unwrap_or(name);
+        if self.names.contains(leaf) {
+            return true;
+        }
+        !self.patterns.is_empty()
+            && name
+                .parse::<ParameterPath>()
+                .is_ok_and(|path| self.patterns.is_match(&path))
+    }
+}
+
+impl Default for SensitiveParameters {
+    fn default() -> Self {
+        let mut sensitive = Self::empty();
+        for pattern in DEFAULT_PATTERNS {
+            sensitive
+                .insert(pattern)
+                .expect("default patterns are valid");
+        }
+        sensitive
+    }
+}
+
+impl fmt::Debug for Download {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Download")
+            .field("command_key", &self.command_key)
+            .field("file_type", &self.file_type)
+            .field("url", &self.url)
+            .field("username", &REDACTED)
+            .field("password", &REDACTED)
+            .field("file_size", &self.file_size)
+            .field("target_file_name", &self.target_file_name)
+            .field("delay_seconds", &self.delay_seconds)
+            .field("success_url", &self.success_url)
+            .field("failure_url", &self.failure_url)
+            .finish()
+    }
+}
+
+/// Masks values of the default [`SensitiveParameters`].
+impl fmt::Debug for ParameterValueStruct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("ParameterValueStruct");
+        debug.field("name", &self.name);
+        if DEFAULT.is_sensitive(&self.name) {
+            debug.field("value", &REDACTED);
+        } else {
+            debug.field("value", &self.value);
+        }
+        debug.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sensitive_names() {
+        let mut sensitive = SensitiveParameters::default();
+        assert!(
+            sensitive.is_sensitive(
+                "InternetGatewayDevice.LANDevice.1.WLANConfiguration.1.KeyPassphrase"
+            )
+        );
+        assert!(sensitive.is_sensitive("Device.Users.User.2.Password"));
+        assert!(!sensitive.is_sensitive("Device.WiFi.SSID.1.SSID"));
+
+        sensitive.insert("Device.X_ACME_Secrets.").unwrap();
+        sensitive.insert("Device.WiFi.SSID.*.SSID").unwrap();
+        assert!(sensitive.is_sensitive("Device.X_ACME_Secrets.Token"));
+        assert!(sensitive.is_sensitive("Device.WiFi.SSID.1.SSID"));
+        assert!(sensitive.insert("Device..Broken").is_err());
+        assert!(!SensitiveParameters::empty().is_sensitive("Device.Users.User.2.Password"));
+    }
+
+    #[test]
+    fn debug_masks_credentials() {
+        let download = Download {
+            command_key: String::new(),
+            file_type: "1 Firmware Upgrade Image".to_string(),
+            url: "http://acs.example.com/firmware.bin".to_string(),
+            username: "admin".to_string(),
+            password: "hunter2".to_string(),
+            file_size: 0,
+            target_file_name: String::new(),
+            delay_seconds: 0,
+            success_url: String::new(),
+            failure_url: String::new(),
+        };
+        let debug = format!("{download:?}");
+        assert!(!debug.contains("admin") && !debug.contains("hunter2"));
+        assert!(debug.contains("firmware.bin"));
+    }
+}
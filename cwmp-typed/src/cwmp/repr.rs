@@ -0,0 +1,216 @@
+//! Serde representation of the typed rpcs, enabled by the `serde` feature.
+//!
+//! The layout is stable:
+//!
+//! - an [`Rpc`] is an object with the cwmp `version` (`"1.0"`, `"1.1"` or
+//!   `"1.2"`), the rpc element name as `method` and its arguments as `params`,
+//!   left out for rpcs without arguments;
+//! - struct fields keep their rust names, except `type_` which is `type`;
+//! - status and notification enums are their numeric codes;
+//! - parameter names, event codes and `xs:dateTime` values are strings, a
+//!   nil `ParameterPath` is `null`.
+//!
+//! ```json
+//! {
+//!   "version": "1.0",
+//!   "method": "SetParameterValues",
+//!   "params": {
+//!     "parameter_list": [
+//!       {
+//!         "name": "Device.WiFi.SSID.1.SSID",
+//!         "value": { "type": { "namespace": "http://www.w3.org/2001/XMLSchema", "prefix": "xsd", "type": "string" }, "value": "home" }
+//!       }
+//!     ],
+//!     "parameter_key": ""
+//!   }
+//! }
+//! ```
+//!
+//! Deserializing an [`Rpc`] checks the schema facets and the version of the
+//! method, like the conversions from the soap types do.
+use super::CwmpVersion;
+use super::datetime::CwmpDateTime;
+use super::event::EventCode;
+use super::facets::Facets;
+use super::rpc::{Rpc, RpcMethod};
+use super::types::{
+    AddObjectResponseStatus, AttributeNotificationValue, DeleteObjectResponseStatus,
+    DownloadResponseStatus, ParameterName, SetParameterValuesResponseStatus,
+};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+impl Serialize for CwmpVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            Self::_10 => "1.0",
+            Self::_11 => "1.1",
+            Self::_12 => "1.2",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for CwmpVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "1.0" => Ok(Self::_10),
+            "1.1" => Ok(Self::_11),
+            // 1.3 and 1.4 share the cwmp-1-2 namespace
+            "1.2" | "1.3" | "1.4" => Ok(Self::_12),
+            other => Err(D::Error::custom(format!(
+                "unsupported cwmp version '{other}'"
+            ))),
+        }
+    }
+}
+
+macro_rules! impl_serde_code {
+    ($($ty: ty { $($variant: ident => $code: literal),+ }),+) => {
+        $(
+            impl Serialize for $ty {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_u8(match self {
+                        $(Self::$variant => $code,)+
+                    })
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $ty {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    match u8::deserialize(deserializer)? {
+                        $($code => Ok(Self::$variant),)+
+                        other => Err(D::Error::custom(format!(
+                            "invalid {} '{other}'",
+                            stringify!($ty)
+                        ))),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_serde_code!(
+    AttributeNotificationValue { _0 => 0, _1 => 1, _2 => 2, _3 => 3, _4 => 4, _5 => 5, _6 => 6 },
+    SetParameterValuesResponseStatus { _0 => 0, _1 => 1 },
+    AddObjectResponseStatus { _0 => 0, _1 => 1 },
+    DeleteObjectResponseStatus { _0 => 0, _1 => 1 },
+    DownloadResponseStatus { _0 => 0, _1 => 1 }
+);
+
+impl Serialize for ParameterName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::None => serializer.serialize_none(),
+            name => serializer.serialize_str(&name.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ParameterName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            None => Ok(Self::None),
+            Some(name) => name.try_into().map_err(D::Error::custom),
+        }
+    }
+}
+
+impl Serialize for CwmpDateTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CwmpDateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for EventCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for EventCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+#[derive(Serialize)]
+struct RpcRef<'a> {
+    version: &'a CwmpVersion,
+    #[serde(flatten)]
+    method: &'a RpcMethod,
+}
+
+#[derive(Deserialize)]
+struct RpcRepr {
+    version: CwmpVersion,
+    #[serde(flatten)]
+    method: RpcMethod,
+}
+
+impl Serialize for Rpc {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RpcRef {
+            version: self.version(),
+            method: self.method(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rpc {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RpcRepr { version, method } = RpcRepr::deserialize(deserializer)?;
+        method.check_facets().map_err(D::Error::custom)?;
+        Ok(Rpc::from((version, method)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rpc_json_round_trip() {
+        let json = r#"{"version":"1.0","method":"SetParameterValues","params":{"parameter_list":[{"name":"Device.WiFi.SSID.1.SSID","value":{"type":"xsd:string","value":"home"}}],"parameter_key":"key"}}"#;
+        let rpc: Rpc = serde_json::from_str(json).unwrap();
+        assert!(matches!(rpc.method(), RpcMethod::SetParameterValues(_)));
+        assert_eq!(serde_json::to_string(&rpc).unwrap(), json);
+
+        let json = r#"{"version":"1.2","method":"FactoryReset"}"#;
+        let rpc: Rpc = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&rpc).unwrap(), json);
+
+        let json = r#"{"version":"1.0","method":"GetParameterNames","params":{"parameter_path":null,"next_level":true}}"#;
+        let rpc: Rpc = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&rpc).unwrap(), json);
+    }
+
+    #[test]
+    fn rpc_json_is_validated() {
+        let long_key = "k".repeat(33);
+        let json = format!(
+            r#"{{"version":"1.0","method":"Reboot","params":{{"command_key":"{long_key}"}}}}"#
+        );
+        assert!(serde_json::from_str::<Rpc>(&json).is_err());
+        assert!(
+            serde_json::from_str::<Rpc>(
+                r#"{"version":"1.0","method":"GetParameterValues","params":{"parameter_names":["Device.*"]}}"#
+            )
+            .is_err()
+        );
+        assert!(
+            serde_json::from_str::<Rpc>(r#"{"version":"2.0","method":"FactoryReset"}"#).is_err()
+        );
+    }
+}
@@ -1,9 +1,11 @@
 use super::CwmpVersion;
+use super::facets;
 use super::types::{
     AddObject, AddObjectResponse, DeleteObject, DeleteObjectResponse, Download, DownloadResponse,
     GetParameterAttributesResponse, GetParameterNames, GetParameterNamesResponse,
-    GetParameterValuesResponse, GetRPCMethodsResponse, ParameterList, ParameterNames,
-    ParameterValueStruct, Reboot, SetParameterAttributesStruct, SetParameterValuesResponse,
+    GetParameterValuesResponse, GetRPCMethodsResponse, Inform, InformResponse, ParameterList,
+    ParameterNames, ParameterValueStruct, Reboot, SetParameterAttributesStruct,
+    SetParameterValuesResponse,
 };
 use crate::error::Error;
 use std::borrow::Cow;
@@ -13,6 +15,8 @@ use xsd_parser_types::xml::AnyElement;
 pub struct Rpc(CwmpVersion, RpcMethod);
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "method", content = "params"))]
 pub enum RpcMethod {
     GetRPCMethods,
     GetParameterNames(GetParameterNames),
@@ -36,20 +40,25 @@ pub enum RpcMethod {
     RebootResponse,
     FactoryResetResponse,
     DownloadResponse(DownloadResponse),
+    Inform(Inform),
+    InformResponse(InformResponse),
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetParameterValues {
     pub(crate) parameter_names: ParameterNames,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetParameterValues {
     pub(crate) parameter_list: ParameterList<ParameterValueStruct>,
     pub(crate) parameter_key: String,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetParameterAttributes {
     pub(crate) parameter_list: ParameterList<SetParameterAttributesStruct>,
 }
@@ -60,6 +69,52 @@ impl From<(CwmpVersion, RpcMethod)> for Rpc {
     }
 }
 
+impl Rpc {
+    pub fn version(&self) -> &CwmpVersion {
+        &self.0
+    }
+
+    pub fn method(&self) -> &RpcMethod {
+        &self.1
+    }
+
+    pub fn method_mut(&mut self) -> &mut RpcMethod {
+        &mut self.1
+    }
+}
+
+impl RpcMethod {
+    /// Element name of the rpc as it appears in the soap body.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::GetRPCMethods => "GetRPCMethods",
+            Self::GetParameterNames(_) => "GetParameterNames",
+            Self::SetParameterValues(_) => "SetParameterValues",
+            Self::GetParameterValues(_) => "GetParameterValues",
+            Self::SetParameterAttributes(_) => "SetParameterAttributes",
+            Self::GetParameterAttributes(_) => "GetParameterAttributes",
+            Self::AddObject(_) => "AddObject",
+            Self::DeleteObject(_) => "DeleteObject",
+            Self::Reboot(_) => "Reboot",
+            Self::FactoryReset => "FactoryReset",
+            Self::Download(_) => "Download",
+            Self::GetRPCMethodsResponse(_) => "GetRPCMethodsResponse",
+            Self::GetParameterNamesResponse(_) => "GetParameterNamesResponse",
+            Self::SetParameterValuesResponse(_) => "SetParameterValuesResponse",
+            Self::GetParameterValuesResponse(_) => "GetParameterValuesResponse",
+            Self::SetParameterAttributesResponse => "SetParameterAttributesResponse",
+            Self::GetParameterAttributesResponse(_) => "GetParameterAttributesResponse",
+            Self::AddObjectResponse(_) => "AddObjectResponse",
+            Self::DeleteObjectResponse(_) => "DeleteObjectResponse",
+            Self::RebootResponse => "RebootResponse",
+            Self::FactoryResetResponse => "FactoryResetResponse",
+            Self::DownloadResponse(_) => "DownloadResponse",
+            Self::Inform(_) => "Inform",
+            Self::InformResponse(_) => "InformResponse",
+        }
+    }
+}
+
 impl TryFrom<cwmp_xsd_schema::soapenv::BodyTypeContent> for Rpc {
     type Error = Error;
     fn try_from(input: cwmp_xsd_schema::soapenv::BodyTypeContent) -> Result<Self, Self::Error> {
@@ -101,6 +156,7 @@ impl TryFrom<cwmp_xsd_schema::soapenv::BodyTypeContent> for Rpc {
                 CwmpVersion::_11,
                 RpcMethod::GetParameterNames((inner.parameter_path, inner.next_level).try_into()?),
             ))),
+            // not nillable in the cwmp-1-2 schema
             RpcBody::GetParameterNames12(inner) => Ok(Rpc::from((
                 CwmpVersion::_12,
                 RpcMethod::GetParameterNames(
@@ -145,39 +201,39 @@ impl TryFrom<cwmp_xsd_schema::soapenv::BodyTypeContent> for Rpc {
             ))),
             RpcBody::AddObject10(inner) => Ok(Rpc::from((
                 CwmpVersion::_10,
-                RpcMethod::AddObject((inner.object_name, inner.parameter_key).into()),
+                RpcMethod::AddObject((inner.object_name, inner.parameter_key).try_into()?),
             ))),
             RpcBody::AddObject11(inner) => Ok(Rpc::from((
                 CwmpVersion::_11,
-                RpcMethod::AddObject((inner.object_name, inner.parameter_key).into()),
+                RpcMethod::AddObject((inner.object_name, inner.parameter_key).try_into()?),
             ))),
             RpcBody::AddObject12(inner) => Ok(Rpc::from((
                 CwmpVersion::_12,
-                RpcMethod::AddObject((inner.object_name, inner.parameter_key).into()),
+                RpcMethod::AddObject((inner.object_name, inner.parameter_key).try_into()?),
             ))),
             RpcBody::DeleteObject10(inner) => Ok(Rpc::from((
                 CwmpVersion::_10,
-                RpcMethod::DeleteObject((inner.object_name, inner.parameter_key).into()),
+                RpcMethod::DeleteObject((inner.object_name, inner.parameter_key).try_into()?),
             ))),
             RpcBody::DeleteObject11(inner) => Ok(Rpc::from((
                 CwmpVersion::_11,
-                RpcMethod::DeleteObject((inner.object_name, inner.parameter_key).into()),
+                RpcMethod::DeleteObject((inner.object_name, inner.parameter_key).try_into()?),
             ))),
             RpcBody::DeleteObject12(inner) => Ok(Rpc::from((
                 CwmpVersion::_12,
-                RpcMethod::DeleteObject((inner.object_name, inner.parameter_key).into()),
+                RpcMethod::DeleteObject((inner.object_name, inner.parameter_key).try_into()?),
             ))),
             RpcBody::Reboot10(inner) => Ok(Rpc::from((
                 CwmpVersion::_10,
-                RpcMethod::Reboot(inner.command_key.into()),
+                RpcMethod::Reboot(inner.command_key.try_into()?),
             ))),
             RpcBody::Reboot11(inner) => Ok(Rpc::from((
                 CwmpVersion::_11,
-                RpcMethod::Reboot(inner.command_key.into()),
+                RpcMethod::Reboot(inner.command_key.try_into()?),
             ))),
             RpcBody::Reboot12(inner) => Ok(Rpc::from((
                 CwmpVersion::_12,
-                RpcMethod::Reboot(inner.command_key.into()),
+                RpcMethod::Reboot(inner.command_key.try_into()?),
             ))),
             RpcBody::FactoryReset10(_) => {
                 Ok(Rpc::from((CwmpVersion::_10, RpcMethod::FactoryReset)))
@@ -190,15 +246,15 @@ impl TryFrom<cwmp_xsd_schema::soapenv::BodyTypeContent> for Rpc {
             }
             RpcBody::Download10(inner) => Ok(Rpc::from((
                 CwmpVersion::_10,
-                RpcMethod::Download(inner.into()),
+                RpcMethod::Download(inner.try_into()?),
             ))),
             RpcBody::Download11(inner) => Ok(Rpc::from((
                 CwmpVersion::_11,
-                RpcMethod::Download(inner.into()),
+                RpcMethod::Download(inner.try_into()?),
             ))),
             RpcBody::Download12(inner) => Ok(Rpc::from((
                 CwmpVersion::_12,
-                RpcMethod::Download(inner.into()),
+                RpcMethod::Download(inner.try_into()?),
             ))),
             RpcBody::GetRpcMethodsResponse10(inner) => Ok(Rpc::from((
                 CwmpVersion::_10,
@@ -348,27 +404,51 @@ impl TryFrom<cwmp_xsd_schema::soapenv::BodyTypeContent> for Rpc {
                 CwmpVersion::_10,
                 RpcMethod::DownloadResponse(DownloadResponse::new(
                     inner.status,
-                    inner.start_time,
-                    inner.complete_time,
+                    inner.start_time.parse()?,
+                    inner.complete_time.parse()?,
                 )),
             ))),
             RpcBody::DownloadResponse11(inner) => Ok(Rpc::from((
                 CwmpVersion::_11,
                 RpcMethod::DownloadResponse(DownloadResponse::new(
                     inner.status,
-                    inner.start_time,
-                    inner.complete_time,
+                    inner.start_time.parse()?,
+                    inner.complete_time.parse()?,
                 )),
             ))),
             RpcBody::DownloadResponse12(inner) => Ok(Rpc::from((
                 CwmpVersion::_12,
                 RpcMethod::DownloadResponse(DownloadResponse::new(
                     inner.status,
-                    inner.start_time,
-                    inner.complete_time,
+                    inner.start_time.parse()?,
+                    inner.complete_time.parse()?,
                 )),
             ))),
-            _ => unimplemented!(),
+            RpcBody::Inform10(inner) => Ok(Rpc::from((
+                CwmpVersion::_10,
+                RpcMethod::Inform(inner.try_into()?),
+            ))),
+            RpcBody::Inform11(inner) => Ok(Rpc::from((
+                CwmpVersion::_11,
+                RpcMethod::Inform(inner.try_into()?),
+            ))),
+            RpcBody::Inform12(inner) => Ok(Rpc::from((
+                CwmpVersion::_12,
+                RpcMethod::Inform(inner.try_into()?),
+            ))),
+            RpcBody::InformResponse10(inner) => Ok(Rpc::from((
+                CwmpVersion::_10,
+                RpcMethod::InformResponse(InformResponse::new(inner.max_envelopes)),
+            ))),
+            RpcBody::InformResponse11(inner) => Ok(Rpc::from((
+                CwmpVersion::_11,
+                RpcMethod::InformResponse(InformResponse::new(inner.max_envelopes)),
+            ))),
+            RpcBody::InformResponse12(inner) => Ok(Rpc::from((
+                CwmpVersion::_12,
+                RpcMethod::InformResponse(InformResponse::new(inner.max_envelopes)),
+            ))),
+            _ => Err(Error::UnknownRpc),
         }
     }
 }
@@ -396,6 +476,7 @@ impl TryFrom<(String, cwmp_xsd_schema::soapenc::ArrayType)> for SetParameterValu
     fn try_from(
         (parameter_key, parameter_list): (String, cwmp_xsd_schema::soapenc::ArrayType),
     ) -> Result<Self, Self::Error> {
+        facets::max_length("ParameterKey", &parameter_key, facets::PARAMETER_KEY)?;
         Ok(Self {
             parameter_key,
             parameter_list: parameter_list.try_into()?,
@@ -403,6 +484,38 @@ impl TryFrom<(String, cwmp_xsd_schema::soapenc::ArrayType)> for SetParameterValu
     }
 }
 
+impl TryFrom<(String, ParameterList<ParameterValueStruct>)> for SetParameterValues {
+    type Error = Error;
+    fn try_from(
+        (parameter_key, parameter_list): (String, ParameterList<ParameterValueStruct>),
+    ) -> Result<Self, Self::Error> {
+        facets::max_length("ParameterKey", &parameter_key, facets::PARAMETER_KEY)?;
+        for parameter in &parameter_list.0 {
+            facets::max_length("Name", &parameter.name, facets::PARAMETER_NAME)?;
+        }
+        Ok(Self {
+            parameter_key,
+            parameter_list,
+        })
+    }
+}
+
+impl GetParameterValues {
+    pub fn parameter_names(&self) -> &ParameterNames {
+        &self.parameter_names
+    }
+}
+
+impl SetParameterValues {
+    pub fn parameter_list(&self) -> &ParameterList<ParameterValueStruct> {
+        &self.parameter_list
+    }
+
+    pub fn parameter_key(&self) -> &str {
+        &self.parameter_key
+    }
+}
+
 #[cfg(test)]
 mod test {
 
@@ -453,6 +566,7 @@ mod test {
 
         let rpc: Rpc = envelope.body.content.try_into().unwrap();
         dbg!(&rpc);
+        assert!(!format!("{rpc:?}").contains("SuperSecurePass123!"));
     }
 
     #[test]
@@ -860,5 +974,68 @@ mod test {
 
         let rpc: Rpc = envelope.body.content.try_into().unwrap();
         dbg!(&rpc);
+        let RpcMethod::DownloadResponse(inner) = rpc.method() else {
+            panic!();
+        };
+        assert!(inner.complete_time.is_unknown());
+        assert_eq!(inner.start_time.to_string(), "2024-01-01T10:00:00Z");
+    }
+
+    #[test]
+    fn deserialize_inform() {
+        let soap = r#"
+<soapenv:Envelope
+  xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/"
+  xmlns:soap="http://schemas.xmlsoap.org/soap/encoding/"
+  xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+  xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+  xmlns:cwmp="urn:dslforum-org:cwmp-1-0">
+  <soapenv:Header>
+    <cwmp:ID soapenv:mustUnderstand="1">1</cwmp:ID>
+  </soapenv:Header>
+  <soapenv:Body>
+    <cwmp:Inform>
+      <DeviceId>
+        <Manufacturer>Example</Manufacturer>
+        <OUI>00D09E</OUI>
+        <ProductClass>IGD</ProductClass>
+        <SerialNumber>SN0001</SerialNumber>
+      </DeviceId>
+      <Event soap:arrayType="cwmp:EventStruct[2]">
+        <EventStruct>
+          <EventCode>1 BOOT</EventCode>
+          <CommandKey></CommandKey>
+        </EventStruct>
+        <EventStruct>
+          <EventCode>M Reboot</EventCode>
+          <CommandKey>reboot-1</CommandKey>
+        </EventStruct>
+      </Event>
+      <MaxEnvelopes>1</MaxEnvelopes>
+      <CurrentTime>2024-01-01T10:00:00Z</CurrentTime>
+      <RetryCount>0</RetryCount>
+      <ParameterList soap:arrayType="cwmp:ParameterValueStruct[1]">
+        <ParameterValueStruct>
+          <Name>Device.DeviceInfo.SoftwareVersion</Name>
+          <Value xsi:type="xsd:string">1.0.0</Value>
+        </ParameterValueStruct>
+      </ParameterList>
+    </cwmp:Inform>
+  </soapenv:Body>
+</soapenv:Envelope>
+"#;
+
+        let cursor = Cursor::new(soap);
+        let mut reader = IoReader::new(cursor).with_error_info();
+        let envelope = EnvelopeType::deserialize(&mut reader).unwrap();
+
+        let rpc: Rpc = envelope.body.content.try_into().unwrap();
+        let RpcMethod::Inform(inner) = rpc.method() else {
+            panic!();
+        };
+        assert_eq!(inner.device_id().key(), "00D09E-IGD-SN0001");
+        assert_eq!(inner.event().len(), 2);
+        assert_eq!(inner.event()[1].command_key(), "reboot-1");
+        assert_eq!(inner.parameter_list().0.len(), 1);
     }
 }
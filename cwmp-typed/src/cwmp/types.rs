@@ -1,3 +1,8 @@
+use super::datetime::CwmpDateTime;
+use super::device::DeviceId;
+use super::event::EventCode;
+use super::facets::{self, Facets};
+use super::path::ParameterPath;
 use crate::error::Error;
 use cwmp_xsd_types::XsiType;
 
@@ -60,38 +65,53 @@ pub type SetParameterAttributesNotification = AttributeNotificationValue;
 pub type ParameterAttributeStructNotification = AttributeNotificationValue;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParameterList<T>(pub Vec<T>);
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AccessList(pub Vec<AccessListMember>);
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AccessListMember {
     Subscriber,
 }
 
+impl AccessListMember {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Subscriber => "Subscriber",
+        }
+    }
+}
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetParameterAttributesStruct {
-    name: Option<String>,
-    notification_change: bool,
-    notification: SetParameterAttributesNotification,
-    access_list_change: bool,
+    pub(crate) name: Option<String>,
+    pub(crate) notification_change: bool,
+    pub(crate) notification: SetParameterAttributesNotification,
+    pub(crate) access_list_change: bool,
     // i have a feeling some vendors implement this wrong and not send the
     // attribute instead of doing soap-enc:arrayType="xsd:string[0]"
-    access_list: AccessList,
+    pub(crate) access_list: AccessList,
 }
 
-#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParameterValueStruct {
     pub name: String,
     pub value: ParameterValueStructValue,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
 pub enum ParameterValueType {
     Qualified {
         namespace: String,
         prefix: String,
+        #[cfg_attr(feature = "serde", serde(rename = "type"))]
         type_: String,
     },
     Unqualified(String),
@@ -111,18 +131,22 @@ impl ToString for ParameterValueType {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParameterValueStructValue {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub type_: Option<ParameterValueType>,
     pub value: String,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetParameterNames {
-    parameter_path: ParameterName,
-    next_level: bool,
+    pub(crate) parameter_path: ParameterName,
+    pub(crate) next_level: bool,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParameterNames(pub Vec<ParameterName>);
 
 #[derive(Debug)]
@@ -135,6 +159,7 @@ pub enum ParameterName {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddObject {
     pub object_name: String,
     pub parameter_key: String,
@@ -143,11 +168,12 @@ pub struct AddObject {
 pub type DeleteObject = AddObject;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Reboot {
-    command_key: String,
+    pub(crate) command_key: String,
 }
 
-#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Download {
     pub command_key: String,
     pub file_type: String,
@@ -162,23 +188,27 @@ pub struct Download {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MethodList(pub Vec<String>);
 
 #[derive(Debug)]
 pub struct MethodNames(pub Vec<String>);
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetParameterNamesResponse {
     pub parameter_list: ParameterList<ParameterInfoStruct>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParameterInfoStruct {
     pub name: String,
     pub writable: bool,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetParameterValuesResponse {
     pub status: SetParameterValuesResponseStatus,
 }
@@ -192,6 +222,7 @@ impl SetParameterValuesResponse {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetParameterValuesResponse {
     pub parameter_list: ParameterList<ParameterValueStruct>,
 }
@@ -207,6 +238,7 @@ impl GetParameterValuesResponse {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetParameterAttributesResponse {
     pub parameter_list: ParameterList<ParameterAttributeStruct>,
 }
@@ -222,6 +254,7 @@ impl GetParameterAttributesResponse {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParameterAttributeStruct {
     pub name: String,
     pub notification: ParameterAttributeStructNotification,
@@ -236,6 +269,7 @@ where
     type Error = Error;
 
     fn try_from(input: (String, N, Box<A>)) -> Result<Self, Self::Error> {
+        facets::max_length("Name", &input.0, facets::PARAMETER_NAME)?;
         Ok(Self {
             name: input.0,
             notification: input.1.into(),
@@ -245,9 +279,10 @@ where
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddObjectResponse {
-    instance_number: u32,
-    status: AddObjectResponseStatus,
+    pub(crate) instance_number: u32,
+    pub(crate) status: AddObjectResponseStatus,
 }
 
 impl AddObjectResponse {
@@ -260,8 +295,9 @@ impl AddObjectResponse {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeleteObjectResponse {
-    status: DeleteObjectResponseStatus,
+    pub(crate) status: DeleteObjectResponseStatus,
 }
 
 impl DeleteObjectResponse {
@@ -273,17 +309,18 @@ impl DeleteObjectResponse {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DownloadResponse {
-    status: DownloadResponseStatus,
-    start_time: String,
-    complete_time: String,
+    pub(crate) status: DownloadResponseStatus,
+    pub(crate) start_time: CwmpDateTime,
+    pub(crate) complete_time: CwmpDateTime,
 }
 
 impl DownloadResponse {
     pub fn new<T: Into<DownloadResponseStatus>>(
         status: T,
-        start_time: String,
-        complete_time: String,
+        start_time: CwmpDateTime,
+        complete_time: CwmpDateTime,
     ) -> Self {
         Self {
             status: status.into(),
@@ -294,8 +331,114 @@ impl DownloadResponse {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetRPCMethodsResponse {
-    method_list: MethodList,
+    pub(crate) method_list: MethodList,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventStruct {
+    pub(crate) event_code: EventCode,
+    pub(crate) command_key: String,
+}
+
+impl EventStruct {
+    pub fn new(event_code: EventCode, command_key: String) -> Result<Self, Error> {
+        facets::max_length("CommandKey", &command_key, facets::COMMAND_KEY)?;
+        Ok(Self {
+            event_code,
+            command_key,
+        })
+    }
+
+    pub fn event_code(&self) -> &EventCode {
+        &self.event_code
+    }
+
+    /// The command key of the method the event reports, empty otherwise.
+    pub fn command_key(&self) -> &str {
+        &self.command_key
+    }
+}
+
+impl TryFrom<(String, String)> for EventStruct {
+    type Error = Error;
+    fn try_from((event_code, command_key): (String, String)) -> Result<Self, Self::Error> {
+        Self::new(event_code.parse()?, command_key)
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Inform {
+    pub(crate) device_id: DeviceId,
+    pub(crate) event: ParameterList<EventStruct>,
+    pub(crate) max_envelopes: u32,
+    pub(crate) current_time: CwmpDateTime,
+    pub(crate) retry_count: u32,
+    pub(crate) parameter_list: ParameterList<ParameterValueStruct>,
+}
+
+impl Inform {
+    pub fn new(
+        device_id: DeviceId,
+        event: Vec<EventStruct>,
+        max_envelopes: u32,
+        current_time: CwmpDateTime,
+        retry_count: u32,
+        parameter_list: ParameterList<ParameterValueStruct>,
+    ) -> Self {
+        Self {
+            device_id,
+            event: ParameterList(event),
+            max_envelopes,
+            current_time,
+            retry_count,
+            parameter_list,
+        }
+    }
+
+    pub fn device_id(&self) -> &DeviceId {
+        &self.device_id
+    }
+
+    pub fn event(&self) -> &[EventStruct] {
+        &self.event.0
+    }
+
+    pub fn max_envelopes(&self) -> u32 {
+        self.max_envelopes
+    }
+
+    pub fn current_time(&self) -> &CwmpDateTime {
+        &self.current_time
+    }
+
+    /// Consecutive failed attempts to deliver this Inform, 0 on the first.
+    pub fn retry_count(&self) -> u32 {
+        self.retry_count
+    }
+
+    pub fn parameter_list(&self) -> &ParameterList<ParameterValueStruct> {
+        &self.parameter_list
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InformResponse {
+    pub(crate) max_envelopes: u32,
+}
+
+impl InformResponse {
+    pub fn new(max_envelopes: u32) -> Self {
+        Self { max_envelopes }
+    }
+
+    pub fn max_envelopes(&self) -> u32 {
+        self.max_envelopes
+    }
 }
 
 impl TryFrom<cwmp_xsd_schema::soapenc::ArrayType> for GetRPCMethodsResponse {
@@ -321,11 +464,16 @@ impl TryFrom<cwmp_xsd_schema::soapenc::ArrayType> for GetParameterNamesResponse
 impl TryFrom<String> for ParameterName {
     type Error = Error;
     fn try_from(input: String) -> Result<Self, Self::Error> {
+        facets::max_length("ParameterName", &input, facets::PARAMETER_NAME)?;
         if input.ends_with('*') || input.ends_with("*.") {
             return Err(Error::InvalidParameterName(
                 "Wildcard '*' cannot be the last part of a path.".to_string(),
             ));
         }
+        // alias based addressing follows the rules of `cwmp::alias`
+        if input.contains(['[', ']']) {
+            input.parse::<ParameterPath>()?;
+        }
 
         let partial = input.ends_with('.');
         let wildcard = input.contains('*');
@@ -356,6 +504,7 @@ impl ToString for ParameterName {
 impl TryFrom<(String, cwmp_xsd_schema::ValueType)> for ParameterValueStruct {
     type Error = Error;
     fn try_from((name, value): (String, cwmp_xsd_schema::ValueType)) -> Result<Self, Self::Error> {
+        facets::max_length("Name", &name, facets::PARAMETER_NAME)?;
         let value = {
             ParameterValueStructValue {
                 //XXX this actually handles the case where the value of `xsi:type="xsd:string"`,
@@ -377,7 +526,7 @@ fn extract_array_content<T, I, F>(
     filter_expr: F,
 ) -> Result<T, Error>
 where
-    F: Fn(cwmp_xsd_schema::soapenc::ArrayTypeContent) -> Option<I>,
+    F: Fn(cwmp_xsd_schema::soapenc::ArrayTypeContent) -> Result<Option<I>, Error>,
     T: From<Vec<I>>,
 {
     let Some(array_type) = soap_array.array_type else {
@@ -396,8 +545,9 @@ where
 
     let elements = content
         .into_iter()
-        .filter_map(filter_expr)
-        .collect::<Vec<I>>();
+        .map(filter_expr)
+        .filter_map(Result::transpose)
+        .collect::<Result<Vec<I>, Error>>()?;
 
     if elements.len() > elem_length {
         return Err(Error::InvalidValue(format!(
@@ -476,33 +626,41 @@ impl From<cwmp_xsd_schema::cwmp_12::ParameterAttributeNotificationValueType>
     }
 }
 
-impl From<(String, String)> for AddObject {
-    fn from((object_name, parameter_key): (String, String)) -> AddObject {
-        AddObject {
+impl TryFrom<(String, String)> for AddObject {
+    type Error = Error;
+    fn try_from((object_name, parameter_key): (String, String)) -> Result<AddObject, Self::Error> {
+        let object = AddObject {
             object_name,
             parameter_key,
-        }
+        };
+        object.check_facets()?;
+        Ok(object)
     }
 }
 
-impl From<(String, bool)> for ParameterInfoStruct {
-    fn from((name, writable): (String, bool)) -> Self {
-        ParameterInfoStruct { name, writable }
+impl TryFrom<(String, bool)> for ParameterInfoStruct {
+    type Error = Error;
+    fn try_from((name, writable): (String, bool)) -> Result<Self, Self::Error> {
+        facets::max_length("Name", &name, facets::PARAMETER_NAME)?;
+        Ok(ParameterInfoStruct { name, writable })
     }
 }
 
-impl From<String> for Reboot {
-    fn from(command_key: String) -> Reboot {
-        Reboot { command_key }
+impl TryFrom<String> for Reboot {
+    type Error = Error;
+    fn try_from(command_key: String) -> Result<Reboot, Self::Error> {
+        facets::max_length("CommandKey", &command_key, facets::COMMAND_KEY)?;
+        Ok(Reboot { command_key })
     }
 }
 
-macro_rules! impl_from_download{
+macro_rules! impl_try_from_download{
     ($($t:ty),*) => {
         $(
-            impl From<$t> for Download {
-                fn from(v: $t) -> Self {
-                    Self {
+            impl TryFrom<$t> for Download {
+                type Error = Error;
+                fn try_from(v: $t) -> Result<Self, Self::Error> {
+                    let download = Self {
                         command_key: v.command_key,
                         file_type: v.file_type,
                         url: v.url,
@@ -513,19 +671,47 @@ macro_rules! impl_from_download{
                         delay_seconds: v.delay_seconds,
                         success_url: v.success_url,
                         failure_url: v.failure_url,
-                    }
+                    };
+                    download.check_facets()?;
+                    Ok(download)
                 }
             }
         )*
     };
 }
 
-impl_from_download!(
+impl_try_from_download!(
     cwmp_xsd_schema::DownloadElementType,
     cwmp_xsd_schema::tns::DownloadElementType,
     cwmp_xsd_schema::cwmp_12::DownloadElementType
 );
 
+macro_rules! impl_try_from_inform {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<$t> for Inform {
+                type Error = Error;
+                fn try_from(v: $t) -> Result<Self, Self::Error> {
+                    Ok(Self {
+                        device_id: v.device_id.try_into()?,
+                        event: v.event.try_into()?,
+                        max_envelopes: v.max_envelopes,
+                        current_time: v.current_time.parse()?,
+                        retry_count: v.retry_count,
+                        parameter_list: v.parameter_list.try_into()?,
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_inform!(
+    cwmp_xsd_schema::InformElementType,
+    cwmp_xsd_schema::tns::InformElementType,
+    cwmp_xsd_schema::cwmp_12::InformElementType
+);
+
 macro_rules! impl_try_from_array_type {
     ($ty: ty, $inner: expr ) => {
         impl TryFrom<cwmp_xsd_schema::soapenc::ArrayType> for $ty {
@@ -547,23 +733,18 @@ impl_try_from_array_type! {
 
         let values = |content: ArrayTypeContent|  {
 
-            let log_err = |e: &Error| eprintln!("Error converting to ParameterValueStruct: {e}");
-
             match content {
                 ArrayTypeContent::ParameterValueStruct10(inner) => (inner.name, inner.value)
                     .try_into()
-                    .inspect_err(log_err)
-                    .ok(),
+                    .map(Some),
                 ArrayTypeContent::ParameterValueStruct11(inner) => (inner.name, inner.value)
                     .try_into()
-                    .inspect_err(log_err)
-                    .ok(),
+                    .map(Some),
                 ArrayTypeContent::ParameterValueStruct12(inner) => (inner.name, inner.value)
                     .try_into()
-                    .inspect_err(log_err)
-                    .ok(),
+                    .map(Some),
 
-                _ => None,
+                _ => Ok(None),
             }
         };
 
@@ -580,10 +761,16 @@ impl_try_from_array_type! {
         let values = |content: ArrayTypeContent|  {
 
             match content {
-                ArrayTypeContent::ParameterInfoStruct10(inner) => Some((inner.name, inner.writable).into()),
-                ArrayTypeContent::ParameterInfoStruct11(inner) => Some((inner.name, inner.writable).into()),
-                ArrayTypeContent::ParameterInfoStruct12(inner) => Some((inner.name, inner.writable).into()),
-                _ => None,
+                ArrayTypeContent::ParameterInfoStruct10(inner) => (inner.name, inner.writable)
+                    .try_into()
+                    .map(Some),
+                ArrayTypeContent::ParameterInfoStruct11(inner) => (inner.name, inner.writable)
+                    .try_into()
+                    .map(Some),
+                ArrayTypeContent::ParameterInfoStruct12(inner) => (inner.name, inner.writable)
+                    .try_into()
+                    .map(Some),
+                _ => Ok(None),
             }
         };
 
@@ -599,21 +786,17 @@ impl_try_from_array_type! {
 
         let values = |content: ArrayTypeContent|  {
 
-            let log_err = |e: &Error| eprintln!("Error converting to ParameterAttributeStruct: {e}");
             match content {
                 ArrayTypeContent::ParameterAttributeStruct10(inner) => (inner.name, inner.notification, Box::new(inner.access_list))
                     .try_into()
-                    .inspect_err(log_err)
-                    .ok(),
+                    .map(Some),
                 ArrayTypeContent::ParameterAttributeStruct11(inner) => (inner.name, inner.notification, inner.access_list)
                     .try_into()
-                    .inspect_err(log_err)
-                    .ok(),
+                    .map(Some),
                 ArrayTypeContent::ParameterAttributeStruct12(inner) => (inner.name, inner.notification, inner.access_list)
                     .try_into()
-                    .inspect_err(log_err)
-                    .ok(),
-                _ => None,
+                    .map(Some),
+                _ => Ok(None),
             }
         };
 
@@ -629,10 +812,10 @@ impl_try_from_array_type! {
 
             let values = |content: ArrayTypeContent| match content {
                 ArrayTypeContent::String(inner) => match inner.as_str() {
-                    "Subscriber" => Some(AccessListMember::Subscriber),
-                    _ => None,
+                    "Subscriber" => Ok(Some(AccessListMember::Subscriber)),
+                    _ => Ok(None),
                 },
-                _ => None,
+                _ => Ok(None),
             };
             values
         }
@@ -645,11 +828,9 @@ impl_try_from_array_type! {
            use cwmp_xsd_schema::soapenc::ArrayTypeContent;
 
            let values = |content: ArrayTypeContent| match content {
-               ArrayTypeContent::String(inner) => inner
-                   .try_into()
-                   .inspect_err(|e|eprintln!("Error converting to MethodList: {e}"))
-                   .ok(),
-               _ => None,
+               ArrayTypeContent::String(inner) => facets::max_length("MethodList", &inner, facets::METHOD_NAME)
+                   .map(|_| Some(inner)),
+               _ => Ok(None),
            };
            values
         }
@@ -664,9 +845,8 @@ impl_try_from_array_type! {
            let values = |content: ArrayTypeContent| match content {
                ArrayTypeContent::String(inner) => inner
                    .try_into()
-                   .inspect_err(|e|eprintln!("Error converting to ParameterNames: {e}"))
-                   .ok(),
-               _ => None,
+                   .map(Some),
+               _ => Ok(None),
            };
            values
         }
@@ -678,55 +858,83 @@ impl_try_from_array_type! {
     {
         use cwmp_xsd_schema::soapenc::ArrayTypeContent;
 
-        let values: _ = |content: ArrayTypeContent| {
-            let log_err = |e: &Error| eprintln!("Error converting to AccessList: {e}");
+        let values = |content: ArrayTypeContent| -> Result<Option<SetParameterAttributesStruct>, Error> {
             match content {
                 ArrayTypeContent::SetParameterAttributesStruct10(inner) => {
                     let access_list: AccessList =
-                        inner.access_list.try_into().inspect_err(log_err).ok()?;
+                        inner.access_list.try_into()?;
                     let notification: SetParameterAttributesNotification =
                         inner.notification.into();
                     let name = inner.name.into_inner().and_then(|v| v.into_inner());
-                    Some(SetParameterAttributesStruct {
+                    let item = SetParameterAttributesStruct {
                         name,
                         access_list,
                         notification,
                         access_list_change: inner.access_list_change,
                         notification_change: inner.notification_change,
-                    })
+                    };
+                    item.check_facets().map(|_| Some(item))
                 }
                 ArrayTypeContent::SetParameterAttributesStruct11(inner) => {
                     let access_list: AccessList =
-                        (*inner.access_list).try_into().inspect_err(log_err).ok()?;
+                        (*inner.access_list).try_into()?;
                     let notification: SetParameterAttributesNotification =
                         inner.notification.into();
                     let name = inner.name.into_inner().and_then(|v| v.into_inner());
-                    Some(SetParameterAttributesStruct {
+                    let item = SetParameterAttributesStruct {
                         name,
                         access_list,
                         notification,
                         access_list_change: inner.access_list_change,
                         notification_change: inner.notification_change,
-                    })
+                    };
+                    item.check_facets().map(|_| Some(item))
                 }
                 ArrayTypeContent::SetParameterAttributesStruct12(inner) => {
                     let access_list: AccessList =
-                        (*inner.access_list).try_into().inspect_err(log_err).ok()?;
+                        (*inner.access_list).try_into()?;
                     let notification: SetParameterAttributesNotification =
                         inner.notification.into();
                     let name = Some(inner.name);
-                    Some(SetParameterAttributesStruct {
+                    let item = SetParameterAttributesStruct {
                         name,
                         access_list,
                         notification,
                         access_list_change: inner.access_list_change,
                         notification_change: inner.notification_change,
-                    })
+                    };
+                    item.check_facets().map(|_| Some(item))
                 }
-                _ => None,
+                _ => Ok(None),
+            }
+        };
+        values
+    }
+}
+
+impl_try_from_array_type! {
+    ParameterList<EventStruct>,
+    {
+        use cwmp_xsd_schema::soapenc::ArrayTypeContent;
+
+        let values = |content: ArrayTypeContent|  {
+
+            match content {
+                ArrayTypeContent::EventStruct10(inner) => (inner.event_code, inner.command_key)
+                    .try_into()
+                    .map(Some),
+                ArrayTypeContent::EventStruct11(inner) => (inner.event_code, inner.command_key)
+                    .try_into()
+                    .map(Some),
+                ArrayTypeContent::EventStruct12(inner) => (inner.event_code, inner.command_key)
+                    .try_into()
+                    .map(Some),
+                _ => Ok(None),
             }
         };
+
         values
+
     }
 }
 
@@ -753,6 +961,7 @@ impl_from_vec!(
     ParameterList<SetParameterAttributesStruct>,
     SetParameterAttributesStruct
 );
+impl_from_vec!(ParameterList<EventStruct>, EventStruct);
 
 macro_rules! impl_parameter_attributes_struct_notification {
     ($($ty: ty),+ ) => {
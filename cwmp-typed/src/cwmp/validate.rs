@@ -0,0 +1,360 @@
+use super::CwmpVersion;
+use super::facets::Facets;
+use super::path::ParameterPath;
+use super::rpc::{Rpc, RpcMethod};
+use super::types::{AttributeNotificationValue, ParameterName, ParameterNames};
+use crate::error::Error;
+
+#[derive(thiserror::Error, Debug)]
+#[error("{field}: {error}")]
+pub struct ValidationError {
+    pub field: String,
+    pub error: Error,
+}
+
+impl ValidationError {
+    pub fn new<F: Into<String>>(field: F, error: Error) -> Self {
+        Self {
+            field: field.into(),
+            error,
+        }
+    }
+}
+
+/// Checks an rpc against the rules of the cwmp version it is tagged with,
+/// collecting every violation instead of stopping at the first one. Without
+/// a negotiated version the features of 1.4 are rejected, refer
+/// [`validate_negotiated`].
+pub fn validate(rpc: &Rpc) -> Result<(), Vec<ValidationError>> {
+    check(rpc, None)
+}
+
+/// Like [`validate`], only accepting what was added in 1.4 (instance
+/// wildcards, alias based addressing and the lightweight notifications) when
+/// the session negotiated 1.4 or later through `UseCWMPVersion`.
+pub fn validate_negotiated(
+    rpc: &Rpc,
+    negotiated_version: (u32, u32),
+) -> Result<(), Vec<ValidationError>> {
+    check(rpc, Some(negotiated_version))
+}
+
+fn check(rpc: &Rpc, negotiated_version: Option<(u32, u32)>) -> Result<(), Vec<ValidationError>> {
+    let version = rpc.version();
+    let method = rpc.method();
+    let mut errors: Vec<ValidationError> = Vec::new();
+
+    // 1.4 still uses the 1.2 namespace, only a negotiated version can tell
+    // 1.2 or 1.3 apart from it. A CPE that lists no SupportedCWMPVersions
+    // predates 1.4.
+    let cwmp_14 = *version >= CwmpVersion::_12
+        && negotiated_version.is_some_and(|negotiated| negotiated >= (1, 4));
+
+    if let Err(error) = method.check_facets() {
+        errors.push(ValidationError::new(method.name(), error));
+    }
+
+    match method {
+        RpcMethod::GetParameterNames(inner) => {
+            // an empty ParameterPath (refer: 3.6.2, A.2.4) was introduced in 1.1
+            if *version == CwmpVersion::_10 && matches!(inner.parameter_path, ParameterName::None) {
+                errors.push(ValidationError::new(
+                    "GetParameterNames.ParameterPath",
+                    Error::RpcVersionMismatch,
+                ));
+            }
+            validate_wildcard(
+                cwmp_14,
+                "GetParameterNames.ParameterPath",
+                &inner.parameter_path,
+                &mut errors,
+            );
+            validate_alias_segments(
+                cwmp_14,
+                "GetParameterNames.ParameterPath",
+                &inner.parameter_path.to_string(),
+                &mut errors,
+            );
+        }
+        RpcMethod::SetParameterValues(inner) => {
+            for (i, item) in inner.parameter_list.0.iter().enumerate() {
+                validate_alias_segments(
+                    cwmp_14,
+                    &format!("SetParameterValues.ParameterList[{i}].Name"),
+                    &item.name,
+                    &mut errors,
+                );
+            }
+        }
+        RpcMethod::GetParameterValues(inner) => {
+            validate_parameter_names(
+                cwmp_14,
+                "GetParameterValues.ParameterNames",
+                &inner.parameter_names,
+                &mut errors,
+            );
+        }
+        RpcMethod::GetParameterAttributes(inner) => {
+            validate_parameter_names(
+                cwmp_14,
+                "GetParameterAttributes.ParameterNames",
+                inner,
+                &mut errors,
+            );
+        }
+        RpcMethod::SetParameterAttributes(inner) => {
+            for (i, item) in inner.parameter_list.0.iter().enumerate() {
+                let field = format!("SetParameterAttributes.ParameterList[{i}]");
+
+                // the lightweight notification values 3-6 were added in 1.4
+                let lightweight = !matches!(
+                    item.notification,
+                    AttributeNotificationValue::_0
+                        | AttributeNotificationValue::_1
+                        | AttributeNotificationValue::_2
+                );
+                if lightweight && !cwmp_14 {
+                    errors.push(ValidationError::new(
+                        format!("{field}.Notification"),
+                        Error::RpcVersionMismatch,
+                    ));
+                }
+
+                // Name stopped being nillable in 1.2
+                match &item.name {
+                    None if *version >= CwmpVersion::_12 => {
+                        errors.push(ValidationError::new(
+                            format!("{field}.Name"),
+                            Error::RpcVersionMismatch,
+                        ));
+                    }
+                    Some(name) => validate_alias_segments(
+                        cwmp_14,
+                        &format!("{field}.Name"),
+                        name,
+                        &mut errors,
+                    ),
+                    None => {}
+                }
+            }
+        }
+        RpcMethod::AddObject(inner) | RpcMethod::DeleteObject(inner) => {
+            if !inner.object_name.ends_with('.') {
+                errors.push(ValidationError::new(
+                    format!("{}.ObjectName", method.name()),
+                    Error::InvalidParameterName(format!(
+                        "Object name '{}' must end with a '.'.",
+                        inner.object_name
+                    )),
+                ));
+            }
+            validate_alias_segments(
+                cwmp_14,
+                &format!("{}.ObjectName", method.name()),
+                &inner.object_name,
+                &mut errors,
+            );
+        }
+        _ => {}
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn validate_parameter_names(
+    cwmp_14: bool,
+    field: &str,
+    names: &ParameterNames,
+    errors: &mut Vec<ValidationError>,
+) {
+    for (i, name) in names.0.iter().enumerate() {
+        let field = format!("{field}[{i}]");
+        validate_wildcard(cwmp_14, &field, name, errors);
+        validate_alias_segments(cwmp_14, &field, &name.to_string(), errors);
+    }
+}
+
+// instance wildcards in parameter names were added in 1.4
+fn validate_wildcard(
+    cwmp_14: bool,
+    field: &str,
+    name: &ParameterName,
+    errors: &mut Vec<ValidationError>,
+) {
+    let wildcard = matches!(
+        name,
+        ParameterName::WildCard(_) | ParameterName::WildCardPath(_)
+    );
+    if wildcard && !cwmp_14 {
+        errors.push(ValidationError::new(field, Error::RpcVersionMismatch));
+    }
+}
+
+// instance alias references (`[alias]` segments) were added in 1.4
+fn validate_alias_segments(
+    cwmp_14: bool,
+    field: &str,
+    name: &str,
+    errors: &mut Vec<ValidationError>,
+) {
+    if !name.contains('[') {
+        return;
+    }
+    match name.parse::<ParameterPath>() {
+        Err(error) => errors.push(ValidationError::new(field, error)),
+        Ok(_) if !cwmp_14 => errors.push(ValidationError::new(field, Error::RpcVersionMismatch)),
+        Ok(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cwmp::rpc::{GetParameterValues, SetParameterAttributes};
+    use crate::cwmp::types::{AccessList, AddObject, ParameterList, SetParameterAttributesStruct};
+
+    fn set_parameter_attributes(
+        name: Option<&str>,
+        notification: AttributeNotificationValue,
+    ) -> RpcMethod {
+        RpcMethod::SetParameterAttributes(SetParameterAttributes {
+            parameter_list: ParameterList(vec![SetParameterAttributesStruct {
+                name: name.map(|v| v.to_string()),
+                notification_change: true,
+                notification,
+                access_list_change: false,
+                access_list: AccessList(vec![]),
+            }]),
+        })
+    }
+
+    #[test]
+    fn get_parameter_names_empty_path() {
+        let method = || RpcMethod::GetParameterNames((None, true).try_into().unwrap());
+        let errors = validate(&Rpc::from((CwmpVersion::_10, method()))).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "GetParameterNames.ParameterPath");
+        assert!(matches!(errors[0].error, Error::RpcVersionMismatch));
+
+        assert!(validate(&Rpc::from((CwmpVersion::_11, method()))).is_ok());
+        assert!(validate(&Rpc::from((CwmpVersion::_12, method()))).is_ok());
+    }
+
+    #[test]
+    fn set_parameter_attributes_notification() {
+        let rpc = Rpc::from((
+            CwmpVersion::_10,
+            set_parameter_attributes(
+                Some("Device.DeviceInfo.UpTime"),
+                AttributeNotificationValue::_4,
+            ),
+        ));
+        let errors = validate(&rpc).unwrap_err();
+        assert_eq!(
+            errors[0].field,
+            "SetParameterAttributes.ParameterList[0].Notification"
+        );
+
+        let rpc = Rpc::from((
+            CwmpVersion::_12,
+            set_parameter_attributes(
+                Some("Device.DeviceInfo.UpTime"),
+                AttributeNotificationValue::_4,
+            ),
+        ));
+        assert!(validate(&rpc).is_err());
+        assert!(validate_negotiated(&rpc, (1, 4)).is_ok());
+    }
+
+    #[test]
+    fn set_parameter_attributes_nil_name() {
+        let rpc = Rpc::from((
+            CwmpVersion::_11,
+            set_parameter_attributes(None, AttributeNotificationValue::_1),
+        ));
+        assert!(validate(&rpc).is_ok());
+
+        let rpc = Rpc::from((
+            CwmpVersion::_12,
+            set_parameter_attributes(None, AttributeNotificationValue::_5),
+        ));
+        let errors = validate_negotiated(&rpc, (1, 4)).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].field,
+            "SetParameterAttributes.ParameterList[0].Name"
+        );
+    }
+
+    #[test]
+    fn get_parameter_values_wildcards() {
+        let method = || {
+            RpcMethod::GetParameterValues(GetParameterValues {
+                parameter_names: vec![
+                    "Device.DeviceInfo.".to_string(),
+                    "Device.WiFi.SSID.*.SSID".to_string(),
+                ]
+                .try_into()
+                .unwrap(),
+            })
+        };
+        let errors = validate(&Rpc::from((CwmpVersion::_11, method()))).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "GetParameterValues.ParameterNames[1]");
+
+        let rpc = Rpc::from((CwmpVersion::_12, method()));
+        assert!(validate(&rpc).is_err());
+        assert!(validate_negotiated(&rpc, (1, 4)).is_ok());
+    }
+
+    #[test]
+    fn alias_addressing() {
+        let method = |object_name: &str| {
+            RpcMethod::AddObject(AddObject {
+                object_name: object_name.to_string(),
+                parameter_key: String::new(),
+            })
+        };
+        let errors = validate(&Rpc::from((
+            CwmpVersion::_11,
+            method("Device.IP.Interface.[wan]."),
+        )))
+        .unwrap_err();
+        assert_eq!(errors[0].field, "AddObject.ObjectName");
+        assert!(matches!(errors[0].error, Error::RpcVersionMismatch));
+
+        let rpc = Rpc::from((CwmpVersion::_12, method("Device.IP.Interface.[wan].")));
+        assert!(validate(&rpc).is_err());
+        assert!(validate_negotiated(&rpc, (1, 4)).is_ok());
+
+        let errors = validate_negotiated(
+            &Rpc::from((CwmpVersion::_12, method("Device.IP.Interface.[1wan]."))),
+            (1, 4),
+        )
+        .unwrap_err();
+        assert!(matches!(errors[0].error, Error::InvalidParameterName(_)));
+    }
+
+    #[test]
+    fn negotiated_version() {
+        let rpc = |name: &str| {
+            Rpc::from((
+                CwmpVersion::_12,
+                RpcMethod::GetParameterValues(GetParameterValues {
+                    parameter_names: vec![name.to_string()].try_into().unwrap(),
+                }),
+            ))
+        };
+        for name in ["Device.WiFi.SSID.*.SSID", "Device.WiFi.SSID.[guest].SSID"] {
+            assert!(validate_negotiated(&rpc(name), (1, 4)).is_ok());
+            let errors = validate_negotiated(&rpc(name), (1, 3)).unwrap_err();
+            assert_eq!(errors[0].field, "GetParameterValues.ParameterNames[0]");
+            assert!(matches!(errors[0].error, Error::RpcVersionMismatch));
+        }
+        assert!(validate_negotiated(&rpc("Device.WiFi.SSID.1.SSID"), (1, 2)).is_ok());
+    }
+}
@@ -0,0 +1,470 @@
+//! Typed parameter values (TR-106 3.2), with the type of values sent without
+//! an `xsi:type` looked up in a data model.
+use super::datatype::NamedType;
+use super::datetime::CwmpDateTime;
+use super::path::ParameterPath;
+use super::rpc::SetParameterValues;
+use super::types::{ParameterValueStruct, ParameterValueType};
+use crate::envelope::NS_XSD;
+use crate::error::Error;
+use std::fmt;
+use std::net::IpAddr;
+
+/// The XML schema types parameter values are sent as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum XsdType {
+    String,
+    Boolean,
+    Int,
+    UnsignedInt,
+    Long,
+    UnsignedLong,
+    DateTime,
+    Base64,
+    HexBinary,
+}
+
+impl XsdType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Boolean => "boolean",
+            Self::Int => "int",
+            Self::UnsignedInt => "unsignedInt",
+            Self::Long => "long",
+            Self::UnsignedLong => "unsignedLong",
+            Self::DateTime => "dateTime",
+            Self::Base64 => "base64",
+            Self::HexBinary => "hexBinary",
+        }
+    }
+
+    /// Reads the local name of an `xsi:type`, `xsd:unsignedInt` or `unsignedInt`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.rsplit(':').next().unwrap_or(name);
+        Some(match name {
+            "string" => Self::String,
+            "boolean" => Self::Boolean,
+            "int" => Self::Int,
+            "unsignedInt" => Self::UnsignedInt,
+            "long" => Self::Long,
+            "unsignedLong" => Self::UnsignedLong,
+            "dateTime" => Self::DateTime,
+            "base64" | "base64Binary" => Self::Base64,
+            "hexBinary" => Self::HexBinary,
+            _ => return None,
+        })
+    }
+}
+
+/// The type of a parameter in a data model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParameterType {
+    pub base: XsdType,
+    /// A comma separated list of `base` values.
+    pub list: bool,
+    /// The TR-106 named data type, `IPAddress` or `MACAddress`.
+    pub data_type: Option<String>,
+}
+
+impl ParameterType {
+    pub fn new(base: XsdType) -> Self {
+        Self {
+            base,
+            list: false,
+            data_type: None,
+        }
+    }
+
+    /// The `xsi:type` values of this type are sent with, lists are strings.
+    pub fn xsi_type(&self) -> ParameterValueType {
+        let base = if self.list {
+            XsdType::String
+        } else {
+            self.base
+        };
+        ParameterValueType::Qualified {
+            namespace: NS_XSD.to_string(),
+            prefix: "xsd".to_string(),
+            type_: base.name().to_string(),
+        }
+    }
+}
+
+/// Parameter types by path, implemented by loaded data models.
+pub trait TypeLookup {
+    fn parameter_type(&self, path: &ParameterPath) -> Option<ParameterType>;
+}
+
+/// A parameter value read as its type. Empty `IPAddress` and `MACAddress`
+/// values, meaning unset, stay strings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedValue {
+    String(String),
+    Boolean(bool),
+    Int(i32),
+    UnsignedInt(u32),
+    Long(i64),
+    UnsignedLong(u64),
+    DateTime(CwmpDateTime),
+    Base64(Vec<u8>),
+    HexBinary(Vec<u8>),
+    IpAddress(IpAddr),
+    MacAddress([u8; 6]),
+    List(Vec<TypedValue>),
+}
+
+fn invalid(value: &str, type_: &str) -> Error {
+    Error::InvalidValue(format!("'{value}' is not a valid {type_}."))
+}
+
+fn number<T: std::str::FromStr>(value: &str, type_: XsdType) -> Result<T, Error> {
+    value.parse().map_err(|_| invalid(value, type_.name()))
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn decode_base64(value: &str) -> Result<Vec<u8>, Error> {
+    let data = value.trim_end_matches('=');
+    if !value.len().is_multiple_of(4) || value.len() - data.len() > 2 {
+        return Err(invalid(value, "base64"));
+    }
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for b in data.bytes() {
+        let digit = BASE64
+            .iter()
+            .position(|&c| c == b)
+            .ok_or_else(|| invalid(value, "base64"))?;
+        buffer = (buffer << 6) | digit as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+fn encode_base64(bytes: &[u8], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for chunk in bytes.chunks(3) {
+        let buffer = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |buffer, (i, &b)| buffer | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let digit = (buffer >> (18 - 6 * i)) & 0x3f;
+                write!(f, "{}", BASE64[digit as usize] as char)?;
+            } else {
+                f.write_str("=")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, Error> {
+    if !value.len().is_multiple_of(2) {
+        return Err(invalid(value, "hexBinary"));
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| {
+            value
+                .get(i..i + 2)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| invalid(value, "hexBinary"))
+        })
+        .collect()
+}
+
+fn parse_mac(value: &str) -> Result<[u8; 6], Error> {
+    let mut mac = [0u8; 6];
+    let mut parts = value.split(':');
+    for byte in mac.iter_mut() {
+        *byte = parts
+            .next()
+            .filter(|part| part.len() == 2)
+            .and_then(|part| u8::from_str_radix(part, 16).ok())
+            .ok_or_else(|| invalid(value, "MACAddress"))?;
+    }
+    if parts.next().is_some() {
+        return Err(invalid(value, "MACAddress"));
+    }
+    Ok(mac)
+}
+
+impl TypedValue {
+    pub fn parse(value: &str, type_: &ParameterType) -> Result<Self, Error> {
+        if type_.list {
+            if value.is_empty() {
+                return Ok(Self::List(Vec::new()));
+            }
+            let item = ParameterType {
+                list: false,
+                ..type_.clone()
+            };
+            return value
+                .split(',')
+                .map(|value| Self::parse(value.trim(), &item))
+                .collect::<Result<_, _>>()
+                .map(Self::List);
+        }
+
+        let named = type_
+            .data_type
+            .as_deref()
+            .and_then(|name| name.parse().ok());
+        if let Some(named) = named {
+            NamedType::validate(&named, value)?;
+        }
+        match named {
+            Some(NamedType::IpAddress | NamedType::Ipv4Address | NamedType::Ipv6Address)
+                if !value.is_empty() =>
+            {
+                return value
+                    .parse()
+                    .map(Self::IpAddress)
+                    .map_err(|_| invalid(value, "IPAddress"));
+            }
+            Some(NamedType::MacAddress) if !value.is_empty() => {
+                return parse_mac(value).map(Self::MacAddress);
+            }
+            _ => {}
+        }
+
+        let base = type_.base;
+        Ok(match base {
+            XsdType::String => Self::String(value.to_string()),
+            XsdType::Boolean => match value {
+                "1" | "true" => Self::Boolean(true),
+                "0" | "false" => Self::Boolean(false),
+                _ => return Err(invalid(value, base.name())),
+            },
+            XsdType::Int => Self::Int(number(value, base)?),
+            XsdType::UnsignedInt => Self::UnsignedInt(number(value, base)?),
+            XsdType::Long => Self::Long(number(value, base)?),
+            XsdType::UnsignedLong => Self::UnsignedLong(number(value, base)?),
+            XsdType::DateTime => Self::DateTime(value.parse()?),
+            XsdType::Base64 => Self::Base64(decode_base64(value)?),
+            XsdType::HexBinary => Self::HexBinary(decode_hex(value)?),
+        })
+    }
+}
+
+/// The wire form of the value.
+impl fmt::Display for TypedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(value) => f.write_str(value),
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::UnsignedInt(value) => write!(f, "{value}"),
+            Self::Long(value) => write!(f, "{value}"),
+            Self::UnsignedLong(value) => write!(f, "{value}"),
+            Self::DateTime(value) => write!(f, "{value}"),
+            Self::Base64(bytes) => encode_base64(bytes, f),
+            Self::HexBinary(bytes) => bytes.iter().try_for_each(|b| write!(f, "{b:02x}")),
+            Self::IpAddress(value) => write!(f, "{value}"),
+            Self::MacAddress(mac) => {
+                let [a, b, c, d, e, g] = mac;
+                write!(f, "{a:02X}:{b:02X}:{c:02X}:{d:02X}:{e:02X}:{g:02X}")
+            }
+            Self::List(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ParameterValueStruct {
+    fn model_type<T: TypeLookup + ?Sized>(&self, types: &T) -> Option<ParameterType> {
+        let path: ParameterPath = self.name.parse().ok()?;
+        types.parameter_type(&path)
+    }
+
+    /// The value read as its `xsi:type`, or as its data model type when the
+    /// `xsi:type` is missing or agrees with it. Values of unknown parameters
+    /// without one are strings.
+    pub fn typed_value<T: TypeLookup + ?Sized>(&self, types: &T) -> Result<TypedValue, Error> {
+        let model = self.model_type(types);
+        let sent = self.value.type_.as_ref().map(|type_| match type_ {
+            ParameterValueType::Qualified { type_, .. }
+            | ParameterValueType::Unqualified(type_) => {
+                XsdType::from_name(type_).unwrap_or(XsdType::String)
+            }
+        });
+        let type_ = match (sent, model) {
+            (None, Some(model)) => model,
+            (Some(sent), Some(model))
+                if sent == model.base || (model.list && sent == XsdType::String) =>
+            {
+                model
+            }
+            (Some(sent), _) => ParameterType::new(sent),
+            (None, None) => ParameterType::new(XsdType::String),
+        };
+        TypedValue::parse(&self.value.value, &type_)
+    }
+
+    /// Sets a missing `xsi:type` to the data model type, returns whether
+    /// the value has one now.
+    pub fn infer_type<T: TypeLookup + ?Sized>(&mut self, types: &T) -> bool {
+        if self.value.type_.is_none() {
+            self.value.type_ = self.model_type(types).map(|type_| type_.xsi_type());
+        }
+        self.value.type_.is_some()
+    }
+}
+
+impl SetParameterValues {
+    /// Sets the missing `xsi:type`s to the data model types, so callers
+    /// only need to name the values.
+    pub fn infer_types<T: TypeLookup + ?Sized>(&mut self, types: &T) {
+        for parameter in self.parameter_list.0.iter_mut() {
+            parameter.infer_type(types);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cwmp::types::ParameterValueStructValue;
+    use std::collections::HashMap;
+
+    struct Types(HashMap<&'static str, ParameterType>);
+
+    impl TypeLookup for Types {
+        fn parameter_type(&self, path: &ParameterPath) -> Option<ParameterType> {
+            self.0.get(path.to_string().as_str()).cloned()
+        }
+    }
+
+    fn types() -> Types {
+        let named = |base, data_type: &str| ParameterType {
+            base,
+            list: false,
+            data_type: Some(data_type.to_string()),
+        };
+        Types(HashMap::from([
+            (
+                "Device.WiFi.SSID.1.Enable",
+                ParameterType::new(XsdType::Boolean),
+            ),
+            (
+                "Device.WiFi.SSID.1.MACAddress",
+                named(XsdType::String, "MACAddress"),
+            ),
+            (
+                "Device.IP.Interface.1.IPv4Address.1.IPAddress",
+                named(XsdType::String, "IPv4Address"),
+            ),
+            (
+                "Device.DHCPv4.Server.Pool.1.ReservedAddresses",
+                ParameterType {
+                    list: true,
+                    ..named(XsdType::String, "IPv4Address")
+                },
+            ),
+            (
+                "Device.DeviceInfo.UpTime",
+                ParameterType::new(XsdType::UnsignedInt),
+            ),
+        ]))
+    }
+
+    fn value(name: &str, type_: Option<&str>, value: &str) -> ParameterValueStruct {
+        ParameterValueStruct {
+            name: name.to_string(),
+            value: ParameterValueStructValue {
+                type_: type_.map(|type_| ParameterValueType::Unqualified(type_.to_string())),
+                value: value.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn inferred_types() {
+        let types = types();
+        let typed = |name, type_, v| value(name, type_, v).typed_value(&types).unwrap();
+
+        assert_eq!(
+            typed("Device.WiFi.SSID.1.Enable", None, "1"),
+            TypedValue::Boolean(true)
+        );
+        assert_eq!(
+            typed("Device.DeviceInfo.UpTime", None, "1"),
+            TypedValue::UnsignedInt(1)
+        );
+        assert_eq!(
+            typed(
+                "Device.WiFi.SSID.1.MACAddress",
+                Some("xsd:string"),
+                "00:d0:9e:01:02:03"
+            ),
+            TypedValue::MacAddress([0x00, 0xd0, 0x9e, 0x01, 0x02, 0x03])
+        );
+        assert_eq!(
+            typed(
+                "Device.DHCPv4.Server.Pool.1.ReservedAddresses",
+                None,
+                "192.168.1.2, 192.168.1.3"
+            )
+            .to_string(),
+            "192.168.1.2,192.168.1.3"
+        );
+        // the CPE's xsi:type wins over the data model
+        assert_eq!(
+            typed("Device.DeviceInfo.UpTime", Some("xsd:string"), "1"),
+            TypedValue::String("1".to_string())
+        );
+        assert_eq!(
+            typed("Device.Unknown", None, "1"),
+            TypedValue::String("1".to_string())
+        );
+        assert!(
+            value(
+                "Device.IP.Interface.1.IPv4Address.1.IPAddress",
+                None,
+                "300.1.1.1"
+            )
+            .typed_value(&types)
+            .is_err()
+        );
+        assert!(
+            value("Device.IP.Interface.1.IPv4Address.1.IPAddress", None, "::1")
+                .typed_value(&types)
+                .is_err()
+        );
+
+        let mut outgoing = value("Device.WiFi.SSID.1.Enable", None, "true");
+        assert!(outgoing.infer_type(&types));
+        assert_eq!(outgoing.value.type_.unwrap().to_string(), "xsd:boolean");
+        assert!(!value("Device.Unknown", None, "").infer_type(&types));
+    }
+
+    #[test]
+    fn binary_values() {
+        for (text, bytes) in [
+            ("", &b""[..]),
+            ("Zg==", b"f"),
+            ("Zm8=", b"fo"),
+            ("Zm9v", b"foo"),
+        ] {
+            let value = TypedValue::parse(text, &ParameterType::new(XsdType::Base64)).unwrap();
+            assert_eq!(value, TypedValue::Base64(bytes.to_vec()));
+            assert_eq!(value.to_string(), text);
+        }
+        assert!(TypedValue::parse("Zg=", &ParameterType::new(XsdType::Base64)).is_err());
+        let hex = TypedValue::parse("00ff", &ParameterType::new(XsdType::HexBinary)).unwrap();
+        assert_eq!(hex.to_string(), "00ff");
+    }
+}
@@ -0,0 +1,154 @@
+//! CWMP 1.0 allows several SOAP envelopes in one HTTP body, up to the
+//! `MaxEnvelopes` the receiver announced (the CPE in `Inform`, the ACS in
+//! `InformResponse`). Later versions require `MaxEnvelopes` to be 1.
+use super::Envelope;
+use super::limits::ParseLimits;
+use super::profile::SerializerProfile;
+use super::raw::{RawDocument, RawNode};
+use super::serialize::EnvelopeWriter;
+use crate::cwmp::CwmpVersion;
+use crate::error::Error;
+use quick_xml::events::Event;
+use std::collections::VecDeque;
+
+impl Envelope {
+    /// Parses every envelope of an HTTP body. An empty body, which ends a
+    /// session, gives an empty `Vec`.
+    pub fn parse_all(xml: &[u8]) -> Result<Vec<Self>, Error> {
+        Self::parse_all_with_limits(xml, &ParseLimits::default())
+    }
+
+    /// Like [`Envelope::parse_all`], the limits apply to the body as a whole.
+    pub fn parse_all_with_limits(xml: &[u8], limits: &ParseLimits) -> Result<Vec<Self>, Error> {
+        limits.check(xml)?;
+        let document = RawDocument::parse(xml)?;
+
+        let mut envelopes = Vec::new();
+        for node in document.nodes {
+            match node {
+                RawNode::Element(element) => {
+                    if element.local_name() != b"Envelope" {
+                        return Err(Error::InvalidXml(format!(
+                            "Expected Envelope, found {}.",
+                            String::from_utf8_lossy(element.name())
+                        )));
+                    }
+                    let single = RawDocument {
+                        nodes: vec![RawNode::Element(element)],
+                    };
+                    envelopes.push(Self::deserialize(&single.write()?)?);
+                }
+                RawNode::Other(Event::Text(text)) => {
+                    if !text.iter().all(u8::is_ascii_whitespace) {
+                        return Err(Error::InvalidXml(
+                            "Unexpected text between envelopes.".to_string(),
+                        ));
+                    }
+                }
+                RawNode::Other(_) => {}
+            }
+        }
+        Ok(envelopes)
+    }
+}
+
+/// Queue of outgoing envelopes, written into HTTP bodies of at most
+/// `max_envelopes` envelopes each.
+#[derive(Debug)]
+pub struct EnvelopeBatch {
+    max_envelopes: usize,
+    queue: VecDeque<Envelope>,
+}
+
+impl EnvelopeBatch {
+    /// `max_envelopes` is the value the peer announced, it is only honoured
+    /// for CWMP 1.0 sessions. 0 is not a valid announcement and is read as 1.
+    pub fn new(version: &CwmpVersion, max_envelopes: u32) -> Self {
+        let max_envelopes = match version {
+            CwmpVersion::_10 => max_envelopes.max(1) as usize,
+            _ => 1,
+        };
+        Self {
+            max_envelopes,
+            queue: VecDeque::new(),
+        }
+    }
+
+    pub fn max_envelopes(&self) -> usize {
+        self.max_envelopes
+    }
+
+    pub fn push(&mut self, envelope: Envelope) {
+        self.queue.push_back(envelope);
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Writes the next HTTP body, `None` once the queue is drained. The
+    /// envelopes stay queued if writing fails.
+    pub fn next_body(&mut self, profile: &SerializerProfile) -> Result<Option<Vec<u8>>, Error> {
+        if self.queue.is_empty() {
+            return Ok(None);
+        }
+
+        let count = self.max_envelopes.min(self.queue.len());
+        let mut writer = EnvelopeWriter::new(profile)?;
+        for envelope in self.queue.iter().take(count) {
+            writer.envelope(envelope)?;
+        }
+        self.queue.drain(..count);
+        Ok(Some(writer.into_inner()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const REBOOT: &str = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><soap:Header><cwmp:ID soap:mustUnderstand="1">ID</cwmp:ID></soap:Header><soap:Body><cwmp:Reboot><CommandKey>reboot</CommandKey></cwmp:Reboot></soap:Body></soap:Envelope>"#;
+
+    fn reboot(id: &str) -> Envelope {
+        Envelope::parse(REBOOT.replace(">ID<", &format!(">{id}<")).as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn parse_all_envelopes() {
+        let body = format!(
+            "<?xml version=\"1.0\"?>\n{}\n{}\n",
+            REBOOT.replace(">ID<", ">1<"),
+            REBOOT.replace(">ID<", ">2<")
+        );
+        let envelopes = Envelope::parse_all(body.as_bytes()).unwrap();
+        assert_eq!(envelopes.len(), 2);
+
+        assert!(Envelope::parse_all(b"").unwrap().is_empty());
+        assert!(Envelope::parse_all(format!("{REBOOT}junk").as_bytes()).is_err());
+    }
+
+    #[test]
+    fn batch_honours_max_envelopes() {
+        let mut batch = EnvelopeBatch::new(&CwmpVersion::_10, 2);
+        for id in ["1", "2", "3"] {
+            batch.push(reboot(id));
+        }
+
+        let profile = SerializerProfile::default();
+        let first = batch.next_body(&profile).unwrap().unwrap();
+        assert_eq!(Envelope::parse_all(&first).unwrap().len(), 2);
+        let second = batch.next_body(&profile).unwrap().unwrap();
+        assert_eq!(Envelope::parse_all(&second).unwrap().len(), 1);
+        assert!(batch.next_body(&profile).unwrap().is_none());
+    }
+
+    #[test]
+    fn batch_is_single_envelope_after_cwmp_10() {
+        assert_eq!(EnvelopeBatch::new(&CwmpVersion::_10, 0).max_envelopes(), 1);
+        assert_eq!(EnvelopeBatch::new(&CwmpVersion::_12, 5).max_envelopes(), 1);
+    }
+}
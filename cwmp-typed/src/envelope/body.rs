@@ -3,5 +3,5 @@ use crate::cwmp;
 #[derive(Debug)]
 pub enum EnvelopeBody {
     Rpc(cwmp::rpc::Rpc),
-    Fault(super::fault::Fault),
+    Fault(super::fault::SoapFault),
 }
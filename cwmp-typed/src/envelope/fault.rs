@@ -1,2 +1,121 @@
-#[derive(Debug)]
-pub struct Fault;
+//! SOAP faults carrying a cwmp Fault detail, refer TR-069 A.2.
+use crate::cwmp::facets::Facets;
+use crate::cwmp::fault::FaultCode;
+use crate::error::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fault {
+    pub fault_code: u32,
+    pub fault_string: String,
+    pub set_parameter_values_faults: Vec<SetParameterValuesFault>,
+}
+
+/// A soap Fault, with the cwmp Fault of its `detail` when it has one. A
+/// detail-less fault is e.g. sent by a soap stack rejecting the envelope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SoapFault {
+    /// `Client` or `Server`, as sent.
+    pub fault_code: String,
+    pub fault_string: String,
+    pub detail: Option<Fault>,
+}
+
+/// A rejected parameter of a SetParameterValues.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetParameterValuesFault {
+    pub parameter_name: String,
+    pub fault_code: u32,
+    pub fault_string: String,
+}
+
+impl Fault {
+    /// A fault with the FaultString the specification suggests.
+    pub fn new(code: FaultCode) -> Self {
+        Self {
+            fault_code: code.code(),
+            fault_string: code.message().to_string(),
+            set_parameter_values_faults: Vec::new(),
+        }
+    }
+
+    /// The soap `faultcode`, `Client` when the request was at fault. Vendor
+    /// specific codes are `Server` faults.
+    pub fn soap_fault_code(&self) -> &'static str {
+        match FaultCode::try_from(self.fault_code) {
+            Ok(code) if code.is_client() => "Client",
+            _ => "Server",
+        }
+    }
+}
+
+impl From<FaultCode> for Fault {
+    fn from(code: FaultCode) -> Self {
+        Self::new(code)
+    }
+}
+
+impl From<Fault> for SoapFault {
+    fn from(fault: Fault) -> Self {
+        Self {
+            fault_code: fault.soap_fault_code().to_string(),
+            fault_string: "CWMP fault".to_string(),
+            detail: Some(fault),
+        }
+    }
+}
+
+impl TryFrom<cwmp_xsd_schema::soapenv::FaultType> for SoapFault {
+    type Error = Error;
+
+    fn try_from(input: cwmp_xsd_schema::soapenv::FaultType) -> Result<Self, Self::Error> {
+        use cwmp_xsd_schema::soapenv::DetailTypeContent;
+
+        let detail = match input.detail.map(|detail| detail.content) {
+            None => None,
+            Some(DetailTypeContent::Fault10(fault)) => Some(fault.try_into()?),
+            Some(DetailTypeContent::Fault11(fault)) => Some(fault.try_into()?),
+            Some(DetailTypeContent::Fault12(fault)) => Some(fault.try_into()?),
+        };
+        Ok(Self {
+            fault_code: input.faultcode.to_string(),
+            fault_string: input.faultstring,
+            detail,
+        })
+    }
+}
+
+macro_rules! impl_try_from_fault {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<$t> for Fault {
+                type Error = Error;
+                fn try_from(v: $t) -> Result<Self, Self::Error> {
+                    let fault = Self {
+                        fault_code: v.fault_code.0,
+                        fault_string: v.fault_string.unwrap_or_default(),
+                        set_parameter_values_faults: v
+                            .set_parameter_values_fault
+                            .into_iter()
+                            .map(|item| SetParameterValuesFault {
+                                parameter_name: item.parameter_name.trim().to_string(),
+                                fault_code: item.fault_code.0,
+                                fault_string: item.fault_string.unwrap_or_default(),
+                            })
+                            .collect(),
+                    };
+                    fault.check_facets()?;
+                    Ok(fault)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_fault!(
+    cwmp_xsd_schema::FaultElementType,
+    cwmp_xsd_schema::tns::FaultElementType,
+    cwmp_xsd_schema::cwmp_12::FaultElementType
+);
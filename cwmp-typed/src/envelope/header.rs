@@ -1,4 +1,5 @@
 use super::Element;
+use super::profile::SerializerProfile;
 use crate::cwmp;
 use crate::cwmp::CwmpVersion;
 use crate::error::Error;
@@ -9,8 +10,6 @@ use xsd_parser_types::xml::{AnyElement, Value};
 
 use std::ops::{Deref, DerefMut};
 
-const SOAP_MUST_UNDERSTAND: &'static str = "soap:mustUnderstand";
-
 impl Deref for Element {
     type Target = xsd_parser_types::xml::AnyElement;
     fn deref(&self) -> &Self::Target {
@@ -35,127 +34,215 @@ pub enum EnvelopeHeader {
     UseCwmpVersion(cwmp::header::UseCwmpVersion),
 }
 
-#[inline(always)]
-fn soap_bool(b: bool) -> &'static str {
-    if b { "1" } else { "0" }
+impl EnvelopeHeader {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Id(_) => "ID",
+            Self::HoldRequests(_) => "HoldRequests",
+            Self::SessionTimeout(_) => "SessionTimeout",
+            Self::SupportedCwmpVersions(_) => "SupportedCWMPVersions",
+            Self::UseCwmpVersion(_) => "UseCWMPVersion",
+        }
+    }
+
+    /// The first cwmp version whose schema defines this header.
+    pub fn introduced_in(&self) -> CwmpVersion {
+        match self {
+            Self::Id(_) | Self::HoldRequests(_) => CwmpVersion::_10,
+            Self::SessionTimeout(_) | Self::SupportedCwmpVersions(_) | Self::UseCwmpVersion(_) => {
+                CwmpVersion::_12
+            }
+        }
+    }
 }
 
-impl TryFrom<(&CwmpVersion, cwmp::header::Id)> for Element {
-    type Error = Error;
+impl EnvelopeHeaders {
+    /// Adds `header`, replacing one of the same name.
+    pub(crate) fn replace(&mut self, header: EnvelopeHeader) {
+        self.0.retain(|existing| existing.name() != header.name());
+        self.0.push(header);
+    }
 
-    fn try_from((_, input): (&CwmpVersion, cwmp::header::Id)) -> Result<Element, Self::Error> {
-        let element = AnyElement::new()
-            .name(Cow::Borrowed("cwmp:ID".as_bytes()))
-            .attribute(
-                Cow::Borrowed(SOAP_MUST_UNDERSTAND.as_bytes()),
-                Cow::Borrowed(soap_bool(input.must_understand).as_ref()),
-            )
-            .child(Value::Text(BytesText::new(&input.content).into_owned()));
-        Ok(Element(element))
+    pub(crate) fn remove(&mut self, name: &str) {
+        self.0.retain(|existing| existing.name() != name);
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, EnvelopeHeader> {
+        self.0.iter()
+    }
+}
+
+impl From<Vec<EnvelopeHeader>> for EnvelopeHeaders {
+    fn from(input: Vec<EnvelopeHeader>) -> Self {
+        Self(input)
     }
 }
 
-impl TryFrom<(&CwmpVersion, cwmp::header::HoldRequests)> for Element {
-    type Error = Error;
+impl From<cwmp_xsd_schema::soapenv::HeaderTypeContent> for EnvelopeHeader {
+    fn from(input: cwmp_xsd_schema::soapenv::HeaderTypeContent) -> Self {
+        use cwmp_xsd_schema::soapenv::HeaderTypeContent as Header;
 
-    fn try_from(
-        (_, input): (&CwmpVersion, cwmp::header::HoldRequests),
-    ) -> Result<Element, Self::Error> {
-        let element = AnyElement::new()
-            .name(Cow::Borrowed("cwmp:ID".as_bytes()))
-            .attribute(
-                Cow::Borrowed(SOAP_MUST_UNDERSTAND.as_bytes()),
-                Cow::Borrowed(soap_bool(input.must_understand).as_ref()),
-            )
-            .child(Value::Text(
-                BytesText::new(soap_bool(input.content)).into_owned(),
-            ));
-        Ok(Element(element))
+        match input {
+            Header::Id10(inner) | Header::Id11(inner) | Header::Id12(inner) => Self::Id(inner),
+            Header::HoldRequests10(inner)
+            | Header::HoldRequests11(inner)
+            | Header::HoldRequests12(inner) => Self::HoldRequests(inner),
+            Header::SessionTimeout12(inner) => Self::SessionTimeout(inner),
+            Header::SupportedCwmpVersions12(inner) => Self::SupportedCwmpVersions(inner),
+            Header::UseCwmpVersion12(inner) => Self::UseCwmpVersion(inner),
+        }
     }
 }
 
-impl TryFrom<(&CwmpVersion, cwmp::header::SessionTimeout)> for Element {
-    type Error = Error;
+/// The namespace version a received header was declared in.
+pub(crate) fn header_version(input: &cwmp_xsd_schema::soapenv::HeaderTypeContent) -> CwmpVersion {
+    use cwmp_xsd_schema::soapenv::HeaderTypeContent as Header;
 
-    fn try_from(
-        (version, input): (&CwmpVersion, cwmp::header::SessionTimeout),
-    ) -> Result<Element, Self::Error> {
-        match version {
-            CwmpVersion::_12 => {
-                let mut element =
-                    AnyElement::new().name(Cow::Borrowed("cwmp:SessionTimeout".as_bytes()));
-                if let Some(must_understand) = input.must_understand {
-                    element = element.attribute(
-                        Cow::Borrowed(SOAP_MUST_UNDERSTAND.as_bytes()),
-                        Cow::Borrowed(soap_bool(must_understand).as_ref()),
-                    );
-                }
-                let element = element.child(Value::Text(
-                    BytesText::new(&input.content.to_string()).into_owned(),
-                ));
-                Ok(Element(element))
+    match input {
+        Header::Id10(_) | Header::HoldRequests10(_) => CwmpVersion::_10,
+        Header::Id11(_) | Header::HoldRequests11(_) => CwmpVersion::_11,
+        _ => CwmpVersion::_12,
+    }
+}
+
+#[inline(always)]
+fn soap_bool(b: bool) -> &'static str {
+    if b { "1" } else { "0" }
+}
+
+impl EnvelopeHeader {
+    /// The optional `mustUnderstand` attribute and the text content of the header.
+    pub(crate) fn content(&self, profile: &SerializerProfile) -> (Option<bool>, String) {
+        match self {
+            Self::Id(inner) => (Some(inner.must_understand), inner.content.clone()),
+            Self::HoldRequests(inner) => (
+                Some(inner.must_understand),
+                profile.boolean(inner.content).to_string(),
+            ),
+            Self::SessionTimeout(inner) => (inner.must_understand, inner.content.to_string()),
+            Self::SupportedCwmpVersions(inner) => {
+                (inner.must_understand, inner.content.to_string())
             }
-            _ => Err(Error::UnsupportedHeaderVersion(
-                "SessionTimeout".to_string(),
-            )),
+            Self::UseCwmpVersion(inner) => (Some(inner.must_understand), inner.content.to_string()),
+        }
+    }
+
+    pub(crate) fn to_element(
+        &self,
+        version: &CwmpVersion,
+        profile: &SerializerProfile,
+    ) -> Result<Element, Error> {
+        if self.introduced_in() > *version {
+            return Err(Error::UnsupportedHeaderVersion(self.name().to_string()));
+        }
+
+        let (must_understand, content) = self.content(profile);
+        let mut element =
+            AnyElement::new().name(Cow::Owned(profile.cwmp(self.name()).into_bytes()));
+        if let Some(must_understand) = must_understand {
+            element = element.attribute(
+                Cow::Owned(profile.soapenv("mustUnderstand").into_bytes()),
+                Cow::Borrowed(soap_bool(must_understand).as_bytes()),
+            );
         }
+        let element = element.child(Value::Text(BytesText::new(&content).into_owned()));
+        Ok(Element(element))
     }
 }
 
-impl TryFrom<(&CwmpVersion, cwmp::header::SupportedCwmpVersions)> for Element {
-    type Error = Error;
+macro_rules! impl_try_from_header {
+    ($($header: ident),+) => {
+        $(
+            impl TryFrom<(&CwmpVersion, cwmp::header::$header)> for Element {
+                type Error = Error;
 
-    fn try_from(
-        (version, input): (&CwmpVersion, cwmp::header::SupportedCwmpVersions),
-    ) -> Result<Element, Self::Error> {
-        match version {
-            CwmpVersion::_12 => {
-                let mut element =
-                    AnyElement::new().name(Cow::Borrowed("cwmp:SupportedCwmpVersions".as_bytes()));
-                if let Some(must_understand) = input.must_understand {
-                    element = element.attribute(
-                        Cow::Borrowed(SOAP_MUST_UNDERSTAND.as_bytes()),
-                        Cow::Borrowed(soap_bool(must_understand).as_ref()),
-                    );
+                fn try_from(
+                    (version, input): (&CwmpVersion, cwmp::header::$header),
+                ) -> Result<Element, Self::Error> {
+                    EnvelopeHeader::$header(input).to_element(version, &SerializerProfile::default())
                 }
-                let element = element.child(Value::Text(
-                    BytesText::new(&input.content.to_string()).into_owned(),
-                ));
-                Ok(Element(element))
             }
-            _ => Err(Error::UnsupportedHeaderVersion(
-                "SupportedCwmpVersions".to_string(),
-            )),
-        }
-    }
+        )+
+    };
 }
 
-impl TryFrom<(&CwmpVersion, cwmp::header::UseCwmpVersion)> for Element {
-    type Error = Error;
+impl_try_from_header!(
+    Id,
+    HoldRequests,
+    SessionTimeout,
+    SupportedCwmpVersions,
+    UseCwmpVersion
+);
+
+/// Headers as `{"header": "<element name>", "must_understand": bool, "content": "<text>"}`,
+/// `must_understand` left out where the header has no such attribute.
+#[cfg(feature = "serde")]
+mod repr {
+    use super::EnvelopeHeader;
+    use crate::cwmp::header::{
+        HoldRequests, Id, SessionTimeout, SupportedCwmpVersions, UseCwmpVersion,
+    };
+    use crate::envelope::profile::SerializerProfile;
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-    fn try_from(
-        (version, input): (&CwmpVersion, cwmp::header::UseCwmpVersion),
-    ) -> Result<Element, Self::Error> {
-        match version {
-            CwmpVersion::_12 => {
-                let element = AnyElement::new()
-                    .name(Cow::Borrowed("cwmp:UseCwmpVersion".as_bytes()))
-                    .attribute(
-                        Cow::Borrowed(SOAP_MUST_UNDERSTAND.as_bytes()),
-                        Cow::Borrowed(soap_bool(input.must_understand).as_ref()),
-                    )
-                    .child(Value::Text(
-                        BytesText::new(&input.content.to_string()).into_owned(),
-                    ));
-                Ok(Element(element))
+    #[derive(Serialize, Deserialize)]
+    struct HeaderRepr {
+        header: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        must_understand: Option<bool>,
+        content: String,
+    }
+
+    impl Serialize for EnvelopeHeader {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let (must_understand, content) = self.content(&SerializerProfile::default());
+            HeaderRepr {
+                header: self.name().to_string(),
+                must_understand,
+                content,
             }
+            .serialize(serializer)
+        }
+    }
 
-            _ => Err(Error::UnsupportedHeaderVersion(
-                "UseCwmpVersion".to_string(),
-            )),
+    impl<'de> Deserialize<'de> for EnvelopeHeader {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let repr = HeaderRepr::deserialize(deserializer)?;
+            let invalid =
+                || D::Error::custom(format!("invalid {} '{}'", repr.header, repr.content));
+            let content = repr.content.trim();
+            Ok(match repr.header.as_str() {
+                "ID" => Self::Id(Id {
+                    must_understand: repr.must_understand.unwrap_or(true),
+                    content: repr.content.clone(),
+                }),
+                "HoldRequests" => Self::HoldRequests(HoldRequests {
+                    must_understand: repr.must_understand.unwrap_or(true),
+                    content: match content {
+                        "1" | "true" => true,
+                        "0" | "false" => false,
+                        _ => return Err(invalid()),
+                    },
+                }),
+                "SessionTimeout" => Self::SessionTimeout(SessionTimeout {
+                    must_understand: repr.must_understand,
+                    content: content.parse().map_err(|_| invalid())?,
+                }),
+                "SupportedCWMPVersions" => Self::SupportedCwmpVersions(SupportedCwmpVersions {
+                    must_understand: repr.must_understand,
+                    content: content.to_string(),
+                }),
+                "UseCWMPVersion" => Self::UseCwmpVersion(UseCwmpVersion {
+                    must_understand: repr.must_understand.unwrap_or(true),
+                    content: content.to_string(),
+                }),
+                other => return Err(D::Error::custom(format!("unknown header '{other}'"))),
+            })
         }
     }
 }
+
 #[cfg(test)]
 mod test {
     use crate::cwmp::CwmpVersion;
@@ -0,0 +1,212 @@
+//! Bounds for documents received from CPEs. The generated deserializer
+//! builds the whole envelope in memory, so the document is checked with a
+//! streaming pass before any of it is materialized.
+use crate::error::Error;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Size of the whole HTTP body in bytes.
+    pub max_document_size: usize,
+    /// Element nesting depth, the `Envelope` being 1.
+    pub max_depth: usize,
+    /// Attributes on a single element, namespace declarations included.
+    pub max_attributes: usize,
+    /// Items of a soap encoded array, both the declared `[N]` and the
+    /// elements actually sent.
+    pub max_array_length: usize,
+    /// Bytes of text in a single element or attribute value, before unescaping.
+    pub max_string_length: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_document_size: 16 * 1024 * 1024,
+            max_depth: 32,
+            max_attributes: 32,
+            max_array_length: 65_536,
+            max_string_length: 65_536,
+        }
+    }
+}
+
+struct Frame {
+    // declared array length, for elements carrying soapenc:arrayType
+    array: Option<usize>,
+    children: usize,
+}
+
+impl ParseLimits {
+    /// Limits that accept anything, for trusted input.
+    pub fn unlimited() -> Self {
+        Self {
+            max_document_size: usize::MAX,
+            max_depth: usize::MAX,
+            max_attributes: usize::MAX,
+            max_array_length: usize::MAX,
+            max_string_length: usize::MAX,
+        }
+    }
+
+    pub fn check(&self, xml: &[u8]) -> Result<(), Error> {
+        exceeds("document size", xml.len(), self.max_document_size)?;
+
+        let mut reader = Reader::from_reader(xml);
+        let mut stack: Vec<Frame> = Vec::new();
+        let mut text_length = 0usize;
+        let mut buf = Vec::new();
+
+        loop {
+            let event = reader
+                .read_event_into(&mut buf)
+                .map_err(|e| Error::InvalidXml(e.to_string()))?;
+
+            match event {
+                Event::Eof => break,
+                Event::Start(start) => {
+                    text_length = 0;
+                    let frame = self.element(&start, &mut stack)?;
+                    stack.push(frame);
+                    exceeds("nesting depth", stack.len(), self.max_depth)?;
+                }
+                Event::Empty(start) => {
+                    text_length = 0;
+                    self.element(&start, &mut stack)?;
+                    exceeds("nesting depth", stack.len() + 1, self.max_depth)?;
+                }
+                Event::End(_) => {
+                    text_length = 0;
+                    stack.pop();
+                }
+                Event::Text(text) => {
+                    text_length = text_length.saturating_add(text.len());
+                    exceeds("string length", text_length, self.max_string_length)?;
+                }
+                Event::CData(text) => {
+                    text_length = text_length.saturating_add(text.len());
+                    exceeds("string length", text_length, self.max_string_length)?;
+                }
+                Event::GeneralRef(reference) => {
+                    // `&name;`
+                    text_length = text_length.saturating_add(reference.len() + 2);
+                    exceeds("string length", text_length, self.max_string_length)?;
+                }
+                _ => {}
+            }
+            buf.clear();
+        }
+        Ok(())
+    }
+
+    fn element(&self, start: &BytesStart<'_>, stack: &mut [Frame]) -> Result<Frame, Error> {
+        if let Some(parent) = stack.last_mut()
+            && parent.array.is_some()
+        {
+            parent.children += 1;
+            exceeds("array length", parent.children, self.max_array_length)?;
+        }
+
+        let mut attributes = 0usize;
+        let mut array = None;
+        for attr in start.attributes() {
+            let attr = attr.map_err(|e| Error::InvalidXml(e.to_string()))?;
+            attributes += 1;
+            exceeds("attribute count", attributes, self.max_attributes)?;
+            exceeds("string length", attr.value.len(), self.max_string_length)?;
+
+            if attr.key.local_name().as_ref() == b"arrayType" {
+                let length = declared_length(&attr.value).unwrap_or(0);
+                exceeds("array length", length, self.max_array_length)?;
+                array = Some(length);
+            }
+        }
+        Ok(Frame { array, children: 0 })
+    }
+}
+
+// the `N` of `cwmp:ParameterValueStruct[N]`, saturating on overflow
+fn declared_length(value: &[u8]) -> Option<usize> {
+    let start = value.iter().rposition(|b| *b == b'[')? + 1;
+    let end = start + value[start..].iter().position(|b| *b == b']')?;
+    let digits = &value[start..end];
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some(digits.iter().fold(0usize, |length, digit| {
+        length
+            .saturating_mul(10)
+            .saturating_add((digit - b'0') as usize)
+    }))
+}
+
+fn exceeds(limit: &str, value: usize, max: usize) -> Result<(), Error> {
+    if value > max {
+        return Err(Error::LimitExceeded {
+            limit: limit.to_string(),
+            max,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn limits() -> ParseLimits {
+        ParseLimits {
+            max_document_size: 4096,
+            max_depth: 4,
+            max_attributes: 2,
+            max_array_length: 2,
+            max_string_length: 16,
+        }
+    }
+
+    fn exceeded(xml: &str) -> String {
+        match limits().check(xml.as_bytes()) {
+            Err(Error::LimitExceeded { limit, .. }) => limit,
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn within_limits() {
+        let xml =
+            r#"<a x="1"><b><c soapenc:arrayType="xsd:string[2]"><s>12345678</s><s/></c></b></a>"#;
+        assert!(limits().check(xml.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn limits_are_enforced() {
+        assert_eq!(
+            exceeded("<a><b><c><d><e/></d></c></b></a>"),
+            "nesting depth"
+        );
+        assert_eq!(exceeded(r#"<a x="1" y="2" z="3"/>"#), "attribute count");
+        assert_eq!(exceeded("<a>12345678901234567</a>"), "string length");
+        assert_eq!(exceeded("<a>123456789012345&amp;</a>"), "string length");
+        assert_eq!(exceeded(r#"<a x="12345678901234567"/>"#), "string length");
+        assert_eq!(
+            exceeded(r#"<a soapenc:arrayType="s[99999999999]"/>"#),
+            "array length"
+        );
+        // the declared length is not trusted
+        assert_eq!(
+            exceeded(r#"<a soapenc:arrayType="xsd:string[1]"><s/><s/><s/></a>"#),
+            "array length"
+        );
+        assert_eq!(
+            ParseLimits {
+                max_document_size: 4,
+                ..limits()
+            }
+            .check(b"<abc/>")
+            .map_err(|e| e.to_string())
+            .unwrap_err(),
+            "Parse limit exceeded: document size is above 4."
+        );
+    }
+}
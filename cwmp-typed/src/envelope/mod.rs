@@ -1,24 +1,543 @@
+mod batch;
 mod body;
 mod fault;
 mod header;
+mod limits;
+mod profile;
+mod raw;
+mod serialize;
 
+pub use batch::EnvelopeBatch;
 use body::EnvelopeBody;
-use header::EnvelopeHeaders;
+pub use fault::{Fault, SetParameterValuesFault, SoapFault};
+use header::{EnvelopeHeader, EnvelopeHeaders};
+pub use limits::ParseLimits;
+pub(crate) use profile::NS_XSD;
+pub use profile::{
+    BooleanStyle, EmptyElementStyle, NamespacePlacement, Prefixes, SerializerProfile,
+};
+use profile::{NS_SOAPENC, NS_SOAPENV, NS_XSI, cwmp_namespace};
+use raw::{ElementPath, resolve_reference};
+pub use raw::{PrefixMap, RawDocument, RawElement, RawNode};
+use serialize::EnvelopeWriter;
 
 use crate::cwmp::CwmpVersion;
+use crate::cwmp::redact::SensitiveParameters;
+use crate::cwmp::rpc::Rpc;
+use crate::cwmp::validate::{ValidationError, validate, validate_negotiated};
+use crate::error::Error;
+use quick_xml::events::{BytesText, Event};
+use std::borrow::Cow;
+use std::fmt;
+use std::io::Cursor;
+use xsd_parser_types::quick_xml::{DeserializeSync, IoReader, XmlReader};
+use xsd_parser_types::xml::{AnyElement, Value};
 
+#[derive(Debug)]
 pub struct Element(pub xsd_parser_types::xml::AnyElement);
 
-const NS_SOAPENV: &'static str = "http://schemas.xmlsoap.org/soap/envelope/";
-const NS_SOAPENC: &'static str = "http://schemas.xmlsoap.org/soap/encoding/";
-const NS_XSI: &'static str = "http://www.w3.org/2001/XMLSchema-instance";
-const NS_XSD: &'static str = "http://www.w3.org/2001/XMLSchema";
+// header elements the generated schema knows how to deserialize
+const KNOWN_HEADERS: [&[u8]; 5] = [
+    b"ID",
+    b"HoldRequests",
+    b"SessionTimeout",
+    b"SupportedCWMPVersions",
+    b"UseCWMPVersion",
+];
 
 #[derive(Debug)]
 pub struct Envelope {
     pub(crate) cwmp_version: CwmpVersion,
     pub(crate) headers: EnvelopeHeaders,
     pub(crate) body: EnvelopeBody,
+    pub(crate) preserved: Option<Preserved>,
+}
+
+/// What a lossless parse keeps on top of the typed envelope.
+pub struct Preserved {
+    pub(crate) document: RawDocument,
+    pub(crate) prefixes: PrefixMap,
+    pub(crate) unknown: Vec<Element>,
+    // the unknown elements as received, with the path of their parent
+    pub(crate) removed: Vec<(ElementPath, RawElement)>,
+    // the typed envelope as parsed, to tell whether it was modified since
+    pub(crate) parsed: Option<Vec<u8>>,
+}
+
+// the raw document holds every value in clear text
+impl fmt::Debug for Preserved {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Preserved")
+            .field("prefixes", &self.prefixes)
+            .field("unknown", &self.unknown)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An envelope pretty-printed with secrets masked, see [`Envelope::redacted`].
+pub struct Redacted<'a> {
+    envelope: &'a Envelope,
+    sensitive: &'a SensitiveParameters,
+}
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self
+            .envelope
+            .to_redacted_xml(&SerializerProfile::pretty(), self.sensitive)
+        {
+            Ok(xml) => f.write_str(&String::from_utf8_lossy(&xml)),
+            Err(e) => write!(f, "<envelope not serializable: {e}>"),
+        }
+    }
+}
+
+impl Preserved {
+    pub fn prefixes(&self) -> &PrefixMap {
+        &self.prefixes
+    }
+
+    /// Header entries and vendor `X_` elements the typed model has no place for.
+    pub fn unknown(&self) -> &[Element] {
+        &self.unknown
+    }
+
+    /// The default profile with the sender's prefixes wherever it declared one.
+    pub(crate) fn profile(&self, version: &CwmpVersion) -> SerializerProfile {
+        let mut profile = SerializerProfile {
+            xml_declaration: self
+                .document
+                .nodes
+                .iter()
+                .any(|node| matches!(node, RawNode::Other(Event::Decl(_)))),
+            ..SerializerProfile::default()
+        };
+        let prefixes = &mut profile.prefixes;
+        for (prefix, namespace) in [
+            (&mut prefixes.soapenv, NS_SOAPENV),
+            (&mut prefixes.soapenc, NS_SOAPENC),
+            (&mut prefixes.xsi, NS_XSI),
+            (&mut prefixes.xsd, NS_XSD),
+            (&mut prefixes.cwmp, cwmp_namespace(version)),
+        ] {
+            // the default namespace can't be used, rpc arguments are unqualified
+            if let Some(sent) = self.prefixes.prefix_of(namespace)
+                && !sent.is_empty()
+            {
+                *prefix = sent.to_string();
+            }
+        }
+        profile
+    }
+}
+
+impl Envelope {
+    /// Parses an envelope within the [`ParseLimits::default`] bounds.
+    pub fn parse(xml: &[u8]) -> Result<Self, Error> {
+        Self::parse_with_limits(xml, &ParseLimits::default())
+    }
+
+    pub fn parse_with_limits(xml: &[u8], limits: &ParseLimits) -> Result<Self, Error> {
+        limits.check(xml)?;
+        Self::deserialize(xml)
+    }
+
+    // callers are responsible for checking the limits
+    fn deserialize(xml: &[u8]) -> Result<Self, Error> {
+        let cursor = Cursor::new(xml);
+        let mut reader = IoReader::new(cursor).with_error_info();
+        let envelope = cwmp_xsd_schema::soapenv::EnvelopeType::deserialize(&mut reader)
+            .map_err(|e| Error::InvalidXml(e.to_string()))?;
+        envelope.try_into()
+    }
+
+    /// Parses an envelope while keeping everything needed to write it back
+    /// unchanged: vendor elements, extra attributes, comments and the
+    /// sender's own namespace prefixes.
+    pub fn parse_lossless(xml: &[u8]) -> Result<Self, Error> {
+        ParseLimits::default().check(xml)?;
+        let document = RawDocument::parse(xml)?;
+        let prefixes = document.prefixes()?;
+
+        // the generated deserializer rejects content it doesn't know, so the
+        // typed envelope is parsed from a copy with unknown elements removed.
+        let mut stripped = document.clone();
+        let root = stripped
+            .root_mut()
+            .ok_or_else(|| Error::InvalidXml("Missing Envelope element.".to_string()))?;
+
+        let root_path: ElementPath = vec![(root.local_name().to_vec(), 0)];
+        let mut removed: Vec<(ElementPath, RawElement)> = Vec::new();
+        if let Some(header) = root.child_mut(b"Header") {
+            let mut parent = root_path.clone();
+            parent.push((b"Header".to_vec(), 0));
+            for element in
+                header.take_children(|child| !KNOWN_HEADERS.contains(&child.local_name()))
+            {
+                removed.push((parent.clone(), element));
+            }
+        }
+        if let Some(body) = root.child_mut(b"Body") {
+            for (path, element) in
+                body.take_descendants(&|child| child.local_name().starts_with(b"X_"))
+            {
+                let mut parent = root_path.clone();
+                parent.push((b"Body".to_vec(), 0));
+                parent.extend(path);
+                removed.push((parent, element));
+            }
+        }
+
+        let mut envelope = Self::deserialize(&stripped.write()?)?;
+        let parsed = envelope.to_xml(&SerializerProfile::default()).ok();
+        envelope.preserved = Some(Preserved {
+            document,
+            prefixes,
+            unknown: removed
+                .iter()
+                .map(|(_, element)| Element::try_from(element))
+                .collect::<Result<Vec<_>, _>>()?,
+            removed,
+            parsed,
+        });
+        Ok(envelope)
+    }
+
+    pub fn preserved(&self) -> Option<&Preserved> {
+        self.preserved.as_ref()
+    }
+
+    pub fn version(&self) -> &CwmpVersion {
+        &self.cwmp_version
+    }
+
+    /// The rpc of the body, `None` for a fault.
+    pub fn rpc(&self) -> Option<&Rpc> {
+        match &self.body {
+            EnvelopeBody::Rpc(rpc) => Some(rpc),
+            EnvelopeBody::Fault(_) => None,
+        }
+    }
+
+    pub fn rpc_mut(&mut self) -> Option<&mut Rpc> {
+        match &mut self.body {
+            EnvelopeBody::Rpc(rpc) => Some(rpc),
+            EnvelopeBody::Fault(_) => None,
+        }
+    }
+
+    /// The soap Fault of the body, `None` for an rpc.
+    pub fn soap_fault(&self) -> Option<&SoapFault> {
+        match &self.body {
+            EnvelopeBody::Fault(fault) => Some(fault),
+            EnvelopeBody::Rpc(_) => None,
+        }
+    }
+
+    /// The cwmp Fault, `None` for an rpc or a soap Fault without one.
+    pub fn fault(&self) -> Option<&Fault> {
+        self.soap_fault().and_then(|fault| fault.detail.as_ref())
+    }
+
+    pub fn fault_mut(&mut self) -> Option<&mut Fault> {
+        match &mut self.body {
+            EnvelopeBody::Fault(fault) => fault.detail.as_mut(),
+            EnvelopeBody::Rpc(_) => None,
+        }
+    }
+
+    /// The `cwmp:ID` header, repeated by the response to a request.
+    pub fn id(&self) -> Option<&str> {
+        self.headers.iter().find_map(|header| match header {
+            EnvelopeHeader::Id(inner) => Some(inner.content.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Sets the `cwmp:ID` header, replacing an earlier one.
+    pub fn set_id<T: Into<String>>(&mut self, id: T) {
+        self.headers
+            .replace(EnvelopeHeader::Id(crate::cwmp::header::Id {
+                must_understand: true,
+                content: id.into(),
+            }));
+    }
+
+    /// Whether the `HoldRequests` header asks the CPE to send no requests
+    /// until the next envelope.
+    pub fn hold_requests(&self) -> bool {
+        self.headers.iter().any(|header| match header {
+            EnvelopeHeader::HoldRequests(inner) => inner.content,
+            _ => false,
+        })
+    }
+
+    /// Sets the `HoldRequests` header, removing it when `hold` is false.
+    pub fn set_hold_requests(&mut self, hold: bool) {
+        if !hold {
+            self.headers.remove("HoldRequests");
+            return;
+        }
+        self.headers.replace(EnvelopeHeader::HoldRequests(
+            crate::cwmp::header::HoldRequests {
+                must_understand: true,
+                content: true,
+            },
+        ));
+    }
+
+    /// Writes the envelope exactly as it was received, or when it was modified
+    /// since, with the sender's prefixes and its unknown elements put back.
+    /// Only available for envelopes read with [`Envelope::parse_lossless`].
+    ///
+    /// A modified envelope is written anew: its comments, whitespace and the
+    /// attributes the schema doesn't define on known elements are lost. The
+    /// envelope counts as modified when its [`Envelope::to_xml`] with the
+    /// default profile differs from the one right after parsing, not from
+    /// the received bytes.
+    pub fn to_lossless_xml(&self) -> Result<Vec<u8>, Error> {
+        let Some(preserved) = &self.preserved else {
+            return Err(Error::InvalidValue(
+                "Envelope was not parsed in lossless mode.".to_string(),
+            ));
+        };
+        if self.to_xml(&SerializerProfile::default()).ok() == preserved.parsed {
+            return preserved.document.write();
+        }
+        let profile = preserved.profile(&self.cwmp_version);
+        let mut writer = EnvelopeWriter::new(&profile)?.preserving(preserved);
+        writer.envelope(self)?;
+        Ok(writer.into_inner())
+    }
+
+    /// Writes the envelope the way `profile` describes.
+    pub fn to_xml(&self, profile: &SerializerProfile) -> Result<Vec<u8>, Error> {
+        let mut writer = EnvelopeWriter::new(profile)?;
+        writer.envelope(self)?;
+        Ok(writer.into_inner())
+    }
+
+    /// Like [`Envelope::to_xml`], with the download credentials and the values
+    /// of `sensitive` parameters masked.
+    pub fn to_redacted_xml(
+        &self,
+        profile: &SerializerProfile,
+        sensitive: &SensitiveParameters,
+    ) -> Result<Vec<u8>, Error> {
+        let mut writer = EnvelopeWriter::new(profile)?.redacting(sensitive);
+        writer.envelope(self)?;
+        Ok(writer.into_inner())
+    }
+
+    /// For logging: displays as pretty-printed xml with secrets masked.
+    pub fn redacted<'a>(&'a self, sensitive: &'a SensitiveParameters) -> Redacted<'a> {
+        Redacted {
+            envelope: self,
+            sensitive,
+        }
+    }
+
+    /// The `major.minor` version agreed on through the `UseCWMPVersion` header.
+    pub fn negotiated_version(&self) -> Option<(u32, u32)> {
+        self.headers.iter().find_map(|header| match header {
+            EnvelopeHeader::UseCwmpVersion(inner) => parse_version(&inner.content),
+            _ => None,
+        })
+    }
+
+    /// Sets the `UseCWMPVersion` header, sent by the ACS in the InformResponse.
+    pub fn set_negotiated_version(&mut self, (major, minor): (u32, u32)) {
+        self.headers.replace(EnvelopeHeader::UseCwmpVersion(
+            crate::cwmp::header::UseCwmpVersion {
+                must_understand: true,
+                content: format!("{major}.{minor}"),
+            },
+        ));
+    }
+
+    /// The `major.minor` versions of the `SupportedCWMPVersions` header, empty
+    /// without one.
+    pub fn supported_versions(&self) -> Vec<(u32, u32)> {
+        self.headers
+            .iter()
+            .find_map(|header| match header {
+                EnvelopeHeader::SupportedCwmpVersions(inner) => {
+                    Some(inner.content.split(',').filter_map(parse_version).collect())
+                }
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Sets the `SupportedCWMPVersions` header, sent by the CPE in the Inform.
+    pub fn set_supported_versions(&mut self, versions: &[(u32, u32)]) {
+        let content = versions
+            .iter()
+            .map(|(major, minor)| format!("{major}.{minor}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.headers.replace(EnvelopeHeader::SupportedCwmpVersions(
+            crate::cwmp::header::SupportedCwmpVersions {
+                must_understand: None,
+                content,
+            },
+        ));
+    }
+
+    /// Validates the headers and the rpc against the envelope's cwmp version,
+    /// rejecting the features of 1.4 as no version was negotiated.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        self.check(None)
+    }
+
+    /// Like [`Envelope::validate`], with the version the session agreed on,
+    /// refer [`crate::session::AcsSession::negotiated_version`].
+    pub fn validate_negotiated(
+        &self,
+        negotiated_version: (u32, u32),
+    ) -> Result<(), Vec<ValidationError>> {
+        self.check(Some(negotiated_version))
+    }
+
+    fn check(&self, negotiated_version: Option<(u32, u32)>) -> Result<(), Vec<ValidationError>> {
+        let mut errors: Vec<ValidationError> = Vec::new();
+
+        for header in self.headers.iter() {
+            if header.introduced_in() > self.cwmp_version {
+                errors.push(ValidationError::new(
+                    format!("Header.{}", header.name()),
+                    Error::UnsupportedHeaderVersion(header.name().to_string()),
+                ));
+            }
+        }
+
+        if let EnvelopeBody::Rpc(rpc) = &self.body {
+            if *rpc.version() != self.cwmp_version {
+                errors.push(ValidationError::new(
+                    rpc.method().name(),
+                    Error::RpcVersionMismatch,
+                ));
+            }
+            let result = match negotiated_version {
+                Some(negotiated_version) => validate_negotiated(rpc, negotiated_version),
+                None => validate(rpc),
+            };
+            if let Err(mut rpc_errors) = result {
+                errors.append(&mut rpc_errors);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn parse_version(input: &str) -> Option<(u32, u32)> {
+    let (major, minor) = input.trim().split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// An envelope without headers, refer [`Envelope::set_id`].
+impl From<Rpc> for Envelope {
+    fn from(rpc: Rpc) -> Self {
+        Self {
+            cwmp_version: *rpc.version(),
+            headers: Vec::new().into(),
+            body: EnvelopeBody::Rpc(rpc),
+            preserved: None,
+        }
+    }
+}
+
+impl From<(CwmpVersion, Fault)> for Envelope {
+    fn from((cwmp_version, fault): (CwmpVersion, Fault)) -> Self {
+        Self {
+            cwmp_version,
+            headers: Vec::new().into(),
+            body: EnvelopeBody::Fault(fault.into()),
+            preserved: None,
+        }
+    }
+}
+
+impl TryFrom<cwmp_xsd_schema::soapenv::EnvelopeType> for Envelope {
+    type Error = Error;
+
+    fn try_from(input: cwmp_xsd_schema::soapenv::EnvelopeType) -> Result<Self, Self::Error> {
+        use cwmp_xsd_schema::soapenv::BodyTypeContent;
+
+        let mut header_version: Option<CwmpVersion> = None;
+        let mut headers: Vec<EnvelopeHeader> = Vec::new();
+        for content in input
+            .header
+            .map(|header| header.content)
+            .unwrap_or_default()
+        {
+            header_version = header_version.max(Some(header::header_version(&content)));
+            headers.push(content.into());
+        }
+
+        let (cwmp_version, body) = match input.body.content {
+            BodyTypeContent::SoapFault(fault) => {
+                let version = header_version.ok_or_else(|| {
+                    Error::InvalidValue(
+                        "Unable to determine the cwmp version of the envelope.".to_string(),
+                    )
+                })?;
+                (version, EnvelopeBody::Fault(fault.try_into()?))
+            }
+            content => {
+                let rpc: Rpc = content.try_into()?;
+                (*rpc.version(), EnvelopeBody::Rpc(rpc))
+            }
+        };
+
+        Ok(Self {
+            cwmp_version,
+            headers: headers.into(),
+            body,
+            preserved: None,
+        })
+    }
+}
+
+impl TryFrom<&RawElement> for Element {
+    type Error = Error;
+
+    fn try_from(input: &RawElement) -> Result<Self, Self::Error> {
+        let mut element = AnyElement::new().name(Cow::Owned(input.name().to_vec()));
+        for (key, value) in input.attributes()? {
+            element =
+                element.attribute(Cow::Owned(key.into_bytes()), Cow::Owned(value.into_bytes()));
+        }
+        for child in input.children.iter() {
+            element = match child {
+                RawNode::Element(inner) => {
+                    element.child(Value::Element(Element::try_from(inner)?.0))
+                }
+                RawNode::Other(Event::Text(text)) => element.child(Value::Text(text.clone())),
+                RawNode::Other(Event::CData(data)) => element.child(Value::CData(data.clone())),
+                RawNode::Other(Event::Comment(comment)) => {
+                    element.child(Value::Comment(comment.clone()))
+                }
+                // entities declared by a DTD are kept as written
+                RawNode::Other(Event::GeneralRef(reference)) => {
+                    element.child(Value::Text(match resolve_reference(reference) {
+                        Some(resolved) => BytesText::new(&resolved).into_owned(),
+                        None => BytesText::from_escaped(format!(
+                            "&{};",
+                            String::from_utf8_lossy(reference)
+                        )),
+                    }))
+                }
+                RawNode::Other(_) => element,
+            };
+        }
+        Ok(Element(element))
+    }
 }
 
 #[cfg(test)]
@@ -38,4 +557,342 @@ mod test {
     use xsd_parser_types::quick_xml::{
         DeserializeSync, IoReader, SerializeSync, Writer, XmlReader,
     };
+
+    use super::{BooleanStyle, Envelope, NamespacePlacement, ParseLimits, SerializerProfile};
+    use crate::cwmp::CwmpVersion;
+    use crate::cwmp::event::EventCode;
+    use crate::cwmp::redact::SensitiveParameters;
+    use crate::cwmp::rpc::{Rpc, RpcMethod};
+
+    const VENDOR_ENVELOPE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp1="urn:dslforum-org:cwmp-1-2" xmlns:acme="urn:acme:tr069">
+  <SOAP-ENV:Header>
+    <cwmp1:ID SOAP-ENV:mustUnderstand="1">1234</cwmp1:ID>
+    <acme:X_ACME_SessionHint>fast</acme:X_ACME_SessionHint>
+  </SOAP-ENV:Header>
+  <SOAP-ENV:Body>
+    <!-- vendor stack 4.2 -->
+    <cwmp1:SetParameterValuesResponse>
+      <Status>0</Status>
+      <X_ACME_CommitId>77</X_ACME_CommitId>
+    </cwmp1:SetParameterValuesResponse>
+  </SOAP-ENV:Body>
+</SOAP-ENV:Envelope>
+"#;
+
+    #[test]
+    fn parse_envelope() {
+        let envelope = Envelope::parse(
+            br#"
+<soap:Envelope
+    xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"
+    xmlns:cwmp="urn:dslforum-org:cwmp-1-0">
+  <soap:Header>
+    <cwmp:ID soap:mustUnderstand="1">1234</cwmp:ID>
+  </soap:Header>
+  <soap:Body>
+    <cwmp:RebootResponse/>
+  </soap:Body>
+</soap:Envelope>"#,
+        )
+        .unwrap();
+        assert_eq!(envelope.cwmp_version, CwmpVersion::_10);
+        assert_eq!(envelope.headers.iter().count(), 1);
+        assert!(envelope.preserved().is_none());
+        assert!(envelope.to_lossless_xml().is_err());
+    }
+
+    fn inform_envelope(event_code: &str) -> String {
+        format!(
+            r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:soapenc="http://schemas.xmlsoap.org/soap/encoding/" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><soap:Header><cwmp:ID soap:mustUnderstand="1">1</cwmp:ID></soap:Header><soap:Body><cwmp:Inform><DeviceId><Manufacturer>ACME</Manufacturer><OUI>00D09E</OUI><ProductClass>Router</ProductClass><SerialNumber>1234</SerialNumber></DeviceId><Event soapenc:arrayType="cwmp:EventStruct[1]"><EventStruct><EventCode>{event_code}</EventCode><CommandKey></CommandKey></EventStruct></Event><MaxEnvelopes>1</MaxEnvelopes><CurrentTime>2024-01-01T00:00:00Z</CurrentTime><RetryCount>0</RetryCount><ParameterList soapenc:arrayType="cwmp:ParameterValueStruct[0]"></ParameterList></cwmp:Inform></soap:Body></soap:Envelope>"#
+        )
+    }
+
+    #[test]
+    fn inform_events() {
+        let envelope = Envelope::parse(inform_envelope("0 BOOTSTRAP").as_bytes()).unwrap();
+        let Some(RpcMethod::Inform(inform)) = envelope.rpc().map(Rpc::method) else {
+            panic!("not an Inform");
+        };
+        assert_eq!(inform.event()[0].event_code(), &EventCode::Bootstrap);
+
+        // an unknown event code fails the parse rather than being dropped
+        assert!(Envelope::parse(inform_envelope("BOOTSTRAP").as_bytes()).is_err());
+    }
+
+    #[test]
+    fn lossless_round_trip() {
+        let envelope = Envelope::parse_lossless(VENDOR_ENVELOPE.as_bytes()).unwrap();
+        assert_eq!(envelope.cwmp_version, CwmpVersion::_12);
+
+        let xml = envelope.to_lossless_xml().unwrap();
+        assert_eq!(String::from_utf8(xml).unwrap(), VENDOR_ENVELOPE);
+    }
+
+    #[test]
+    fn lossless_keeps_prefixes_and_unknown_elements() {
+        let envelope = Envelope::parse_lossless(VENDOR_ENVELOPE.as_bytes()).unwrap();
+        let preserved = envelope.preserved().unwrap();
+
+        assert_eq!(
+            preserved
+                .prefixes()
+                .prefix_of("http://schemas.xmlsoap.org/soap/envelope/"),
+            Some("SOAP-ENV")
+        );
+        assert_eq!(
+            preserved.prefixes().prefix_of("urn:dslforum-org:cwmp-1-2"),
+            Some("cwmp1")
+        );
+        assert_eq!(preserved.unknown().len(), 2);
+    }
+
+    #[test]
+    fn lossless_modified_envelope() {
+        let mut envelope = Envelope::parse_lossless(VENDOR_ENVELOPE.as_bytes()).unwrap();
+        envelope.set_id("5678");
+
+        // written anew, without the comment and the indentation
+
+        let xml = String::from_utf8(envelope.to_lossless_xml().unwrap()).unwrap();
+        assert_eq!(
+            xml,
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+                r#"<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:soapenc="http://schemas.xmlsoap.org/soap/encoding/" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:cwmp1="urn:dslforum-org:cwmp-1-2" xmlns:acme="urn:acme:tr069">"#,
+                r#"<SOAP-ENV:Header><cwmp1:ID SOAP-ENV:mustUnderstand="1">5678</cwmp1:ID><acme:X_ACME_SessionHint>fast</acme:X_ACME_SessionHint></SOAP-ENV:Header>"#,
+                r#"<SOAP-ENV:Body><cwmp1:SetParameterValuesResponse><Status>0</Status><X_ACME_CommitId>77</X_ACME_CommitId></cwmp1:SetParameterValuesResponse></SOAP-ENV:Body>"#,
+                r#"</SOAP-ENV:Envelope>"#
+            )
+        );
+
+        // the modified envelope parses back with the same unknown elements
+        let reparsed = Envelope::parse_lossless(xml.as_bytes()).unwrap();
+        assert_eq!(reparsed.id(), Some("5678"));
+        assert_eq!(reparsed.preserved().unwrap().unknown().len(), 2);
+    }
+
+    #[test]
+    fn lossless_unknown_element_references() {
+        let xml = br#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><soap:Header><cwmp:ID soap:mustUnderstand="1">1</cwmp:ID><X_ACME_Note>a &amp; b&#33;</X_ACME_Note></soap:Header><soap:Body><cwmp:RebootResponse/></soap:Body></soap:Envelope>"#;
+        let envelope = Envelope::parse_lossless(xml).unwrap();
+        let element = &envelope.preserved().unwrap().unknown()[0];
+
+        let mut writer = Writer::new(Vec::new());
+        element.0.serialize("X_ACME_Note", &mut writer).unwrap();
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).unwrap(),
+            "<X_ACME_Note>a &amp; b!</X_ACME_Note>"
+        );
+    }
+
+    const REBOOT_RESPONSE: &[u8] = br#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><soap:Header><cwmp:ID soap:mustUnderstand="1">1234</cwmp:ID></soap:Header><soap:Body><cwmp:RebootResponse/></soap:Body></soap:Envelope>"#;
+
+    #[test]
+    fn to_xml_default_profile() {
+        let envelope = Envelope::parse(REBOOT_RESPONSE).unwrap();
+        let xml =
+            String::from_utf8(envelope.to_xml(&SerializerProfile::default()).unwrap()).unwrap();
+        assert_eq!(
+            xml,
+            concat!(
+                r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:soapenc="http://schemas.xmlsoap.org/soap/encoding/" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:cwmp="urn:dslforum-org:cwmp-1-0">"#,
+                r#"<soap:Header><cwmp:ID soap:mustUnderstand="1">1234</cwmp:ID></soap:Header>"#,
+                r#"<soap:Body><cwmp:RebootResponse/></soap:Body></soap:Envelope>"#
+            )
+        );
+    }
+
+    #[test]
+    fn build_envelope() {
+        let mut envelope = Envelope::from(Rpc::from((CwmpVersion::_10, RpcMethod::RebootResponse)));
+        assert!(envelope.id().is_none());
+        envelope.set_id("1");
+        envelope.set_id("1234");
+        assert_eq!(envelope.id(), Some("1234"));
+        assert_eq!(
+            envelope.to_xml(&SerializerProfile::default()).unwrap(),
+            Envelope::parse(REBOOT_RESPONSE)
+                .unwrap()
+                .to_xml(&SerializerProfile::default())
+                .unwrap()
+        );
+    }
+
+    const FAULT_BODY: &str = concat!(
+        r#"<soap:Body><soap:Fault><faultcode>Client</faultcode><faultstring>CWMP fault</faultstring>"#,
+        r#"<detail><cwmp:Fault><FaultCode>9003</FaultCode><FaultString>Invalid arguments</FaultString>"#,
+        r#"<SetParameterValuesFault><ParameterName>Device.WiFi.SSID.1.SSID</ParameterName>"#,
+        r#"<FaultCode>9007</FaultCode><FaultString>Invalid parameter value</FaultString>"#,
+        r#"</SetParameterValuesFault></cwmp:Fault></detail></soap:Fault></soap:Body>"#
+    );
+
+    #[test]
+    fn fault_round_trip() {
+        let xml = format!(
+            r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><soap:Header><cwmp:ID soap:mustUnderstand="1">1234</cwmp:ID></soap:Header>{FAULT_BODY}</soap:Envelope>"#
+        );
+        let envelope = Envelope::parse(xml.as_bytes()).unwrap();
+        assert!(envelope.rpc().is_none());
+        let fault = envelope.fault().unwrap();
+        assert_eq!(fault.fault_code, 9003);
+        assert_eq!(fault.soap_fault_code(), "Client");
+        assert_eq!(fault.set_parameter_values_faults[0].fault_code, 9007);
+
+        let written =
+            String::from_utf8(envelope.to_xml(&SerializerProfile::default()).unwrap()).unwrap();
+        assert!(written.contains(FAULT_BODY));
+    }
+
+    #[test]
+    fn fault_without_detail() {
+        let body = concat!(
+            r#"<soap:Body><soap:Fault><faultcode>Server</faultcode>"#,
+            r#"<faultstring>Internal error</faultstring></soap:Fault></soap:Body>"#
+        );
+        let xml = format!(
+            r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><soap:Header><cwmp:ID soap:mustUnderstand="1">1234</cwmp:ID></soap:Header>{body}</soap:Envelope>"#
+        );
+        let envelope = Envelope::parse(xml.as_bytes()).unwrap();
+        let soap_fault = envelope.soap_fault().unwrap();
+        assert_eq!(soap_fault.fault_code, "Server");
+        assert_eq!(soap_fault.fault_string, "Internal error");
+        assert!(envelope.fault().is_none());
+
+        let written =
+            String::from_utf8(envelope.to_xml(&SerializerProfile::default()).unwrap()).unwrap();
+        assert!(written.contains(body));
+    }
+
+    #[test]
+    fn to_xml_gsoap_profile() {
+        let envelope = Envelope::parse(REBOOT_RESPONSE).unwrap();
+        let xml = String::from_utf8(envelope.to_xml(&SerializerProfile::gsoap()).unwrap()).unwrap();
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?><SOAP-ENV:Envelope"#));
+        assert!(xml.contains(r#"<cwmp:ID SOAP-ENV:mustUnderstand="1">1234</cwmp:ID>"#));
+        assert!(xml.contains("<cwmp:RebootResponse></cwmp:RebootResponse>"));
+        assert!(!xml.contains("/>"));
+    }
+
+    #[test]
+    fn to_xml_literal_booleans_and_first_use_namespaces() {
+        let envelope = Envelope::parse(
+            br#"
+<soap:Envelope
+    xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"
+    xmlns:soapenc="http://schemas.xmlsoap.org/soap/encoding/"
+    xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+    xmlns:cwmp="urn:dslforum-org:cwmp-1-0">
+  <soap:Header/>
+  <soap:Body>
+    <cwmp:GetParameterNames>
+      <ParameterPath>Device.</ParameterPath>
+      <NextLevel>1</NextLevel>
+    </cwmp:GetParameterNames>
+  </soap:Body>
+</soap:Envelope>"#,
+        )
+        .unwrap();
+        let profile = SerializerProfile {
+            namespaces: NamespacePlacement::FirstUse,
+            booleans: BooleanStyle::Literal,
+            ..SerializerProfile::default()
+        };
+        let xml = String::from_utf8(envelope.to_xml(&profile).unwrap()).unwrap();
+        assert!(!xml.contains("xmlns:xsd"));
+        assert!(xml.contains("<NextLevel>true</NextLevel>"));
+    }
+
+    #[test]
+    fn redacted_logging() {
+        let envelope = Envelope::parse(
+            br#"
+<soap:Envelope
+    xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"
+    xmlns:soapenc="http://schemas.xmlsoap.org/soap/encoding/"
+    xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+    xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+    xmlns:cwmp="urn:dslforum-org:cwmp-1-0">
+  <soap:Header/>
+  <soap:Body>
+    <cwmp:SetParameterValues>
+      <ParameterList soapenc:arrayType="cwmp:ParameterValueStruct[2]">
+        <ParameterValueStruct>
+          <Name>Device.WiFi.SSID.1.SSID</Name>
+          <Value xsi:type="xsd:string">MyHomeWiFi</Value>
+        </ParameterValueStruct>
+        <ParameterValueStruct>
+          <Name>Device.WiFi.AccessPoint.1.Security.KeyPassphrase</Name>
+          <Value xsi:type="xsd:string">SuperSecurePass123!</Value>
+        </ParameterValueStruct>
+      </ParameterList>
+      <ParameterKey>key</ParameterKey>
+    </cwmp:SetParameterValues>
+  </soap:Body>
+</soap:Envelope>"#,
+        )
+        .unwrap();
+        let sensitive = SensitiveParameters::default();
+
+        let logged = envelope.redacted(&sensitive).to_string();
+        assert!(logged.contains("MyHomeWiFi"));
+        assert!(!logged.contains("SuperSecurePass123!"));
+        assert!(!format!("{envelope:?}").contains("SuperSecurePass123!"));
+
+        // plain serialization is untouched
+        let xml = envelope.to_xml(&SerializerProfile::default()).unwrap();
+        assert!(
+            String::from_utf8(xml)
+                .unwrap()
+                .contains("SuperSecurePass123!")
+        );
+    }
+
+    #[test]
+    fn parse_with_limits() {
+        let limits = ParseLimits {
+            max_depth: 2,
+            ..ParseLimits::default()
+        };
+        assert!(matches!(
+            Envelope::parse_with_limits(REBOOT_RESPONSE, &limits),
+            Err(crate::error::Error::LimitExceeded { .. })
+        ));
+        assert!(Envelope::parse_with_limits(REBOOT_RESPONSE, &ParseLimits::unlimited()).is_ok());
+    }
+
+    #[test]
+    fn alias_add_object_needs_cwmp_14() {
+        let envelope = Envelope::parse(
+            br#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-2"><soap:Header><cwmp:ID soap:mustUnderstand="1">1</cwmp:ID></soap:Header><soap:Body><cwmp:AddObject><ObjectName>Device.IP.Interface.[wan].</ObjectName><ParameterKey></ParameterKey></cwmp:AddObject></soap:Body></soap:Envelope>"#,
+        )
+        .unwrap();
+
+        // the 1.2 namespace alone is 1.2 to 1.4
+        assert!(envelope.validate_negotiated((1, 4)).is_ok());
+        for errors in [
+            envelope.validate().unwrap_err(),
+            envelope.validate_negotiated((1, 3)).unwrap_err(),
+        ] {
+            assert_eq!(errors[0].field, "AddObject.ObjectName");
+        }
+    }
+
+    #[test]
+    fn version_headers() {
+        let mut envelope = Envelope::from(Rpc::from((CwmpVersion::_12, RpcMethod::RebootResponse)));
+        assert!(envelope.supported_versions().is_empty());
+        assert_eq!(envelope.negotiated_version(), None);
+
+        envelope.set_supported_versions(&[(1, 0), (1, 2), (1, 4)]);
+        assert_eq!(envelope.supported_versions(), vec![(1, 0), (1, 2), (1, 4)]);
+        envelope.set_negotiated_version((1, 4));
+        assert_eq!(envelope.negotiated_version(), Some((1, 4)));
+
+        let xml =
+            String::from_utf8(envelope.to_xml(&SerializerProfile::default()).unwrap()).unwrap();
+        assert!(xml.contains(
+            r#"<cwmp:SupportedCWMPVersions>1.0,1.2,1.4</cwmp:SupportedCWMPVersions><cwmp:UseCWMPVersion soap:mustUnderstand="1">1.4</cwmp:UseCWMPVersion>"#
+        ));
+    }
 }
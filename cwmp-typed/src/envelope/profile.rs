@@ -0,0 +1,182 @@
+use crate::cwmp::CwmpVersion;
+use crate::error::Error;
+
+pub const NS_SOAPENV: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+pub const NS_SOAPENC: &str = "http://schemas.xmlsoap.org/soap/encoding/";
+pub const NS_XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+pub const NS_XSD: &str = "http://www.w3.org/2001/XMLSchema";
+
+pub fn cwmp_namespace(version: &CwmpVersion) -> &'static str {
+    match version {
+        CwmpVersion::_10 => "urn:dslforum-org:cwmp-1-0",
+        CwmpVersion::_11 => "urn:dslforum-org:cwmp-1-1",
+        CwmpVersion::_12 => "urn:dslforum-org:cwmp-1-2",
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamespacePlacement {
+    /// Every namespace is declared on the `Envelope` element.
+    Envelope,
+    /// Only the soap envelope and cwmp namespaces are declared on the
+    /// `Envelope`, the rest on each element that uses them.
+    FirstUse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyElementStyle {
+    /// `<soap:Header/>`
+    SelfClosing,
+    /// `<soap:Header></soap:Header>`
+    Expanded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanStyle {
+    /// `0` and `1`
+    Numeric,
+    /// `false` and `true`
+    Literal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prefixes {
+    pub soapenv: String,
+    pub soapenc: String,
+    pub xsi: String,
+    pub xsd: String,
+    pub cwmp: String,
+}
+
+/// Controls how outgoing envelopes are written, so the output can be
+/// matched to what a particular CPE stack accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerializerProfile {
+    pub prefixes: Prefixes,
+    pub namespaces: NamespacePlacement,
+    pub empty_elements: EmptyElementStyle,
+    /// Indent character and its count per level, `None` writes everything on one line.
+    pub indent: Option<(u8, usize)>,
+    pub xml_declaration: bool,
+    pub booleans: BooleanStyle,
+}
+
+impl Default for SerializerProfile {
+    fn default() -> Self {
+        Self {
+            prefixes: Prefixes {
+                soapenv: "soap".to_string(),
+                soapenc: "soapenc".to_string(),
+                xsi: "xsi".to_string(),
+                xsd: "xsd".to_string(),
+                cwmp: "cwmp".to_string(),
+            },
+            namespaces: NamespacePlacement::Envelope,
+            empty_elements: EmptyElementStyle::SelfClosing,
+            indent: None,
+            xml_declaration: false,
+            booleans: BooleanStyle::Numeric,
+        }
+    }
+}
+
+impl SerializerProfile {
+    /// gSOAP based agents: `SOAP-ENV`/`SOAP-ENC` prefixes, an xml declaration
+    /// and no self-closing elements.
+    pub fn gsoap() -> Self {
+        Self {
+            prefixes: Prefixes {
+                soapenv: "SOAP-ENV".to_string(),
+                soapenc: "SOAP-ENC".to_string(),
+                ..Self::default().prefixes
+            },
+            empty_elements: EmptyElementStyle::Expanded,
+            xml_declaration: true,
+            ..Self::default()
+        }
+    }
+
+    /// easycwmp/freecwmp style agents: `soap_env`/`soap_enc` prefixes and no
+    /// self-closing elements.
+    pub fn easycwmp() -> Self {
+        Self {
+            prefixes: Prefixes {
+                soapenv: "soap_env".to_string(),
+                soapenc: "soap_enc".to_string(),
+                ..Self::default().prefixes
+            },
+            empty_elements: EmptyElementStyle::Expanded,
+            xml_declaration: true,
+            ..Self::default()
+        }
+    }
+
+    /// The prefixes GenieACS sends, for CPEs that were tuned against it.
+    pub fn genieacs() -> Self {
+        Self {
+            prefixes: Prefixes {
+                soapenv: "soap-env".to_string(),
+                soapenc: "soap-enc".to_string(),
+                ..Self::default().prefixes
+            },
+            ..Self::default()
+        }
+    }
+
+    /// Human readable output for logs and debugging.
+    pub fn pretty() -> Self {
+        Self {
+            indent: Some((b' ', 2)),
+            xml_declaration: true,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        let Prefixes {
+            soapenv,
+            soapenc,
+            xsi,
+            xsd,
+            cwmp,
+        } = &self.prefixes;
+        // rpc arguments are unqualified, so none of the namespaces can be the default one
+        for prefix in [soapenv, soapenc, xsi, xsd, cwmp] {
+            if prefix.is_empty() || prefix.contains([':', ' ', '<', '>', '"', '\'']) {
+                return Err(Error::InvalidValue(format!(
+                    "'{prefix}' is not a valid namespace prefix."
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn soapenv(&self, local_name: &str) -> String {
+        format!("{}:{local_name}", self.prefixes.soapenv)
+    }
+
+    pub(crate) fn soapenc(&self, local_name: &str) -> String {
+        format!("{}:{local_name}", self.prefixes.soapenc)
+    }
+
+    pub(crate) fn xsi(&self, local_name: &str) -> String {
+        format!("{}:{local_name}", self.prefixes.xsi)
+    }
+
+    pub(crate) fn xsd(&self, local_name: &str) -> String {
+        format!("{}:{local_name}", self.prefixes.xsd)
+    }
+
+    pub(crate) fn cwmp(&self, local_name: &str) -> String {
+        format!("{}:{local_name}", self.prefixes.cwmp)
+    }
+
+    pub(crate) fn boolean(&self, value: bool) -> &'static str {
+        match (self.booleans, value) {
+            (BooleanStyle::Numeric, true) => "1",
+            (BooleanStyle::Numeric, false) => "0",
+            (BooleanStyle::Literal, true) => "true",
+            (BooleanStyle::Literal, false) => "false",
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! Lossless event tree of a received document. Every event is kept exactly as
+//! quick-xml read it (prefixes, attribute quoting, comments, whitespace and
+//! self-closing tags), so writing the tree back reproduces the input.
+use crate::error::Error;
+use quick_xml::Reader;
+use quick_xml::Writer;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesEnd, BytesRef, BytesStart, Event};
+
+#[derive(Debug, Clone)]
+pub enum RawNode {
+    Element(RawElement),
+    // text, comments, cdata, references, declarations and processing instructions
+    Other(Event<'static>),
+}
+
+#[derive(Debug, Clone)]
+pub struct RawElement {
+    pub(crate) start: BytesStart<'static>,
+    pub(crate) children: Vec<RawNode>,
+    // `None` for a self-closing tag
+    pub(crate) end: Option<BytesEnd<'static>>,
+}
+
+/// Location of an element as (local name, index among the same named
+/// siblings) pairs, starting at the document root.
+pub(crate) type ElementPath = Vec<(Vec<u8>, usize)>;
+
+#[derive(Debug, Clone)]
+pub struct RawDocument {
+    pub(crate) nodes: Vec<RawNode>,
+}
+
+/// Namespace declarations as written by the sender, in document order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefixMap(pub Vec<(String, String)>);
+
+impl PrefixMap {
+    pub fn prefix_of(&self, namespace: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(_, ns)| ns == namespace)
+            .map(|(prefix, _)| prefix.as_str())
+    }
+
+    pub fn namespace_of(&self, prefix: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(p, _)| p == prefix)
+            .map(|(_, ns)| ns.as_str())
+    }
+}
+
+impl RawDocument {
+    pub fn parse(xml: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader::from_reader(xml);
+        let mut stack: Vec<RawElement> = Vec::new();
+        let mut nodes: Vec<RawNode> = Vec::new();
+        let mut buf = Vec::new();
+
+        loop {
+            let event = reader
+                .read_event_into(&mut buf)
+                .map_err(|e| Error::InvalidXml(e.to_string()))?
+                .into_owned();
+
+            let node = match event {
+                Event::Eof => break,
+                Event::Start(start) => {
+                    stack.push(RawElement {
+                        start,
+                        children: Vec::new(),
+                        end: None,
+                    });
+                    buf.clear();
+                    continue;
+                }
+                Event::End(end) => {
+                    let Some(mut element) = stack.pop() else {
+                        return Err(Error::InvalidXml("Unexpected closing tag.".to_string()));
+                    };
+                    element.end = Some(end);
+                    RawNode::Element(element)
+                }
+                Event::Empty(start) => RawNode::Element(RawElement {
+                    start,
+                    children: Vec::new(),
+                    end: None,
+                }),
+                other => RawNode::Other(other),
+            };
+
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(node),
+                None => nodes.push(node),
+            }
+            buf.clear();
+        }
+
+        if !stack.is_empty() {
+            return Err(Error::InvalidXml("Unexpected end of document.".to_string()));
+        }
+
+        Ok(Self { nodes })
+    }
+
+    pub fn write(&self) -> Result<Vec<u8>, Error> {
+        let mut writer = Writer::new(Vec::new());
+        for node in self.nodes.iter() {
+            node.write(&mut writer)?;
+        }
+        Ok(writer.into_inner())
+    }
+
+    /// Every namespace declaration in the document, in document order.
+    pub fn prefixes(&self) -> Result<PrefixMap, Error> {
+        fn collect(element: &RawElement, prefixes: &mut PrefixMap) -> Result<(), Error> {
+            prefixes.0.append(&mut element.namespace_declarations()?.0);
+            for child in element.children() {
+                collect(child, prefixes)?;
+            }
+            Ok(())
+        }
+
+        let mut prefixes = PrefixMap::default();
+        if let Some(root) = self.root() {
+            collect(root, &mut prefixes)?;
+        }
+        Ok(prefixes)
+    }
+
+    pub fn root(&self) -> Option<&RawElement> {
+        self.nodes.iter().find_map(|node| match node {
+            RawNode::Element(element) => Some(element),
+            RawNode::Other(_) => None,
+        })
+    }
+
+    pub fn root_mut(&mut self) -> Option<&mut RawElement> {
+        self.nodes.iter_mut().find_map(|node| match node {
+            RawNode::Element(element) => Some(element),
+            RawNode::Other(_) => None,
+        })
+    }
+}
+
+impl RawNode {
+    fn write(&self, writer: &mut Writer<Vec<u8>>) -> Result<(), Error> {
+        match self {
+            RawNode::Element(element) => element.write(writer),
+            RawNode::Other(event) => writer
+                .write_event(event.clone())
+                .map_err(|e| Error::InvalidXml(e.to_string())),
+        }
+    }
+}
+
+impl RawElement {
+    pub fn name(&self) -> &[u8] {
+        self.start.name().into_inner()
+    }
+
+    pub fn local_name(&self) -> &[u8] {
+        self.start.local_name().into_inner()
+    }
+
+    pub fn prefix(&self) -> Option<&[u8]> {
+        self.start.name().prefix().map(|prefix| prefix.into_inner())
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = &RawElement> {
+        self.children.iter().filter_map(|node| match node {
+            RawNode::Element(element) => Some(element),
+            RawNode::Other(_) => None,
+        })
+    }
+
+    pub fn child(&self, local_name: &[u8]) -> Option<&RawElement> {
+        self.children()
+            .find(|child| child.local_name() == local_name)
+    }
+
+    pub fn child_mut(&mut self, local_name: &[u8]) -> Option<&mut RawElement> {
+        self.children.iter_mut().find_map(|node| match node {
+            RawNode::Element(element) if element.local_name() == local_name => Some(element),
+            _ => None,
+        })
+    }
+
+    /// Removes the child elements matching `filter` and returns them in document order.
+    pub fn take_children<F>(&mut self, filter: F) -> Vec<RawElement>
+    where
+        F: Fn(&RawElement) -> bool,
+    {
+        let mut taken = Vec::new();
+        let mut kept = Vec::with_capacity(self.children.len());
+        for node in self.children.drain(..) {
+            match node {
+                RawNode::Element(element) if filter(&element) => taken.push(element),
+                other => kept.push(other),
+            }
+        }
+        self.children = kept;
+        taken
+    }
+
+    /// Like [`RawElement::take_children`] but searches the whole subtree. Each
+    /// element comes with the path of its parent, relative to `self`.
+    pub(crate) fn take_descendants(
+        &mut self,
+        filter: &dyn Fn(&RawElement) -> bool,
+    ) -> Vec<(ElementPath, RawElement)> {
+        let mut taken: Vec<(ElementPath, RawElement)> = self
+            .take_children(filter)
+            .into_iter()
+            .map(|element| (Vec::new(), element))
+            .collect();
+        let mut seen: Vec<Vec<u8>> = Vec::new();
+        for node in self.children.iter_mut() {
+            if let RawNode::Element(element) = node {
+                let name = element.local_name().to_vec();
+                let index = seen.iter().filter(|other| **other == name).count();
+                for (mut path, inner) in element.take_descendants(filter) {
+                    path.insert(0, (name.clone(), index));
+                    taken.push((path, inner));
+                }
+                seen.push(name);
+            }
+        }
+        taken
+    }
+
+    /// Attributes as (qualified name, unescaped value) pairs.
+    pub fn attributes(&self) -> Result<Vec<(String, String)>, Error> {
+        self.start
+            .attributes()
+            .map(|attr| {
+                let attr = attr.map_err(|e| Error::InvalidXml(e.to_string()))?;
+                let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+                let value = attr
+                    .unescape_value()
+                    .map_err(|e| Error::InvalidXml(e.to_string()))?
+                    .into_owned();
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    pub fn namespace_declarations(&self) -> Result<PrefixMap, Error> {
+        let declarations = self
+            .attributes()?
+            .into_iter()
+            .filter_map(|(key, value)| {
+                if key == "xmlns" {
+                    Some((String::new(), value))
+                } else {
+                    key.strip_prefix("xmlns:")
+                        .map(|prefix| (prefix.to_string(), value))
+                }
+            })
+            .collect();
+        Ok(PrefixMap(declarations))
+    }
+
+    /// Concatenated text content of the element and its descendants.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for node in self.children.iter() {
+            match node {
+                RawNode::Element(element) => text.push_str(&element.text()),
+                RawNode::Other(Event::Text(inner)) => {
+                    if let Ok(inner) = inner.decode() {
+                        text.push_str(&inner);
+                    }
+                }
+                RawNode::Other(Event::CData(inner)) => {
+                    if let Ok(inner) = inner.decode() {
+                        text.push_str(&inner);
+                    }
+                }
+                RawNode::Other(Event::GeneralRef(inner)) => {
+                    if let Some(resolved) = resolve_reference(inner) {
+                        text.push_str(&resolved);
+                    }
+                }
+                RawNode::Other(_) => {}
+            }
+        }
+        text
+    }
+
+    pub(crate) fn write(&self, writer: &mut Writer<Vec<u8>>) -> Result<(), Error> {
+        let map_err = |e: std::io::Error| Error::InvalidXml(e.to_string());
+        match &self.end {
+            None if self.children.is_empty() => writer
+                .write_event(Event::Empty(self.start.borrow()))
+                .map_err(map_err),
+            end => {
+                writer
+                    .write_event(Event::Start(self.start.borrow()))
+                    .map_err(map_err)?;
+                for child in self.children.iter() {
+                    child.write(writer)?;
+                }
+                let end = match end {
+                    Some(end) => end.borrow(),
+                    None => self.start.to_end(),
+                };
+                writer.write_event(Event::End(end)).map_err(map_err)
+            }
+        }
+    }
+}
+
+/// The text of a character reference or a predefined entity, `None` for
+/// entities declared by a DTD.
+pub(crate) fn resolve_reference(reference: &BytesRef<'_>) -> Option<String> {
+    if let Ok(Some(ch)) = reference.resolve_char_ref() {
+        return Some(ch.to_string());
+    }
+    reference
+        .decode()
+        .ok()
+        .and_then(|name| resolve_predefined_entity(&name))
+        .map(|entity| entity.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip_is_byte_for_byte() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp1='urn:dslforum-org:cwmp-1-0'>
+  <!-- sent by a CPE -->
+  <SOAP-ENV:Header><cwmp1:ID SOAP-ENV:mustUnderstand="1">42</cwmp1:ID><X_ACME_Trace a="1&amp;2"/></SOAP-ENV:Header>
+  <SOAP-ENV:Body >
+    <cwmp1:RebootResponse />
+    <![CDATA[ <raw> ]]>
+  </SOAP-ENV:Body>
+</SOAP-ENV:Envelope>
+"#;
+        let document = RawDocument::parse(xml.as_bytes()).unwrap();
+        let written = document.write().unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), xml);
+    }
+
+    #[test]
+    fn namespace_declarations() {
+        let xml = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-2"/>"#;
+        let document = RawDocument::parse(xml.as_bytes()).unwrap();
+        let prefixes = document.root().unwrap().namespace_declarations().unwrap();
+        assert_eq!(
+            prefixes.prefix_of("urn:dslforum-org:cwmp-1-2"),
+            Some("cwmp")
+        );
+        assert_eq!(
+            prefixes.namespace_of("soap"),
+            Some("http://schemas.xmlsoap.org/soap/envelope/")
+        );
+    }
+
+    #[test]
+    fn take_children() {
+        let xml = r#"<Header><ID>1</ID><X_ACME_Trace>abc</X_ACME_Trace></Header>"#;
+        let mut document = RawDocument::parse(xml.as_bytes()).unwrap();
+        let header = document.root_mut().unwrap();
+        let taken = header.take_children(|child| child.local_name().starts_with(b"X_"));
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].text(), "abc");
+        assert_eq!(
+            String::from_utf8(document.write().unwrap()).unwrap(),
+            "<Header><ID>1</ID></Header>"
+        );
+    }
+
+    #[test]
+    fn take_descendants_paths() {
+        let xml = r#"<Body><List><Item/><Item><X_A/></Item></List><X_B/></Body>"#;
+        let mut document = RawDocument::parse(xml.as_bytes()).unwrap();
+        let body = document.root_mut().unwrap();
+        let taken = body.take_descendants(&|child| child.local_name().starts_with(b"X_"));
+        let paths: Vec<_> = taken
+            .iter()
+            .map(|(path, element)| (path.clone(), element.local_name().to_vec()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                (vec![], b"X_B".to_vec()),
+                (
+                    vec![(b"List".to_vec(), 0), (b"Item".to_vec(), 1)],
+                    b"X_A".to_vec()
+                ),
+            ]
+        );
+    }
+}
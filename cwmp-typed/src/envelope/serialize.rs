@@ -0,0 +1,558 @@
+use super::body::EnvelopeBody;
+use super::fault::SoapFault;
+use super::header::EnvelopeHeader;
+use super::profile::{
+    EmptyElementStyle, NS_SOAPENC, NS_SOAPENV, NS_XSD, NS_XSI, NamespacePlacement,
+    SerializerProfile, cwmp_namespace,
+};
+use super::raw::ElementPath;
+use super::{Envelope, Preserved};
+use crate::cwmp::redact::{REDACTED, SensitiveParameters};
+use crate::cwmp::rpc::RpcMethod;
+use crate::cwmp::types::{
+    AccessList, AddObjectResponseStatus, AttributeNotificationValue, DeleteObjectResponseStatus,
+    DownloadResponseStatus, ParameterNames, ParameterValueStruct, ParameterValueType,
+    SetParameterValuesResponseStatus,
+};
+use crate::error::Error;
+use quick_xml::Writer;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+
+macro_rules! impl_code {
+    ($($ty: ty { $($variant: ident => $code: literal),+ }),+) => {
+        $(
+            impl $ty {
+                pub fn code(&self) -> &'static str {
+                    match self {
+                        $(Self::$variant => $code,)+
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_code!(
+    AttributeNotificationValue { _0 => "0", _1 => "1", _2 => "2", _3 => "3", _4 => "4", _5 => "5", _6 => "6" },
+    SetParameterValuesResponseStatus { _0 => "0", _1 => "1" },
+    AddObjectResponseStatus { _0 => "0", _1 => "1" },
+    DeleteObjectResponseStatus { _0 => "0", _1 => "1" },
+    DownloadResponseStatus { _0 => "0", _1 => "1" }
+);
+
+pub(crate) struct EnvelopeWriter<'a> {
+    writer: Writer<Vec<u8>>,
+    profile: &'a SerializerProfile,
+    sensitive: Option<&'a SensitiveParameters>,
+    preserved: Option<&'a Preserved>,
+    // open elements, with the local names of the elements written into them
+    open: Vec<(Vec<u8>, usize, Vec<Vec<u8>>)>,
+}
+
+impl<'a> EnvelopeWriter<'a> {
+    pub(crate) fn new(profile: &'a SerializerProfile) -> Result<Self, Error> {
+        profile.check()?;
+        let writer = match profile.indent {
+            Some((indent_char, indent_size)) => {
+                Writer::new_with_indent(Vec::new(), indent_char, indent_size)
+            }
+            None => Writer::new(Vec::new()),
+        };
+        let mut writer = Self {
+            writer,
+            profile,
+            sensitive: None,
+            preserved: None,
+            open: Vec::new(),
+        };
+        // written once per document, a batch of envelopes shares it
+        if profile.xml_declaration {
+            writer.event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        }
+        Ok(writer)
+    }
+
+    /// Masks credentials and the values of `sensitive` parameters.
+    pub(crate) fn redacting(mut self, sensitive: &'a SensitiveParameters) -> Self {
+        self.sensitive = Some(sensitive);
+        self
+    }
+
+    /// Puts the unknown elements of a lossless parse back where they were.
+    pub(crate) fn preserving(mut self, preserved: &'a Preserved) -> Self {
+        self.preserved = Some(preserved);
+        self
+    }
+
+    fn secret<'s>(&self, value: &'s str) -> &'s str {
+        if self.sensitive.is_some() {
+            REDACTED
+        } else {
+            value
+        }
+    }
+
+    pub(crate) fn into_inner(self) -> Vec<u8> {
+        self.writer.into_inner()
+    }
+
+    fn event(&mut self, event: Event<'_>) -> Result<(), Error> {
+        self.writer
+            .write_event(event)
+            .map_err(|e| Error::InvalidXml(e.to_string()))
+    }
+
+    fn path(&self) -> ElementPath {
+        self.open
+            .iter()
+            .map(|(name, index, _)| (name.clone(), *index))
+            .collect()
+    }
+
+    // index of the next `name` among the children of the current element
+    fn next_index(&self, name: &[u8]) -> usize {
+        self.open.last().map_or(0, |(_, _, children)| {
+            children
+                .iter()
+                .filter(|child| child.as_slice() == name)
+                .count()
+        })
+    }
+
+    fn start(&mut self, start: BytesStart<'_>) -> Result<(), Error> {
+        let name = start.local_name().into_inner().to_vec();
+        let index = self.next_index(&name);
+        if let Some((_, _, children)) = self.open.last_mut() {
+            children.push(name.clone());
+        }
+        self.open.push((name, index, Vec::new()));
+        self.event(Event::Start(start))
+    }
+
+    fn end(&mut self, name: &str) -> Result<(), Error> {
+        if let Some(preserved) = self.preserved {
+            let path = self.path();
+            for (_, element) in preserved
+                .removed
+                .iter()
+                .filter(|(parent, _)| *parent == path)
+            {
+                element.write(&mut self.writer)?;
+            }
+        }
+        self.open.pop();
+        self.event(Event::End(BytesEnd::new(name)))
+    }
+
+    fn empty(&mut self, start: BytesStart<'_>) -> Result<(), Error> {
+        // an element that had unknown children is written with them
+        let name = start.local_name().into_inner().to_vec();
+        let mut path = self.path();
+        path.push((name.clone(), self.next_index(&name)));
+        let has_unknown = self
+            .preserved
+            .is_some_and(|preserved| preserved.removed.iter().any(|(parent, _)| *parent == path));
+        match self.profile.empty_elements {
+            EmptyElementStyle::SelfClosing if !has_unknown => {
+                if let Some((_, _, children)) = self.open.last_mut() {
+                    children.push(name);
+                }
+                self.event(Event::Empty(start))
+            }
+            _ => {
+                let name = String::from_utf8_lossy(start.name().into_inner()).into_owned();
+                self.start(start)?;
+                // keeps an indenting writer from breaking the line between the tags
+                self.event(Event::Text(BytesText::new("")))?;
+                self.end(&name)
+            }
+        }
+    }
+
+    fn text_element(&mut self, start: BytesStart<'_>, text: &str) -> Result<(), Error> {
+        if text.is_empty() {
+            return self.empty(start);
+        }
+        let name = String::from_utf8_lossy(start.name().into_inner()).into_owned();
+        self.start(start)?;
+        self.event(Event::Text(BytesText::new(text)))?;
+        self.end(&name)
+    }
+
+    fn field(&mut self, name: &str, text: &str) -> Result<(), Error> {
+        self.text_element(BytesStart::new(name), text)
+    }
+
+    fn bool_field(&mut self, name: &str, value: bool) -> Result<(), Error> {
+        self.field(name, self.profile.boolean(value))
+    }
+
+    /// Start tag of a soap encoded array, `item_type` is already qualified.
+    fn array_start(&self, name: &str, item_type: &str, len: usize) -> BytesStart<'static> {
+        let mut start = BytesStart::new(name.to_string());
+        if self.profile.namespaces == NamespacePlacement::FirstUse {
+            start.push_attribute((
+                format!("xmlns:{}", self.profile.prefixes.soapenc).as_str(),
+                NS_SOAPENC,
+            ));
+            if item_type.starts_with(&format!("{}:", self.profile.prefixes.xsd)) {
+                start.push_attribute((
+                    format!("xmlns:{}", self.profile.prefixes.xsd).as_str(),
+                    NS_XSD,
+                ));
+            }
+        }
+        start.push_attribute((
+            self.profile.soapenc("arrayType").as_str(),
+            format!("{item_type}[{len}]").as_str(),
+        ));
+        start
+    }
+
+    fn string_array<'s, I>(&mut self, name: &str, items: I, len: usize) -> Result<(), Error>
+    where
+        I: Iterator<Item = &'s str>,
+    {
+        let start = self.array_start(name, &self.profile.xsd("string"), len);
+        if len == 0 {
+            return self.empty(start);
+        }
+        self.start(start)?;
+        for item in items {
+            self.field("string", item)?;
+        }
+        self.end(name)
+    }
+
+    fn parameter_names(&mut self, name: &str, names: &ParameterNames) -> Result<(), Error> {
+        let names: Vec<String> = names.0.iter().map(|name| name.to_string()).collect();
+        self.string_array(name, names.iter().map(|name| name.as_str()), names.len())
+    }
+
+    fn access_list(&mut self, access_list: &AccessList) -> Result<(), Error> {
+        self.string_array(
+            "AccessList",
+            access_list.0.iter().map(|member| member.as_str()),
+            access_list.0.len(),
+        )
+    }
+
+    fn struct_array<T, F>(&mut self, item: &str, items: &[T], write: F) -> Result<(), Error>
+    where
+        F: Fn(&mut Self, &T) -> Result<(), Error>,
+    {
+        self.named_struct_array("ParameterList", item, items, write)
+    }
+
+    fn named_struct_array<T, F>(
+        &mut self,
+        name: &str,
+        item: &str,
+        items: &[T],
+        write: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(&mut Self, &T) -> Result<(), Error>,
+    {
+        let start = self.array_start(name, &self.profile.cwmp(item), items.len());
+        if items.is_empty() {
+            return self.empty(start);
+        }
+        self.start(start)?;
+        for value in items {
+            self.start(BytesStart::new(item))?;
+            write(self, value)?;
+            self.end(item)?;
+        }
+        self.end(name)
+    }
+
+    fn parameter_value_struct(&mut self, item: &ParameterValueStruct) -> Result<(), Error> {
+        self.field("Name", &item.name)?;
+
+        let mut start = BytesStart::new("Value");
+        if let Some(type_) = &item.value.type_ {
+            let type_ = self.xsi_type(type_);
+            if self.profile.namespaces == NamespacePlacement::FirstUse {
+                start.push_attribute((
+                    format!("xmlns:{}", self.profile.prefixes.xsi).as_str(),
+                    NS_XSI,
+                ));
+                start.push_attribute((
+                    format!("xmlns:{}", self.profile.prefixes.xsd).as_str(),
+                    NS_XSD,
+                ));
+            }
+            start.push_attribute((self.profile.xsi("type").as_str(), type_.as_str()));
+        }
+        let value = match self.sensitive {
+            Some(sensitive) if sensitive.is_sensitive(&item.name) => REDACTED,
+            _ => &item.value.value,
+        };
+        self.text_element(start, value)
+    }
+
+    // types from the xml schema namespace are re-prefixed with the profile's xsd prefix
+    fn xsi_type(&self, type_: &ParameterValueType) -> String {
+        match type_ {
+            ParameterValueType::Qualified {
+                namespace, type_, ..
+            } if namespace == NS_XSD => self.profile.xsd(type_),
+            ParameterValueType::Unqualified(inner) if !inner.contains(':') => {
+                self.profile.xsd(inner)
+            }
+            other => other.to_string(),
+        }
+    }
+
+    fn fault(&mut self, soap_fault: &SoapFault) -> Result<(), Error> {
+        let name = self.profile.soapenv("Fault");
+        self.start(BytesStart::new(name.as_str()))?;
+        self.field("faultcode", &soap_fault.fault_code)?;
+        self.field("faultstring", &soap_fault.fault_string)?;
+        if let Some(fault) = &soap_fault.detail {
+            self.start(BytesStart::new("detail"))?;
+
+            let cwmp_fault = self.profile.cwmp("Fault");
+            self.start(BytesStart::new(cwmp_fault.as_str()))?;
+            self.field("FaultCode", &fault.fault_code.to_string())?;
+            self.field("FaultString", &fault.fault_string)?;
+            for item in &fault.set_parameter_values_faults {
+                self.start(BytesStart::new("SetParameterValuesFault"))?;
+                self.field("ParameterName", &item.parameter_name)?;
+                self.field("FaultCode", &item.fault_code.to_string())?;
+                self.field("FaultString", &item.fault_string)?;
+                self.end("SetParameterValuesFault")?;
+            }
+            self.end(&cwmp_fault)?;
+
+            self.end("detail")?;
+        }
+        self.end(&name)
+    }
+
+    fn header(&mut self, header: &EnvelopeHeader) -> Result<(), Error> {
+        let (must_understand, content) = header.content(self.profile);
+        let name = self.profile.cwmp(header.name());
+        let mut start = BytesStart::new(name.as_str());
+        if let Some(must_understand) = must_understand {
+            start.push_attribute((
+                self.profile.soapenv("mustUnderstand").as_str(),
+                if must_understand { "1" } else { "0" },
+            ));
+        }
+        self.text_element(start, &content)
+    }
+
+    fn rpc(&mut self, method: &RpcMethod) -> Result<(), Error> {
+        let name = self.profile.cwmp(method.name());
+
+        match method {
+            RpcMethod::GetRPCMethods
+            | RpcMethod::FactoryReset
+            | RpcMethod::SetParameterAttributesResponse
+            | RpcMethod::RebootResponse
+            | RpcMethod::FactoryResetResponse => {
+                return self.empty(BytesStart::new(name.as_str()));
+            }
+            _ => {}
+        }
+
+        self.start(BytesStart::new(name.as_str()))?;
+        match method {
+            RpcMethod::GetParameterNames(inner) => {
+                self.field("ParameterPath", &inner.parameter_path.to_string())?;
+                self.bool_field("NextLevel", inner.next_level)?;
+            }
+            RpcMethod::SetParameterValues(inner) => {
+                self.struct_array(
+                    "ParameterValueStruct",
+                    &inner.parameter_list.0,
+                    Self::parameter_value_struct,
+                )?;
+                self.field("ParameterKey", &inner.parameter_key)?;
+            }
+            RpcMethod::GetParameterValues(inner) => {
+                self.parameter_names("ParameterNames", &inner.parameter_names)?;
+            }
+            RpcMethod::SetParameterAttributes(inner) => {
+                self.struct_array(
+                    "SetParameterAttributesStruct",
+                    &inner.parameter_list.0,
+                    |w, item| {
+                        w.field("Name", item.name.as_deref().unwrap_or_default())?;
+                        w.bool_field("NotificationChange", item.notification_change)?;
+                        w.field("Notification", item.notification.code())?;
+                        w.bool_field("AccessListChange", item.access_list_change)?;
+                        w.access_list(&item.access_list)
+                    },
+                )?;
+            }
+            RpcMethod::GetParameterAttributes(inner) => {
+                self.parameter_names("ParameterNames", inner)?;
+            }
+            RpcMethod::AddObject(inner) | RpcMethod::DeleteObject(inner) => {
+                self.field("ObjectName", &inner.object_name)?;
+                self.field("ParameterKey", &inner.parameter_key)?;
+            }
+            RpcMethod::Reboot(inner) => {
+                self.field("CommandKey", &inner.command_key)?;
+            }
+            RpcMethod::Download(inner) => {
+                self.field("CommandKey", &inner.command_key)?;
+                self.field("FileType", &inner.file_type)?;
+                self.field("URL", &inner.url)?;
+                self.field("Username", self.secret(&inner.username))?;
+                self.field("Password", self.secret(&inner.password))?;
+                self.field("FileSize", &inner.file_size.to_string())?;
+                self.field("TargetFileName", &inner.target_file_name)?;
+                self.field("DelaySeconds", &inner.delay_seconds.to_string())?;
+                self.field("SuccessURL", &inner.success_url)?;
+                self.field("FailureURL", &inner.failure_url)?;
+            }
+            RpcMethod::GetRPCMethodsResponse(inner) => {
+                let methods = &inner.method_list.0;
+                self.string_array(
+                    "MethodList",
+                    methods.iter().map(|method| method.as_str()),
+                    methods.len(),
+                )?;
+            }
+            RpcMethod::GetParameterNamesResponse(inner) => {
+                self.struct_array("ParameterInfoStruct", &inner.parameter_list.0, |w, item| {
+                    w.field("Name", &item.name)?;
+                    w.bool_field("Writable", item.writable)
+                })?;
+            }
+            RpcMethod::SetParameterValuesResponse(inner) => {
+                self.field("Status", inner.status.code())?;
+            }
+            RpcMethod::GetParameterValuesResponse(inner) => {
+                self.struct_array(
+                    "ParameterValueStruct",
+                    &inner.parameter_list.0,
+                    Self::parameter_value_struct,
+                )?;
+            }
+            RpcMethod::GetParameterAttributesResponse(inner) => {
+                self.struct_array(
+                    "ParameterAttributeStruct",
+                    &inner.parameter_list.0,
+                    |w, item| {
+                        w.field("Name", &item.name)?;
+                        w.field("Notification", item.notification.code())?;
+                        w.access_list(&item.access_list)
+                    },
+                )?;
+            }
+            RpcMethod::AddObjectResponse(inner) => {
+                self.field("InstanceNumber", &inner.instance_number.to_string())?;
+                self.field("Status", inner.status.code())?;
+            }
+            RpcMethod::DeleteObjectResponse(inner) => {
+                self.field("Status", inner.status.code())?;
+            }
+            RpcMethod::DownloadResponse(inner) => {
+                self.field("Status", inner.status.code())?;
+                self.field("StartTime", &inner.start_time.to_string())?;
+                self.field("CompleteTime", &inner.complete_time.to_string())?;
+            }
+            RpcMethod::Inform(inner) => {
+                let device_id = &inner.device_id;
+                self.start(BytesStart::new("DeviceId"))?;
+                self.field("Manufacturer", device_id.manufacturer())?;
+                self.field("OUI", device_id.oui())?;
+                self.field("ProductClass", device_id.product_class())?;
+                self.field("SerialNumber", device_id.serial_number())?;
+                self.end("DeviceId")?;
+                self.named_struct_array("Event", "EventStruct", &inner.event.0, |w, item| {
+                    w.field("EventCode", &item.event_code.to_string())?;
+                    w.field("CommandKey", &item.command_key)
+                })?;
+                self.field("MaxEnvelopes", &inner.max_envelopes.to_string())?;
+                self.field("CurrentTime", &inner.current_time.to_string())?;
+                self.field("RetryCount", &inner.retry_count.to_string())?;
+                self.struct_array(
+                    "ParameterValueStruct",
+                    &inner.parameter_list.0,
+                    Self::parameter_value_struct,
+                )?;
+            }
+            RpcMethod::InformResponse(inner) => {
+                self.field("MaxEnvelopes", &inner.max_envelopes.to_string())?;
+            }
+            RpcMethod::GetRPCMethods
+            | RpcMethod::FactoryReset
+            | RpcMethod::SetParameterAttributesResponse
+            | RpcMethod::RebootResponse
+            | RpcMethod::FactoryResetResponse => {}
+        }
+        self.end(&name)
+    }
+
+    pub(crate) fn envelope(&mut self, envelope: &Envelope) -> Result<(), Error> {
+        let profile = self.profile;
+
+        let name = profile.soapenv("Envelope");
+        let mut start = BytesStart::new(name.as_str());
+        start.push_attribute((
+            format!("xmlns:{}", profile.prefixes.soapenv).as_str(),
+            NS_SOAPENV,
+        ));
+        if profile.namespaces == NamespacePlacement::Envelope {
+            start.push_attribute((
+                format!("xmlns:{}", profile.prefixes.soapenc).as_str(),
+                NS_SOAPENC,
+            ));
+            start.push_attribute((format!("xmlns:{}", profile.prefixes.xsd).as_str(), NS_XSD));
+            start.push_attribute((format!("xmlns:{}", profile.prefixes.xsi).as_str(), NS_XSI));
+        }
+        start.push_attribute((
+            format!("xmlns:{}", profile.prefixes.cwmp).as_str(),
+            cwmp_namespace(&envelope.cwmp_version),
+        ));
+        // vendor namespaces the unknown elements may be using
+        if let Some(preserved) = self.preserved {
+            let prefixes = &profile.prefixes;
+            let mut declared = vec![
+                &prefixes.soapenv,
+                &prefixes.soapenc,
+                &prefixes.xsi,
+                &prefixes.xsd,
+                &prefixes.cwmp,
+            ];
+            for (prefix, namespace) in preserved.prefixes.0.iter() {
+                if prefix.is_empty() || declared.contains(&prefix) {
+                    continue;
+                }
+                declared.push(prefix);
+                start.push_attribute((format!("xmlns:{prefix}").as_str(), namespace.as_str()));
+            }
+        }
+        self.start(start)?;
+
+        let header = profile.soapenv("Header");
+        if envelope.headers.iter().next().is_none() {
+            self.empty(BytesStart::new(header.as_str()))?;
+        } else {
+            self.start(BytesStart::new(header.as_str()))?;
+            for item in envelope.headers.iter() {
+                if item.introduced_in() > envelope.cwmp_version {
+                    return Err(Error::UnsupportedHeaderVersion(item.name().to_string()));
+                }
+                self.header(item)?;
+            }
+            self.end(&header)?;
+        }
+
+        let body = profile.soapenv("Body");
+        self.start(BytesStart::new(body.as_str()))?;
+        match &envelope.body {
+            EnvelopeBody::Rpc(rpc) => self.rpc(rpc.method())?,
+            EnvelopeBody::Fault(fault) => self.fault(fault)?,
+        }
+        self.end(&body)?;
+
+        self.end(&name)
+    }
+}
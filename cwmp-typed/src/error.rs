@@ -10,10 +10,24 @@ pub enum Error {
     MissingAttribute(String),
     #[error("{0}")]
     ParseIntError(#[from] std::num::ParseIntError),
+    #[error("Invalid xml: {0}")]
+    InvalidXml(String),
     #[error("Invalid value: {0}")]
     InvalidValue(String),
     #[error("Rpc is incompatible with the cwmp version.")]
     RpcVersionMismatch,
     #[error("Rpc does not exist.")]
     UnknownRpc,
+    #[error("'{field}' exceeds the maximum length of {limit} (got {length}).")]
+    MaxLengthExceeded {
+        field: String,
+        limit: usize,
+        length: usize,
+    },
+    #[error("Parse limit exceeded: {limit} is above {max}.")]
+    LimitExceeded { limit: String, max: usize },
+    #[error("Unexpected message: {0}")]
+    UnexpectedMessage(String),
+    #[error("cwmp:ID '{received}' does not match the request '{expected}'.")]
+    IdMismatch { expected: String, received: String },
 }
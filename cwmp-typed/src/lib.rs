@@ -1,3 +1,4 @@
-mod cwmp;
-mod envelope;
-mod error;
+pub mod cwmp;
+pub mod envelope;
+pub mod error;
+pub mod session;
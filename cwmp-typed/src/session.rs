@@ -0,0 +1,509 @@
+//! The ACS side of a CWMP session (TR-069 3.7.1), without any transport.
+//!
+//! Each HTTP POST of the CPE is passed to [`AcsSession::receive`], parsed
+//! (refer [`Envelope::parse_all`]) or `None` for an empty body, and the
+//! [`Reply`] is the body of the HTTP response:
+//!
+//! 1. the CPE opens with an Inform, answered with an InformResponse;
+//! 2. the CPE sends its own requests, each answered through
+//!    [`AcsSession::respond`], until it posts an empty body;
+//! 3. the requests queued with [`AcsSession::request`] are sent one per HTTP
+//!    response, the CPE posting the response to each;
+//! 4. once the queue is empty the ACS answers `204 No Content` and the
+//!    session is closed.
+//!
+//! The InformResponse announces a `MaxEnvelopes` of 1, every HTTP body holds
+//! a single envelope. When the Inform lists `SupportedCWMPVersions`, the
+//! InformResponse picks the newest one up to 1.4 as `UseCWMPVersion`, refer
+//! [`AcsSession::negotiated_version`].
+use crate::cwmp::CwmpVersion;
+use crate::cwmp::device::DeviceId;
+use crate::cwmp::rpc::{Rpc, RpcMethod};
+use crate::cwmp::types::InformResponse;
+use crate::envelope::{Envelope, Fault};
+use crate::error::Error;
+use std::collections::VecDeque;
+
+/// What to send back to the CPE.
+#[derive(Debug)]
+// moved once per HTTP exchange, not worth a box
+#[allow(clippy::large_enum_variant)]
+pub enum Reply {
+    /// The body of the HTTP response.
+    Envelope(Envelope),
+    /// A CPE request, to answer with [`AcsSession::respond`] or
+    /// [`AcsSession::respond_fault`].
+    CpeRequest,
+    /// An empty `204 No Content`, the session is closed.
+    NoContent,
+}
+
+#[derive(Debug)]
+enum State {
+    AwaitingInform,
+    CpeRequests,
+    CpeRequest {
+        id: Option<String>,
+        method: &'static str,
+    },
+    AcsRequest {
+        id: String,
+        method: &'static str,
+    },
+    Closed,
+}
+
+// the newest version whose rules are known, refer `cwmp::validate`
+const NEWEST_VERSION: (u32, u32) = (1, 4);
+
+#[derive(Debug)]
+pub struct AcsSession {
+    state: State,
+    version: CwmpVersion,
+    negotiated_version: Option<(u32, u32)>,
+    device_id: Option<DeviceId>,
+    queue: VecDeque<RpcMethod>,
+    hold_requests: bool,
+    last_id: u64,
+}
+
+fn describe(envelope: &Envelope) -> &'static str {
+    envelope.rpc().map_or("Fault", |rpc| rpc.method().name())
+}
+
+fn is_response(method: &RpcMethod) -> bool {
+    method.name().ends_with("Response")
+}
+
+impl Default for AcsSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AcsSession {
+    pub fn new() -> Self {
+        Self {
+            state: State::AwaitingInform,
+            version: CwmpVersion::_10,
+            negotiated_version: None,
+            device_id: None,
+            queue: VecDeque::new(),
+            hold_requests: false,
+            last_id: 0,
+        }
+    }
+
+    /// The CPE of the session, known once the Inform is received.
+    pub fn device_id(&self) -> Option<&DeviceId> {
+        self.device_id.as_ref()
+    }
+
+    /// The cwmp version of the Inform, used for every envelope sent.
+    pub fn version(&self) -> &CwmpVersion {
+        &self.version
+    }
+
+    /// The `major.minor` version sent as `UseCWMPVersion`, `None` when the
+    /// CPE didn't list its `SupportedCWMPVersions`. Envelopes of the session
+    /// are checked against it by [`Envelope::validate_negotiated`].
+    pub fn negotiated_version(&self) -> Option<(u32, u32)> {
+        self.negotiated_version
+    }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(self.state, State::Closed)
+    }
+
+    /// Asks the CPE to hold back its requests, sent as the `HoldRequests`
+    /// header of every envelope until turned off.
+    pub fn set_hold_requests(&mut self, hold: bool) {
+        self.hold_requests = hold;
+    }
+
+    /// Queues a request for the CPE, sent once the CPE has no more requests.
+    pub fn request(&mut self, method: RpcMethod) -> Result<(), Error> {
+        if self.is_closed() {
+            return Err(Error::UnexpectedMessage(format!(
+                "{} after the session closed.",
+                method.name()
+            )));
+        }
+        self.queue.push_back(method);
+        Ok(())
+    }
+
+    /// Handles the body of an HTTP POST, `None` when it was empty.
+    pub fn receive(&mut self, body: Option<&Envelope>) -> Result<Reply, Error> {
+        let unexpected = |message: String| Err(Error::UnexpectedMessage(message));
+
+        match (&self.state, body) {
+            (State::AwaitingInform, Some(envelope)) => self.inform(envelope),
+            (State::AwaitingInform, None) => {
+                unexpected("empty body before the Inform.".to_string())
+            }
+            (State::CpeRequests, Some(envelope)) => self.cpe_request(envelope),
+            (State::CpeRequests, None) => Ok(self.next_request()),
+            (State::CpeRequest { .. }, _) => {
+                unexpected("message before the CPE request was answered.".to_string())
+            }
+            (State::AcsRequest { .. }, Some(envelope)) => self.acs_response(envelope),
+            (State::AcsRequest { method, .. }, None) => {
+                unexpected(format!("empty body while {method} is outstanding."))
+            }
+            (State::Closed, _) => unexpected("message after the session closed.".to_string()),
+        }
+    }
+
+    /// Answers the pending CPE request, `method` being its response.
+    pub fn respond(&mut self, method: RpcMethod) -> Result<Envelope, Error> {
+        let id = self.answer(method.name())?;
+        Ok(self.outgoing(Envelope::from(Rpc::from((self.version, method))), id))
+    }
+
+    /// Rejects the pending CPE request.
+    pub fn respond_fault(&mut self, fault: Fault) -> Result<Envelope, Error> {
+        let id = self.answer("Fault")?;
+        Ok(self.outgoing(Envelope::from((self.version, fault)), id))
+    }
+
+    fn answer(&mut self, name: &str) -> Result<Option<String>, Error> {
+        let State::CpeRequest { method, .. } = &self.state else {
+            return Err(Error::UnexpectedMessage(format!(
+                "{name} without a pending CPE request."
+            )));
+        };
+        // a fault answers any request
+        if name != "Fault" && name.strip_suffix("Response") != Some(method) {
+            return Err(Error::UnexpectedMessage(format!(
+                "{name} in response to {method}."
+            )));
+        }
+        let State::CpeRequest { id, .. } = std::mem::replace(&mut self.state, State::CpeRequests)
+        else {
+            unreachable!("checked above");
+        };
+        Ok(id)
+    }
+
+    fn outgoing(&self, mut envelope: Envelope, id: Option<String>) -> Envelope {
+        if let Some(id) = id {
+            envelope.set_id(id);
+        }
+        envelope.set_hold_requests(self.hold_requests);
+        envelope
+    }
+
+    fn inform(&mut self, envelope: &Envelope) -> Result<Reply, Error> {
+        let Some(RpcMethod::Inform(inform)) = envelope.rpc().map(Rpc::method) else {
+            return Err(Error::UnexpectedMessage(format!(
+                "{} before the Inform.",
+                describe(envelope)
+            )));
+        };
+        self.version = *envelope.version();
+        self.negotiated_version = envelope
+            .supported_versions()
+            .into_iter()
+            .filter(|version| *version <= NEWEST_VERSION)
+            .max();
+        self.device_id = Some(inform.device_id().clone());
+        self.state = State::CpeRequests;
+
+        let response = Rpc::from((
+            self.version,
+            RpcMethod::InformResponse(InformResponse::new(1)),
+        ));
+        let mut response = Envelope::from(response);
+        if let Some(version) = self.negotiated_version {
+            response.set_negotiated_version(version);
+        }
+        Ok(Reply::Envelope(
+            self.outgoing(response, envelope.id().map(str::to_string)),
+        ))
+    }
+
+    fn cpe_request(&mut self, envelope: &Envelope) -> Result<Reply, Error> {
+        let name = describe(envelope);
+        match envelope.rpc().map(Rpc::method) {
+            Some(method) if !is_response(method) && !matches!(method, RpcMethod::Inform(_)) => {}
+            _ => {
+                return Err(Error::UnexpectedMessage(format!(
+                    "{name} while no request is outstanding."
+                )));
+            }
+        }
+        if self.hold_requests {
+            return Err(Error::UnexpectedMessage(format!(
+                "{name} while the ACS holds requests."
+            )));
+        }
+        self.state = State::CpeRequest {
+            id: envelope.id().map(str::to_string),
+            method: name,
+        };
+        Ok(Reply::CpeRequest)
+    }
+
+    fn acs_response(&mut self, envelope: &Envelope) -> Result<Reply, Error> {
+        let State::AcsRequest { id, method } = &self.state else {
+            unreachable!("only called with a request outstanding");
+        };
+        if envelope.id() != Some(id.as_str()) {
+            return Err(Error::IdMismatch {
+                expected: id.clone(),
+                received: envelope.id().unwrap_or_default().to_string(),
+            });
+        }
+        if let Some(rpc) = envelope.rpc()
+            && rpc.method().name().strip_suffix("Response") != Some(method)
+        {
+            return Err(Error::UnexpectedMessage(format!(
+                "{} in response to {method}.",
+                rpc.method().name()
+            )));
+        }
+        Ok(self.next_request())
+    }
+
+    fn next_request(&mut self) -> Reply {
+        let Some(method) = self.queue.pop_front() else {
+            self.state = State::Closed;
+            return Reply::NoContent;
+        };
+        self.last_id += 1;
+        let id = self.last_id.to_string();
+        self.state = State::AcsRequest {
+            id: id.clone(),
+            method: method.name(),
+        };
+        Reply::Envelope(self.outgoing(Envelope::from(Rpc::from((self.version, method))), Some(id)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cwmp::fault::FaultCode;
+    use crate::cwmp::types::{
+        EventStruct, GetRPCMethodsResponse, Inform, MethodList, ParameterList,
+    };
+
+    fn inform() -> Envelope {
+        let device_id = DeviceId::new(
+            "Example".to_string(),
+            "00D09E".to_string(),
+            "IGD".to_string(),
+            "SN0001".to_string(),
+        )
+        .unwrap();
+        let event = EventStruct::new("1 BOOT".parse().unwrap(), String::new()).unwrap();
+        let inform = Inform::new(
+            device_id,
+            vec![event],
+            1,
+            "2026-01-01T00:00:00Z".parse().unwrap(),
+            0,
+            ParameterList(Vec::new()),
+        );
+        let mut envelope = Envelope::from(Rpc::from((CwmpVersion::_12, RpcMethod::Inform(inform))));
+        envelope.set_id("inform-1");
+        envelope
+    }
+
+    fn cpe(method: RpcMethod, id: &str) -> Envelope {
+        let mut envelope = Envelope::from(Rpc::from((CwmpVersion::_12, method)));
+        envelope.set_id(id);
+        envelope
+    }
+
+    fn sent(reply: Reply) -> Envelope {
+        let Reply::Envelope(envelope) = reply else {
+            panic!("expected an envelope, got {reply:?}");
+        };
+        envelope
+    }
+
+    fn method(envelope: &Envelope) -> &RpcMethod {
+        envelope.rpc().unwrap().method()
+    }
+
+    #[test]
+    fn session() {
+        let mut session = AcsSession::new();
+        session.request(RpcMethod::FactoryReset).unwrap();
+        session
+            .request(RpcMethod::Reboot("r".to_string().try_into().unwrap()))
+            .unwrap();
+
+        let response = sent(session.receive(Some(&inform())).unwrap());
+        assert!(
+            matches!(method(&response), RpcMethod::InformResponse(inner) if inner.max_envelopes() == 1)
+        );
+        assert_eq!(response.id(), Some("inform-1"));
+        assert_eq!(response.version(), &CwmpVersion::_12);
+        assert_eq!(session.device_id().unwrap().serial_number(), "SN0001");
+
+        let request = cpe(RpcMethod::GetRPCMethods, "cpe-1");
+        assert!(matches!(
+            session.receive(Some(&request)).unwrap(),
+            Reply::CpeRequest
+        ));
+        assert!(session.receive(None).is_err());
+        let response = session
+            .respond(RpcMethod::GetRPCMethodsResponse(GetRPCMethodsResponse {
+                method_list: MethodList(vec!["Inform".to_string()]),
+            }))
+            .unwrap();
+        assert_eq!(response.id(), Some("cpe-1"));
+        assert!(session.respond(RpcMethod::FactoryResetResponse).is_err());
+
+        let request = sent(session.receive(None).unwrap());
+        assert!(matches!(method(&request), RpcMethod::FactoryReset));
+        let id = request.id().unwrap().to_string();
+
+        let request = sent(
+            session
+                .receive(Some(&cpe(RpcMethod::FactoryResetResponse, &id)))
+                .unwrap(),
+        );
+        assert!(matches!(method(&request), RpcMethod::Reboot(_)));
+        assert_ne!(request.id(), Some(id.as_str()));
+
+        let mut fault = Envelope::from((CwmpVersion::_12, Fault::new(FaultCode::RequestDenied)));
+        fault.set_id(request.id().unwrap());
+        assert!(matches!(
+            session.receive(Some(&fault)).unwrap(),
+            Reply::NoContent
+        ));
+        assert!(session.is_closed());
+        assert!(session.receive(None).is_err());
+        assert!(session.request(RpcMethod::FactoryReset).is_err());
+    }
+
+    #[test]
+    fn order() {
+        let mut session = AcsSession::new();
+        assert!(session.receive(None).is_err());
+        assert!(
+            session
+                .receive(Some(&cpe(RpcMethod::GetRPCMethods, "1")))
+                .is_err()
+        );
+        sent(session.receive(Some(&inform())).unwrap());
+
+        // a second Inform, or a response without a request
+        assert!(session.receive(Some(&inform())).is_err());
+        assert!(
+            session
+                .receive(Some(&cpe(RpcMethod::RebootResponse, "1")))
+                .is_err()
+        );
+
+        // nothing queued: the empty post ends the session
+        assert!(matches!(session.receive(None).unwrap(), Reply::NoContent));
+        assert!(session.is_closed());
+    }
+
+    #[test]
+    fn hold_requests() {
+        let mut session = AcsSession::new();
+        session.set_hold_requests(true);
+        let response = sent(session.receive(Some(&inform())).unwrap());
+        assert!(response.hold_requests());
+
+        let Err(Error::UnexpectedMessage(message)) =
+            session.receive(Some(&cpe(RpcMethod::GetRPCMethods, "1")))
+        else {
+            panic!();
+        };
+        assert_eq!(message, "GetRPCMethods while the ACS holds requests.");
+
+        session.set_hold_requests(false);
+        session.request(RpcMethod::FactoryReset).unwrap();
+        let request = sent(session.receive(None).unwrap());
+        assert!(!request.hold_requests());
+    }
+
+    #[test]
+    fn correlation() {
+        let mut session = AcsSession::new();
+        session.request(RpcMethod::FactoryReset).unwrap();
+        sent(session.receive(Some(&inform())).unwrap());
+        let request = sent(session.receive(None).unwrap());
+        let id = request.id().unwrap().to_string();
+
+        let Err(Error::IdMismatch { expected, received }) =
+            session.receive(Some(&cpe(RpcMethod::FactoryResetResponse, "other")))
+        else {
+            panic!();
+        };
+        assert_eq!(
+            (expected.as_str(), received.as_str()),
+            (id.as_str(), "other")
+        );
+
+        // the right id, the wrong response
+        assert!(
+            session
+                .receive(Some(&cpe(RpcMethod::RebootResponse, &id)))
+                .is_err()
+        );
+        assert!(matches!(
+            session
+                .receive(Some(&cpe(RpcMethod::FactoryResetResponse, &id)))
+                .unwrap(),
+            Reply::NoContent
+        ));
+    }
+
+    #[test]
+    fn cpe_request_response() {
+        let mut session = AcsSession::new();
+        sent(session.receive(Some(&inform())).unwrap());
+        session
+            .receive(Some(&cpe(RpcMethod::GetRPCMethods, "cpe-1")))
+            .unwrap();
+
+        // neither a request nor the response to another request answers it
+        let Err(Error::UnexpectedMessage(message)) =
+            session.respond(RpcMethod::Reboot("r".to_string().try_into().unwrap()))
+        else {
+            panic!();
+        };
+        assert_eq!(message, "Reboot in response to GetRPCMethods.");
+        assert!(session.respond(RpcMethod::FactoryResetResponse).is_err());
+
+        // still pending
+        let response = session
+            .respond(RpcMethod::GetRPCMethodsResponse(GetRPCMethodsResponse {
+                method_list: MethodList(Vec::new()),
+            }))
+            .unwrap();
+        assert_eq!(response.id(), Some("cpe-1"));
+
+        session
+            .receive(Some(&cpe(RpcMethod::GetRPCMethods, "cpe-2")))
+            .unwrap();
+        let response = session
+            .respond_fault(Fault::new(FaultCode::RequestDenied))
+            .unwrap();
+        assert_eq!(response.id(), Some("cpe-2"));
+    }
+
+    #[test]
+    fn negotiated_version() {
+        let mut session = AcsSession::new();
+        let response = sent(session.receive(Some(&inform())).unwrap());
+        assert_eq!(session.negotiated_version(), None);
+        assert_eq!(response.negotiated_version(), None);
+
+        let mut session = AcsSession::new();
+        let mut envelope = inform();
+        envelope.set_supported_versions(&[(1, 0), (1, 3), (1, 4), (1, 5)]);
+        let response = sent(session.receive(Some(&envelope)).unwrap());
+        assert_eq!(session.negotiated_version(), Some((1, 4)));
+        assert_eq!(response.negotiated_version(), Some((1, 4)));
+        assert!(response.validate_negotiated((1, 4)).is_ok());
+    }
+}
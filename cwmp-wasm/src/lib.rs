@@ -0,0 +1,149 @@
+//! WebAssembly bindings for a static envelope inspector page, built with
+//! `wasm-pack build --target web cwmp-wasm`.
+//!
+//! ```js
+//! import init, { parse, diagnostics, prettyPrint } from "./pkg/cwmp_wasm.js";
+//!
+//! await init();
+//! const envelope = JSON.parse(parse(pasted));
+//! ```
+//!
+//! Structured results are returned as JSON text, envelopes in the serde
+//! representation of cwmp-typed. Envelopes are parsed losslessly, so captures
+//! with vendor `X_` elements are accepted.
+use cwmp_typed::cwmp::CwmpVersion;
+use cwmp_typed::cwmp::fault::FaultCode;
+use cwmp_typed::cwmp::redact::SensitiveParameters;
+use cwmp_typed::cwmp::rpc::Rpc;
+use cwmp_typed::envelope::{Envelope, Fault, SerializerProfile};
+use cwmp_typed::error::Error;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+#[derive(Serialize)]
+struct EnvelopeView<'a> {
+    version: &'a CwmpVersion,
+    id: Option<&'a str>,
+    rpc: Option<&'a Rpc>,
+    fault: Option<&'a Fault>,
+}
+
+#[derive(Serialize)]
+struct Diagnostic {
+    /// The element at fault, `Envelope` when the xml can't be parsed.
+    field: String,
+    message: String,
+    /// The cwmp fault a receiver would answer with.
+    fault_code: u32,
+}
+
+impl Diagnostic {
+    fn new(field: String, error: &Error) -> Self {
+        Self {
+            field,
+            message: error.to_string(),
+            fault_code: FaultCode::from(error).code(),
+        }
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("the views serialize to json")
+}
+
+fn parse_json(xml: &str) -> Result<String, Error> {
+    let envelope = Envelope::parse_lossless(xml.as_bytes())?;
+    Ok(to_json(&EnvelopeView {
+        version: envelope.version(),
+        id: envelope.id(),
+        rpc: envelope.rpc(),
+        fault: envelope.fault(),
+    }))
+}
+
+fn check(xml: &str) -> Vec<Diagnostic> {
+    let envelope = match Envelope::parse_lossless(xml.as_bytes()) {
+        Ok(envelope) => envelope,
+        Err(e) => return vec![Diagnostic::new("Envelope".to_string(), &e)],
+    };
+    // a single capture has no session to take the negotiated version from
+    match envelope.validate() {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors
+            .into_iter()
+            .map(|e| Diagnostic::new(e.field, &e.error))
+            .collect(),
+    }
+}
+
+fn pretty(xml: &str, redact: bool) -> Result<String, Error> {
+    let envelope = Envelope::parse_lossless(xml.as_bytes())?;
+    let profile = SerializerProfile::pretty();
+    let xml = match redact {
+        true => envelope.to_redacted_xml(&profile, &SensitiveParameters::default())?,
+        false => envelope.to_xml(&profile)?,
+    };
+    Ok(String::from_utf8_lossy(&xml).into_owned())
+}
+
+/// The envelope as JSON: its `version`, `id`, and the `rpc` or `fault`.
+#[wasm_bindgen]
+pub fn parse(xml: &str) -> Result<String, JsError> {
+    parse_json(xml).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// A JSON array of `{field, message, fault_code}`, empty for a valid
+/// envelope.
+#[wasm_bindgen]
+pub fn diagnostics(xml: &str) -> String {
+    to_json(&check(xml))
+}
+
+/// The envelope re-indented, with passwords and keys masked when `redact`.
+#[wasm_bindgen(js_name = prettyPrint)]
+pub fn pretty_print(xml: &str, redact: bool) -> Result<String, JsError> {
+    pretty(xml, redact).map_err(|e| JsError::new(&e.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::Value;
+
+    const SET_PARAMETER_VALUES: &str = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:soapenc="http://schemas.xmlsoap.org/soap/encoding/" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:cwmp="urn:dslforum-org:cwmp-1-0"><soap:Header><cwmp:ID soap:mustUnderstand="1">42</cwmp:ID></soap:Header><soap:Body><cwmp:SetParameterValues><ParameterList soapenc:arrayType="cwmp:ParameterValueStruct[1]"><ParameterValueStruct><Name>Device.WiFi.AccessPoint.1.Security.KeyPassphrase</Name><Value xsi:type="xsd:string">SuperSecurePass123!</Value></ParameterValueStruct></ParameterList><ParameterKey>key</ParameterKey></cwmp:SetParameterValues></soap:Body></soap:Envelope>"#;
+
+    #[test]
+    fn inspect() {
+        let json: Value = serde_json::from_str(&parse_json(SET_PARAMETER_VALUES).unwrap()).unwrap();
+        assert_eq!(json["version"], "1.0");
+        assert_eq!(json["id"], "42");
+        assert_eq!(json["rpc"]["method"], "SetParameterValues");
+        assert_eq!(json["rpc"]["params"]["parameter_key"], "key");
+        assert!(json["fault"].is_null());
+
+        let xml = pretty(SET_PARAMETER_VALUES, true).unwrap();
+        assert!(xml.contains("\n  <soap:Body>"));
+        assert!(!xml.contains("SuperSecurePass123!"));
+        assert!(
+            pretty(SET_PARAMETER_VALUES, false)
+                .unwrap()
+                .contains("SuperSecurePass123!")
+        );
+    }
+
+    #[test]
+    fn diagnose() {
+        assert!(check(SET_PARAMETER_VALUES).is_empty());
+
+        let diagnostics = check("<soap:Envelope");
+        assert_eq!(diagnostics[0].field, "Envelope");
+
+        // alias based AddObject needs a negotiated cwmp 1.4
+        let diagnostics = check(
+            r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:cwmp="urn:dslforum-org:cwmp-1-2"><soap:Header><cwmp:ID soap:mustUnderstand="1">1</cwmp:ID></soap:Header><soap:Body><cwmp:AddObject><ObjectName>Device.IP.Interface.[wan].</ObjectName><ParameterKey></ParameterKey></cwmp:AddObject></soap:Body></soap:Envelope>"#,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].field, "AddObject.ObjectName");
+        assert_eq!(diagnostics[0].fault_code, 9000);
+    }
+}
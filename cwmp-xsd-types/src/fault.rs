@@ -0,0 +1,22 @@
+use xsd_parser_types::quick_xml::{DeserializeBytes, DeserializeHelper, Error, ErrorKind};
+
+/// The cwmp `FaultCodeType`, a union of the standard codes and the vendor
+/// ranges in the schema, read as the number.
+#[derive(Debug)]
+pub struct FaultCode(pub u32);
+
+impl DeserializeBytes for FaultCode {
+    fn deserialize_bytes(_helper: &mut DeserializeHelper, bytes: &[u8]) -> Result<Self, Error> {
+        std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|code| code.trim().parse().ok())
+            .map(FaultCode)
+            .ok_or_else(|| Error {
+                kind: ErrorKind::UnknownOrInvalidValue(
+                    "Expected the fault code to be an unsignedInt.".into(),
+                ),
+                elements: None,
+                position: None,
+            })
+    }
+}
@@ -1,2 +1,4 @@
+pub mod fault;
 pub mod xsi;
+pub use fault::FaultCode;
 pub use xsi::XsiType;